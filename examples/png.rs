@@ -2,7 +2,7 @@ use vulx::{
     geometry::{Mvp, PathGeometry},
     target::{CommandBuffer, RenderTargetBuilder},
     Color, ImageBuilder, InstanceBuilder, Pipeline, PipelineBuilder, PolygonMode, RenderPass,
-    RenderTarget, ShaderKind, Spirv, Stage, StageBuilder, SubPass, Vec3, Vec4,
+    RenderTarget, SampleCount, ShaderKind, Spirv, Stage, StageBuilder, SubPass, Vec3, Vec4,
 };
 
 const WIDTH: u32 = 1280;
@@ -26,12 +26,21 @@ fn main() {
             .build(&instance, physical_device, &device);
     let image_view = image.create_image_view(&device).unwrap();
 
+    // Rendered with as many samples as the device supports, then resolved into `image`.
+    let samples = SampleCount::Max;
+    let multisampled = ImageBuilder::new()
+        .width(WIDTH)
+        .height(HEIGHT)
+        .samples(samples)
+        .build(&instance, physical_device, &device);
+    let multisampled_view = multisampled.create_image_view(&device).unwrap();
+
     let subpasses = vec![SubPass::new()];
 
-    let render_pass = RenderPass::new(&device, &subpasses);
+    let render_pass = RenderPass::multisampled(&device, &subpasses, samples);
 
-    let frame_buffer = image_view
-        .create_frame_buffer(&device, &render_pass, WIDTH, HEIGHT)
+    let frame_buffer = multisampled_view
+        .create_resolving_frame_buffer(&device, &render_pass, &image_view, WIDTH, HEIGHT)
         .unwrap();
 
     let fragment_shader = device
@@ -143,4 +152,6 @@ fn main() {
     device.destroy(&fragment_shader);
     device.destroy(&vertex_shader);
     device.destroy(&image_view);
+    device.destroy(&multisampled_view);
+    device.destroy(&multisampled);
 }
@@ -0,0 +1,75 @@
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+layout(location = 0) in vec2 fragmentPosition;
+// The gradients of the frame. Each one starts with a header (kind, spread, stop count),
+// then its geometry, then a color and an offset per stop.
+layout(std430, set = 0, binding = 1) readonly buffer Gradients {
+    vec4 data[];
+} gradients;
+layout(push_constant) uniform Draw {
+    mat4 transform;
+    uint gradient;
+} draw;
+layout(location = 0) out vec4 outColor;
+
+const float TAU = 6.28318530718;
+
+void main() {
+    uint base = draw.gradient;
+    vec4 header = gradients.data[base];
+    vec4 geometry = gradients.data[base + 1u];
+    uint kind = uint(header.x);
+    uint spread = uint(header.y);
+    uint count = uint(header.z);
+
+    float t = 0.0;
+    if (kind == 0u) {
+        // Linear: projection onto the line from start to end.
+        vec2 direction = geometry.zw - geometry.xy;
+        float length2 = dot(direction, direction);
+        if (length2 > 0.0) {
+            t = dot(fragmentPosition - geometry.xy, direction) / length2;
+        }
+    } else if (kind == 1u) {
+        // Radial: distance from the center relative to the radius.
+        if (geometry.z > 0.0) {
+            t = length(fragmentPosition - geometry.xy) / geometry.z;
+        }
+    } else {
+        // Sweep: angle around the center from the start angle to the end angle.
+        vec2 d = fragmentPosition - geometry.xy;
+        float span = geometry.w - geometry.z;
+        if (span != 0.0) {
+            t = mod(atan(d.y, d.x) - geometry.z, TAU) / span;
+        }
+    }
+
+    if (spread == 1u) {
+        t = fract(t);
+    } else if (spread == 2u) {
+        t = 1.0 - abs(mod(t, 2.0) - 1.0);
+    } else {
+        t = clamp(t, 0.0, 1.0);
+    }
+
+    if (count == 0u) {
+        outColor = vec4(0.0);
+        return;
+    }
+    vec4 color = gradients.data[base + 2u];
+    float offset = gradients.data[base + 3u].x;
+    for (uint i = 1u; i < count; i++) {
+        vec4 nextColor = gradients.data[base + 2u + 2u * i];
+        float nextOffset = gradients.data[base + 3u + 2u * i].x;
+        if (t <= nextOffset) {
+            if (t > offset) {
+                color = mix(color, nextColor, (t - offset) / (nextOffset - offset));
+            }
+            break;
+        }
+        color = nextColor;
+        offset = nextOffset;
+    }
+    outColor = color;
+}
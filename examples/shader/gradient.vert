@@ -0,0 +1,19 @@
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+layout(location = 0) in vec4 inPos;
+layout(set = 0, binding = 0) uniform UBO {
+    mat4 model;
+    mat4 view;
+    mat4 projection;
+} ubo;
+layout(push_constant) uniform Draw {
+    mat4 transform;
+} draw;
+// The position before the draw transform, in which the gradient is defined.
+layout(location = 0) out vec2 fragmentPosition;
+
+void main() {
+    gl_Position = ubo.projection * ubo.view * ubo.model * draw.transform * vec4(inPos);
+    fragmentPosition = inPos.xy;
+}
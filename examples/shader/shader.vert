@@ -3,14 +3,24 @@
 
 layout(location = 0) in vec4 inPos;
 layout(location = 1) in vec4 inColor;
+// The columns of the instance transform.
+layout(location = 2) in vec4 instanceTransform0;
+layout(location = 3) in vec4 instanceTransform1;
+layout(location = 4) in vec4 instanceTransform2;
+layout(location = 5) in vec4 instanceTransform3;
+layout(location = 6) in vec4 instanceColor;
 layout(set = 0, binding = 0) uniform UBO {
     mat4 model;
     mat4 view;
     mat4 projection;
 } ubo;
+layout(push_constant) uniform Draw {
+    mat4 transform;
+} draw;
 layout(location = 0) out vec4 fragmentColor;
 
 void main() {
-    gl_Position = ubo.projection * ubo.view * ubo.model * vec4(inPos);
-    fragmentColor = inColor;
-}
\ No newline at end of file
+    mat4 instanceTransform = mat4(instanceTransform0, instanceTransform1, instanceTransform2, instanceTransform3);
+    gl_Position = ubo.projection * ubo.view * ubo.model * draw.transform * instanceTransform * vec4(inPos);
+    fragmentColor = inColor * instanceColor;
+}
@@ -0,0 +1,17 @@
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+layout(location = 0) in vec2 fragmentUv;
+layout(location = 1) in vec4 fragmentColor;
+layout(set = 0, binding = 1) uniform texture2D atlas;
+layout(set = 0, binding = 2) uniform sampler atlasSampler;
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    // The atlas stores the signed distance to the outline, with the outline at 0.5.
+    float distance = texture(sampler2D(atlas, atlasSampler), fragmentUv).r;
+    // Smooth over about one pixel on screen, whatever the scale of the glyph.
+    float width = max(fwidth(distance), 0.0001) * 0.5;
+    float alpha = smoothstep(0.5 - width, 0.5 + width, distance);
+    outColor = vec4(fragmentColor.rgb, fragmentColor.a * alpha);
+}
@@ -0,0 +1,22 @@
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+layout(location = 0) in vec4 inPos;
+layout(location = 1) in vec2 inUv;
+layout(location = 2) in vec4 inColor;
+layout(set = 0, binding = 0) uniform UBO {
+    mat4 model;
+    mat4 view;
+    mat4 projection;
+} ubo;
+layout(push_constant) uniform Draw {
+    mat4 transform;
+} draw;
+layout(location = 0) out vec2 fragmentUv;
+layout(location = 1) out vec4 fragmentColor;
+
+void main() {
+    gl_Position = ubo.projection * ubo.view * ubo.model * draw.transform * vec4(inPos);
+    fragmentUv = inUv;
+    fragmentColor = inColor;
+}
@@ -0,0 +1,12 @@
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+layout(location = 0) in vec2 fragmentUv;
+layout(location = 1) in vec4 fragmentColor;
+layout(set = 0, binding = 1) uniform texture2D image;
+layout(set = 0, binding = 2) uniform sampler imageSampler;
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    outColor = texture(sampler2D(image, imageSampler), fragmentUv) * fragmentColor;
+}
@@ -0,0 +1,274 @@
+//! Brushes that paint the fill or stroke of a path with a color or a gradient.
+
+mod pipeline;
+
+pub(crate) use pipeline::GradientPipeline;
+
+use crate::{Color, Vec2, Vec4};
+
+/// Specifies how a gradient continues outside of its range
+///
+/// # Value Meaning
+/// * `Pad` - The colors of the first and last stop extend to infinity.
+/// * `Repeat` - The gradient starts over after its end.
+/// * `Reflect` - The gradient runs back and forth, mirrored at every end.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SpreadMode {
+    #[default]
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+/// # GradientStop
+/// The color of a gradient at `offset`, from 0.0 at its start to 1.0 at its end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Vec4<f32>,
+}
+
+impl GradientStop {
+    pub fn new(offset: f32, color: Vec4<f32>) -> Self {
+        Self { offset, color }
+    }
+}
+
+/// The stops and spread mode shared by all gradients.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Stops {
+    stops: Vec<GradientStop>,
+    spread: SpreadMode,
+}
+
+impl Stops {
+    /// Keeps the stops sorted by offset. Stops at the same offset keep the order they were added in,
+    /// which gives a hard edge.
+    fn add(&mut self, offset: f32, color: Vec4<f32>) {
+        let offset = offset.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|stop| stop.offset <= offset);
+        self.stops.insert(index, GradientStop::new(offset, color));
+    }
+}
+
+/// # LinearGradient
+/// Colors that change along the line from `start` to `end` and stay constant across it.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{brush::{Brush, LinearGradient, SpreadMode}, Vec2, Vec4};
+/// let brush: Brush = LinearGradient::new(Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0))
+///     .stop(0.0, Vec4::new(1.0, 0.0, 0.0, 1.0))
+///     .stop(1.0, Vec4::new(0.0, 0.0, 1.0, 1.0))
+///     .spread(SpreadMode::Reflect)
+///     .into();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearGradient {
+    pub start: Vec2<f32>,
+    pub end: Vec2<f32>,
+    stops: Stops,
+}
+
+impl LinearGradient {
+    pub fn new(start: Vec2<f32>, end: Vec2<f32>) -> Self {
+        Self {
+            start,
+            end,
+            stops: Stops::default(),
+        }
+    }
+
+    /// Adds a color stop. `offset` is clamped to `0.0..=1.0`.
+    pub fn stop(mut self, offset: f32, color: impl Into<Vec4<f32>>) -> Self {
+        self.stops.add(offset, color.into());
+        self
+    }
+
+    pub fn spread(mut self, spread: SpreadMode) -> Self {
+        self.stops.spread = spread;
+        self
+    }
+
+    /// The stops sorted by offset.
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops.stops
+    }
+}
+
+/// # RadialGradient
+/// Colors that change with the distance from `center`, reaching the end at `radius`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadialGradient {
+    pub center: Vec2<f32>,
+    pub radius: f32,
+    stops: Stops,
+}
+
+impl RadialGradient {
+    pub fn new(center: Vec2<f32>, radius: f32) -> Self {
+        Self {
+            center,
+            radius,
+            stops: Stops::default(),
+        }
+    }
+
+    /// Adds a color stop. `offset` is clamped to `0.0..=1.0`.
+    pub fn stop(mut self, offset: f32, color: impl Into<Vec4<f32>>) -> Self {
+        self.stops.add(offset, color.into());
+        self
+    }
+
+    pub fn spread(mut self, spread: SpreadMode) -> Self {
+        self.stops.spread = spread;
+        self
+    }
+
+    /// The stops sorted by offset.
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops.stops
+    }
+}
+
+/// # SweepGradient
+/// Colors that change with the angle around `center`, also known as a conic gradient.
+///
+/// Angles are in radians, measured from the positive x axis towards the positive y axis,
+/// which is clockwise on screen. The gradient runs from `start_angle` to `end_angle`, a full turn by default.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepGradient {
+    pub center: Vec2<f32>,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    stops: Stops,
+}
+
+impl SweepGradient {
+    pub fn new(center: Vec2<f32>, start_angle: f32) -> Self {
+        Self {
+            center,
+            start_angle,
+            end_angle: start_angle + std::f32::consts::TAU,
+            stops: Stops::default(),
+        }
+    }
+
+    pub fn end_angle(mut self, end_angle: f32) -> Self {
+        self.end_angle = end_angle;
+        self
+    }
+
+    /// Adds a color stop. `offset` is clamped to `0.0..=1.0`.
+    pub fn stop(mut self, offset: f32, color: impl Into<Vec4<f32>>) -> Self {
+        self.stops.add(offset, color.into());
+        self
+    }
+
+    pub fn spread(mut self, spread: SpreadMode) -> Self {
+        self.stops.spread = spread;
+        self
+    }
+
+    /// The stops sorted by offset.
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops.stops
+    }
+}
+
+/// Specifies how the fill or stroke of a path is painted
+///
+/// Gradients are defined in the coordinates of the path, before the transform of the render target,
+/// and are evaluated for every pixel in the fragment shader. The colors of the path vertices are ignored.
+///
+/// # Value Meaning
+/// * `Solid` - One color.
+/// * `LinearGradient` - See [`LinearGradient`].
+/// * `RadialGradient` - See [`RadialGradient`].
+/// * `SweepGradient` - See [`SweepGradient`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Brush {
+    Solid(Vec4<f32>),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    SweepGradient(SweepGradient),
+}
+
+impl Brush {
+    /// Packs the brush for the gradient shader: a header with the kind, spread mode and stop count,
+    /// the geometry, then the color and offset of each stop.
+    pub(crate) fn encode(&self) -> Vec<Vec4<f32>> {
+        let (kind, geometry, stops) = match self {
+            Brush::Solid(color) => {
+                let solid = Stops {
+                    stops: vec![GradientStop::new(0.0, *color)],
+                    spread: SpreadMode::Pad,
+                };
+                return encode(0.0, Vec4::zeros(), &solid);
+            }
+            Brush::LinearGradient(g) => (
+                0.0,
+                Vec4::new(g.start.x, g.start.y, g.end.x, g.end.y),
+                &g.stops,
+            ),
+            Brush::RadialGradient(g) => (
+                1.0,
+                Vec4::new(g.center.x, g.center.y, g.radius, 0.0),
+                &g.stops,
+            ),
+            Brush::SweepGradient(g) => (
+                2.0,
+                Vec4::new(g.center.x, g.center.y, g.start_angle, g.end_angle),
+                &g.stops,
+            ),
+        };
+        encode(kind, geometry, stops)
+    }
+}
+
+fn encode(kind: f32, geometry: Vec4<f32>, stops: &Stops) -> Vec<Vec4<f32>> {
+    let spread = match stops.spread {
+        SpreadMode::Pad => 0.0,
+        SpreadMode::Repeat => 1.0,
+        SpreadMode::Reflect => 2.0,
+    };
+    let mut data = vec![
+        Vec4::new(kind, spread, stops.stops.len() as f32, 0.0),
+        geometry,
+    ];
+    for stop in &stops.stops {
+        data.push(stop.color);
+        data.push(Vec4::new(stop.offset, 0.0, 0.0, 0.0));
+    }
+    data
+}
+
+impl From<Vec4<f32>> for Brush {
+    fn from(color: Vec4<f32>) -> Self {
+        Brush::Solid(color)
+    }
+}
+
+impl From<Color> for Brush {
+    fn from(color: Color) -> Self {
+        Brush::Solid(color.into())
+    }
+}
+
+impl From<LinearGradient> for Brush {
+    fn from(gradient: LinearGradient) -> Self {
+        Brush::LinearGradient(gradient)
+    }
+}
+
+impl From<RadialGradient> for Brush {
+    fn from(gradient: RadialGradient) -> Self {
+        Brush::RadialGradient(gradient)
+    }
+}
+
+impl From<SweepGradient> for Brush {
+    fn from(gradient: SweepGradient) -> Self {
+        Brush::SweepGradient(gradient)
+    }
+}
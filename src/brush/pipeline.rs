@@ -0,0 +1,232 @@
+use ash::vk::{
+    DescriptorBufferInfo, DescriptorPool, DescriptorPoolCreateInfo, DescriptorPoolSize,
+    DescriptorSet, DescriptorSetAllocateInfo, DescriptorSetLayout, DescriptorSetLayoutBinding,
+    DescriptorSetLayoutCreateInfo, DescriptorType, PipelineLayout, PipelineLayoutCreateInfo,
+    PolygonMode, PrimitiveTopology, PushConstantRange, ShaderStageFlags, WriteDescriptorSet,
+};
+
+use crate::{
+    geometry::{as_bytes, Buffer, BufferUsage, Mvp, VertexData},
+    target::PipelineBinding,
+    BlendMode, Destroy, Instance, LogicalDevice, Mat4, PhysicalDevice, Pipeline, PipelineState,
+    RenderPass, Shader, ShaderKind, Spirv, Stage, Vec4, VertexLayout, VlError, VlResult,
+};
+
+const GRADIENT_VERTEX_SHADER: &[u8] = include_bytes!("../spv/gradient.vert.spv");
+const GRADIENT_FRAGMENT_SHADER: &[u8] = include_bytes!("../spv/gradient.frag.spv");
+
+/// Paints path vertices with the brushes of a frame, read from a storage buffer.
+///
+/// The vertices are the ones of the default pipeline; their colors are ignored. The offset of the
+/// brush in the storage buffer is passed to the fragment shader as push constant after the transform.
+/// The output is blended over the image by its alpha.
+pub(crate) struct GradientPipeline {
+    pipeline: Pipeline,
+    layout: PipelineLayout,
+    descriptor_layout: DescriptorSetLayout,
+    descriptor_pool: DescriptorPool,
+    descriptor_set: DescriptorSet,
+    shaders: [Shader; 2],
+    /// The brushes of the last recorded frame.
+    buffer: Option<Buffer>,
+}
+
+impl GradientPipeline {
+    pub(crate) fn new(
+        device: &LogicalDevice,
+        render_pass: &RenderPass,
+        stage: &Stage,
+    ) -> VlResult<Self> {
+        let bindings = [
+            (0, DescriptorType::UNIFORM_BUFFER, ShaderStageFlags::VERTEX),
+            (
+                1,
+                DescriptorType::STORAGE_BUFFER,
+                ShaderStageFlags::FRAGMENT,
+            ),
+        ]
+        .map(|(binding, ty, stage)| {
+            DescriptorSetLayoutBinding::builder()
+                .binding(binding)
+                .descriptor_type(ty)
+                .descriptor_count(1)
+                .stage_flags(stage)
+                .build()
+        });
+        let create_info = DescriptorSetLayoutCreateInfo::builder()
+            .bindings(&bindings)
+            .build();
+        let descriptor_layout = unsafe {
+            device
+                .inner
+                .create_descriptor_set_layout(&create_info, None)
+        }?;
+
+        let pool_sizes = bindings.map(|binding| {
+            DescriptorPoolSize::builder()
+                .ty(binding.descriptor_type)
+                .descriptor_count(1)
+                .build()
+        });
+        let create_info = DescriptorPoolCreateInfo::builder()
+            .pool_sizes(&pool_sizes)
+            .max_sets(1)
+            .build();
+        let descriptor_pool = unsafe { device.inner.create_descriptor_pool(&create_info, None) }?;
+
+        let alloc_info = DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&[descriptor_layout])
+            .build();
+        let descriptor_set = unsafe { device.inner.allocate_descriptor_sets(&alloc_info) }?[0];
+        let buffer_info = [DescriptorBufferInfo::builder()
+            .buffer(stage.buffer.buffer)
+            .offset(0)
+            .range(std::mem::size_of::<Mvp>() as u64)
+            .build()];
+        let write = WriteDescriptorSet::builder()
+            .dst_set(descriptor_set)
+            .dst_binding(0)
+            .descriptor_type(DescriptorType::UNIFORM_BUFFER)
+            .buffer_info(&buffer_info)
+            .build();
+        unsafe {
+            device.inner.update_descriptor_sets(&[write], &[]);
+        }
+
+        // The per-draw transform, as for the default pipeline, then the offset of the brush.
+        let transform_size = std::mem::size_of::<Mat4<f32>>() as u32;
+        let push_constant_ranges = [
+            PushConstantRange::builder()
+                .stage_flags(ShaderStageFlags::VERTEX)
+                .offset(0)
+                .size(transform_size)
+                .build(),
+            PushConstantRange::builder()
+                .stage_flags(ShaderStageFlags::FRAGMENT)
+                .offset(transform_size)
+                .size(std::mem::size_of::<u32>() as u32)
+                .build(),
+        ];
+        let layout_create_info = PipelineLayoutCreateInfo::builder()
+            .set_layouts(&[descriptor_layout])
+            .push_constant_ranges(&push_constant_ranges)
+            .build();
+        let layout = unsafe {
+            device
+                .inner
+                .create_pipeline_layout(&layout_create_info, None)
+        }?;
+
+        let shaders = [
+            device.create_shader_module(
+                Spirv::from_bytes(GRADIENT_VERTEX_SHADER),
+                ShaderKind::Vertex,
+            )?,
+            device.create_shader_module(
+                Spirv::from_bytes(GRADIENT_FRAGMENT_SHADER),
+                ShaderKind::Fragment,
+            )?,
+        ];
+        let vertex_layout = VertexLayout::of::<VertexData>();
+        let state = PipelineState {
+            mode: PolygonMode::FILL,
+            topology: PrimitiveTopology::TRIANGLE_LIST,
+            vertex_layout: &vertex_layout,
+            width: stage.width,
+            height: stage.height,
+            line_width: 1.0,
+            blend: BlendMode::Alpha,
+        };
+        let pipeline = render_pass
+            .create_graphics_pipeline(device, &shaders, layout, &state)?
+            .pop()
+            .ok_or(VlError::InvalidState("No gradient pipeline was created."))?;
+
+        Ok(Self {
+            pipeline,
+            layout,
+            descriptor_layout,
+            descriptor_pool,
+            descriptor_set,
+            shaders,
+            buffer: None,
+        })
+    }
+
+    pub(crate) fn binding(&self) -> PipelineBinding {
+        PipelineBinding {
+            pipeline: self.pipeline,
+            layout: self.layout,
+            descriptor_set: self.descriptor_set,
+        }
+    }
+
+    /// Replaces the brushes with the ones of the frame about to be recorded.
+    /// The GPU must have finished the previous frame.
+    pub(crate) fn upload(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        brushes: &[Vec4<f32>],
+    ) -> VlResult<()> {
+        if let Some(buffer) = self.buffer.take() {
+            buffer.destroy_with_device(device);
+        }
+        if brushes.is_empty() {
+            return Ok(());
+        }
+        let data = as_bytes(brushes);
+        let mut buffer = Buffer::new(
+            instance,
+            physical_device,
+            device,
+            data.len(),
+            BufferUsage::Storage,
+        );
+        buffer.allocate_data(data.as_ptr() as *const std::ffi::c_void, device);
+        buffer.flush_memory(device)?;
+        buffer.unmap_memory(device)?;
+
+        let buffer_info = [DescriptorBufferInfo::builder()
+            .buffer(buffer.buffer)
+            .offset(0)
+            .range(data.len() as u64)
+            .build()];
+        let write = WriteDescriptorSet::builder()
+            .dst_set(self.descriptor_set)
+            .dst_binding(1)
+            .descriptor_type(DescriptorType::STORAGE_BUFFER)
+            .buffer_info(&buffer_info)
+            .build();
+        unsafe {
+            device.inner.update_descriptor_sets(&[write], &[]);
+        }
+        self.buffer = Some(buffer);
+        Ok(())
+    }
+}
+
+impl Destroy for GradientPipeline {
+    fn destroy_with_instance(&self, _: &Instance) {}
+
+    fn destroy_with_device(&self, device: &LogicalDevice) {
+        if let Some(buffer) = &self.buffer {
+            buffer.destroy_with_device(device);
+        }
+        for shader in &self.shaders {
+            shader.destroy_with_device(device);
+        }
+        device.destroy_pipeline(&self.pipeline);
+        unsafe {
+            device.inner.destroy_pipeline_layout(self.layout, None);
+            device
+                .inner
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            device
+                .inner
+                .destroy_descriptor_set_layout(self.descriptor_layout, None);
+        }
+    }
+}
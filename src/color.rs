@@ -0,0 +1,574 @@
+use std::str::FromStr;
+
+use crate::{offset_error, Vec4, VlError, VlResult};
+
+/// Represents a color
+///
+/// Colors convert into the `Vec4<f32>` read by the shaders, which holds sRGB channels and alpha,
+/// so a `Color` can be passed wherever a path, brush or render target takes a color.
+///
+/// ## Example
+/// ```
+/// use vulx::{Color, ColorSpace};
+/// let orange: Color = "#ffa500".parse().unwrap();
+/// let sky = Color::named("skyblue").unwrap();
+/// let mid = orange.mix(sky, 0.5, ColorSpace::OKLab);
+/// assert_eq!(Color::HSV(0.0, 1.0, 1.0).to_rgba(), Color::RGBA(1.0, 0.0, 0.0, 1.0));
+/// # let _ = mid;
+/// ```
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    /// Specified in RGB color space. Red, green and blue are in `0.0..=1.0` and sRGB encoded.
+    /// The color is opaque.
+    RGB(f64, f64, f64),
+    /// Specified in RGBA color space. Red, green, blue and alpha are in `0.0..=1.0`,
+    /// and the color channels are sRGB encoded.
+    RGBA(f64, f64, f64, f64),
+    /// Specified in HSV color space. The hue is in degrees and wraps around at 360,
+    /// saturation and value are in `0.0..=1.0`, and the color is sRGB. The color is opaque.
+    HSV(f64, f64, f64),
+    /// Specified in HSV color space with alpha. The hue is in degrees and wraps around at 360,
+    /// saturation, value and alpha are in `0.0..=1.0`, and the color is sRGB.
+    HSVA(f64, f64, f64, f64),
+    /// Specified in HSL color space. The hue is in degrees and wraps around at 360,
+    /// saturation and lightness are in `0.0..=1.0`, and the color is sRGB. The color is opaque.
+    HSL(f64, f64, f64),
+    /// Specified in HSL color space with alpha. The hue is in degrees and wraps around at 360,
+    /// saturation, lightness and alpha are in `0.0..=1.0`, and the color is sRGB.
+    HSLA(f64, f64, f64, f64),
+    /// Specified in linear RGBA color space, without the sRGB transfer function.
+    /// Red, green, blue and alpha are in `0.0..=1.0`.
+    LinearRGBA(f64, f64, f64, f64),
+    /// Specified in Oklab color space with alpha. Lightness and alpha are in `0.0..=1.0`,
+    /// the green-red axis `a` and the blue-yellow axis `b` roughly in `-0.4..=0.4`.
+    /// Oklab is built on linear light.
+    OKLab(f64, f64, f64, f64),
+}
+
+/// The color space in which colors are mixed
+///
+/// # Value Meaning
+/// * `SRGB` - Mixes the encoded sRGB channels, as most image editors do.
+/// * `LinearRGB` - Mixes physical light intensities. Blends look brighter than in sRGB.
+/// * `HSV` - Mixes hue, saturation and value. The hue takes the shorter way around the circle.
+/// * `HSL` - Mixes hue, saturation and lightness. The hue takes the shorter way around the circle.
+/// * `OKLab` - Mixes in a perceptually uniform space, without the gray middle of sRGB blends.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorSpace {
+    #[default]
+    SRGB,
+    LinearRGB,
+    HSV,
+    HSL,
+    OKLab,
+}
+
+impl Color {
+    /// Converts the color into `RGBA`.
+    pub fn to_rgba(self) -> Color {
+        let [r, g, b, a] = self.srgba();
+        Color::RGBA(r, g, b, a)
+    }
+
+    /// Converts the color into `LinearRGBA`.
+    pub fn to_linear(self) -> Color {
+        let [r, g, b, a] = self.srgba();
+        Color::LinearRGBA(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+
+    /// Converts the color into `HSVA`. Grays get a hue and saturation of 0.
+    pub fn to_hsva(self) -> Color {
+        let [r, g, b, a] = self.srgba();
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+        Color::HSVA(hue(r, g, b, max, chroma), saturation, max, a)
+    }
+
+    /// Converts the color into `HSLA`. Grays get a hue and saturation of 0.
+    pub fn to_hsla(self) -> Color {
+        let [r, g, b, a] = self.srgba();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+        let saturation = if chroma > 0.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        Color::HSLA(hue(r, g, b, max, chroma), saturation, lightness, a)
+    }
+
+    /// Converts the color into `OKLab`.
+    pub fn to_oklab(self) -> Color {
+        let [r, g, b, a] = self.linear_rgba();
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        Color::OKLab(
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+            a,
+        )
+    }
+
+    /// Converts the color into the variant with alpha of `space`.
+    pub fn to_space(self, space: ColorSpace) -> Color {
+        match space {
+            ColorSpace::SRGB => self.to_rgba(),
+            ColorSpace::LinearRGB => self.to_linear(),
+            ColorSpace::HSV => self.to_hsva(),
+            ColorSpace::HSL => self.to_hsla(),
+            ColorSpace::OKLab => self.to_oklab(),
+        }
+    }
+
+    pub fn alpha(self) -> f64 {
+        self.components()[3]
+    }
+
+    /// Returns the color with its alpha replaced, keeping its color space.
+    pub fn with_alpha(self, alpha: f64) -> Color {
+        match self {
+            Color::RGB(r, g, b) | Color::RGBA(r, g, b, _) => Color::RGBA(r, g, b, alpha),
+            Color::HSV(h, s, v) | Color::HSVA(h, s, v, _) => Color::HSVA(h, s, v, alpha),
+            Color::HSL(h, s, l) | Color::HSLA(h, s, l, _) => Color::HSLA(h, s, l, alpha),
+            Color::LinearRGBA(r, g, b, _) => Color::LinearRGBA(r, g, b, alpha),
+            Color::OKLab(l, a, b, _) => Color::OKLab(l, a, b, alpha),
+        }
+    }
+
+    /// Interpolates from `self` at `t = 0.0` to `other` at `t = 1.0` in `space`.
+    /// The result is in the variant with alpha of `space`; alpha is interpolated linearly.
+    ///
+    /// The hue of a gray has no meaning, so in `HSV` and `HSL` a gray takes the hue of the other color.
+    pub fn mix(self, other: Color, t: f64, space: ColorSpace) -> Color {
+        let mut from = self.to_space(space).components();
+        let mut to = other.to_space(space).components();
+        let mut lerped = [0.0; 4];
+        if matches!(space, ColorSpace::HSV | ColorSpace::HSL) {
+            if from[1] == 0.0 {
+                from[0] = to[0];
+            } else if to[1] == 0.0 {
+                to[0] = from[0];
+            }
+            // Along the shorter arc.
+            let delta = (to[0] - from[0] + 180.0).rem_euclid(360.0) - 180.0;
+            to[0] = from[0] + delta;
+        }
+        for (i, value) in lerped.iter_mut().enumerate() {
+            *value = from[i] + (to[i] - from[i]) * t;
+        }
+        let [c0, c1, c2, a] = lerped;
+        match space {
+            ColorSpace::SRGB => Color::RGBA(c0, c1, c2, a),
+            ColorSpace::LinearRGB => Color::LinearRGBA(c0, c1, c2, a),
+            ColorSpace::HSV => Color::HSVA(c0.rem_euclid(360.0), c1, c2, a),
+            ColorSpace::HSL => Color::HSLA(c0.rem_euclid(360.0), c1, c2, a),
+            ColorSpace::OKLab => Color::OKLab(c0, c1, c2, a),
+        }
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`. The `#` is optional.
+    pub fn from_hex(hex: &str) -> VlResult<Color> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let error = VlError::ParseError(0, "Expected 3, 4, 6 or 8 hexadecimal digits.");
+        if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error);
+        }
+        let channel = |i: usize, len: usize| {
+            let value = u8::from_str_radix(&digits[i * len..(i + 1) * len], 16).unwrap();
+            // A single digit is repeated, so `f` becomes `ff`.
+            let value = if len == 1 { value * 17 } else { value };
+            value as f64 / 255.0
+        };
+        match digits.len() {
+            3 => Ok(Color::RGBA(
+                channel(0, 1),
+                channel(1, 1),
+                channel(2, 1),
+                1.0,
+            )),
+            4 => Ok(Color::RGBA(
+                channel(0, 1),
+                channel(1, 1),
+                channel(2, 1),
+                channel(3, 1),
+            )),
+            6 => Ok(Color::RGBA(
+                channel(0, 2),
+                channel(1, 2),
+                channel(2, 2),
+                1.0,
+            )),
+            8 => Ok(Color::RGBA(
+                channel(0, 2),
+                channel(1, 2),
+                channel(2, 2),
+                channel(3, 2),
+            )),
+            _ => Err(error),
+        }
+    }
+
+    /// Looks up one of the CSS named colors, ignoring case. `transparent` is transparent black.
+    pub fn named(name: &str) -> Option<Color> {
+        if name.eq_ignore_ascii_case("transparent") {
+            return Some(Color::RGBA(0.0, 0.0, 0.0, 0.0));
+        }
+        NAMED_COLORS
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, rgb)| {
+                let channel = |shift: u32| ((rgb >> shift) & 0xff) as f64 / 255.0;
+                Color::RGBA(channel(16), channel(8), channel(0), 1.0)
+            })
+    }
+
+    /// The channels of the variant, with alpha 1.0 for variants without alpha.
+    fn components(self) -> [f64; 4] {
+        match self {
+            Color::RGB(x, y, z) | Color::HSV(x, y, z) | Color::HSL(x, y, z) => [x, y, z, 1.0],
+            Color::RGBA(x, y, z, a)
+            | Color::HSVA(x, y, z, a)
+            | Color::HSLA(x, y, z, a)
+            | Color::LinearRGBA(x, y, z, a)
+            | Color::OKLab(x, y, z, a) => [x, y, z, a],
+        }
+    }
+
+    fn srgba(self) -> [f64; 4] {
+        let [x, y, z, a] = self.components();
+        match self {
+            Color::RGB(..) | Color::RGBA(..) => [x, y, z, a],
+            Color::HSV(..) | Color::HSVA(..) => {
+                let chroma = z * y;
+                let [r, g, b] = from_hue(x, chroma, z - chroma);
+                [r, g, b, a]
+            }
+            Color::HSL(..) | Color::HSLA(..) => {
+                let chroma = (1.0 - (2.0 * z - 1.0).abs()) * y;
+                let [r, g, b] = from_hue(x, chroma, z - chroma / 2.0);
+                [r, g, b, a]
+            }
+            Color::LinearRGBA(..) | Color::OKLab(..) => {
+                let [r, g, b, a] = self.linear_rgba();
+                [linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a]
+            }
+        }
+    }
+
+    fn linear_rgba(self) -> [f64; 4] {
+        match self {
+            Color::LinearRGBA(r, g, b, a) => [r, g, b, a],
+            Color::OKLab(lightness, a, b, alpha) => {
+                let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+                let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+                let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+                [
+                    4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+                    -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+                    -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+                    alpha,
+                ]
+            }
+            _ => {
+                let [r, g, b, a] = self.srgba();
+                [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a]
+            }
+        }
+    }
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The hue in degrees of an sRGB color with the given largest channel and chroma.
+fn hue(r: f64, g: f64, b: f64, max: f64, chroma: f64) -> f64 {
+    if chroma <= 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    }
+}
+
+/// The sRGB channels of a hue with the given chroma, offset by `m`.
+fn from_hue(hue: f64, chroma: f64, m: f64) -> [f64; 3] {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let [r, g, b] = match sector as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    [r + m, g + m, b + m]
+}
+
+impl FromStr for Color {
+    type Err = VlError;
+
+    /// Parses a CSS color: a hex color, a named color, `rgb()`, `rgba()`, `hsl()` or `hsla()`.
+    /// Arguments may be separated by commas or spaces, with an optional `/` before alpha.
+    ///
+    /// On error, the position is the byte offset in `s` where the color could not be read.
+    fn from_str(s: &str) -> VlResult<Color> {
+        let value = s.trim();
+        let start = s.len() - s.trim_start().len();
+        if value.starts_with('#') {
+            return Color::from_hex(value).map_err(|e| offset_error(e, start));
+        }
+        let Some((function, arguments)) = value
+            .strip_suffix(')')
+            .and_then(|value| value.split_once('('))
+        else {
+            return Color::named(value).ok_or(VlError::ParseError(start, "Unknown color."));
+        };
+        let arguments_start = start + function.len() + 1;
+        let arguments: Vec<&str> = arguments
+            .split([',', '/', ' ', '\t', '\n'])
+            .filter(|a| !a.is_empty())
+            .collect();
+        let error = || VlError::ParseError(arguments_start, "Invalid color arguments.");
+        if !(3..=4).contains(&arguments.len()) {
+            return Err(error());
+        }
+        // Numbers are scaled by `range` into 0..=1; percentages always are.
+        let number = |argument: &str, range: f64| match argument.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok().map(|p| p / 100.0),
+            None => argument.parse::<f64>().ok().map(|n| n / range),
+        };
+        let alpha = match arguments.get(3) {
+            Some(a) => number(a, 1.0).ok_or_else(error)?,
+            None => 1.0,
+        }
+        .clamp(0.0, 1.0);
+        match function.trim().to_ascii_lowercase().as_str() {
+            "rgb" | "rgba" => {
+                let channels = arguments[..3]
+                    .iter()
+                    .map(|c| number(c, 255.0).map(|c| c.clamp(0.0, 1.0)))
+                    .collect::<Option<Vec<f64>>>()
+                    .ok_or_else(error)?;
+                Ok(Color::RGBA(channels[0], channels[1], channels[2], alpha))
+            }
+            "hsl" | "hsla" => {
+                let hue = arguments[0]
+                    .strip_suffix("deg")
+                    .unwrap_or(arguments[0])
+                    .parse::<f64>()
+                    .map_err(|_| error())?;
+                let (Some(saturation), Some(lightness)) = (
+                    arguments[1]
+                        .strip_suffix('%')
+                        .and_then(|s| s.parse::<f64>().ok()),
+                    arguments[2]
+                        .strip_suffix('%')
+                        .and_then(|l| l.parse::<f64>().ok()),
+                ) else {
+                    return Err(error());
+                };
+                Ok(Color::HSLA(
+                    hue.rem_euclid(360.0),
+                    (saturation / 100.0).clamp(0.0, 1.0),
+                    (lightness / 100.0).clamp(0.0, 1.0),
+                    alpha,
+                ))
+            }
+            _ => Err(VlError::ParseError(start, "Unknown color function.")),
+        }
+    }
+}
+
+impl From<Color> for Vec4<f32> {
+    fn from(color: Color) -> Self {
+        let [r, g, b, a] = color.srgba();
+        Vec4::new(r as f32, g as f32, b as f32, a as f32)
+    }
+}
+
+impl From<Vec4<f32>> for Color {
+    fn from(color: Vec4<f32>) -> Self {
+        Color::RGBA(
+            color.x as f64,
+            color.y as f64,
+            color.z as f64,
+            color.w as f64,
+        )
+    }
+}
+
+/// The CSS named colors as `0xRRGGBB`.
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
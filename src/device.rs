@@ -1,17 +1,20 @@
 use ash::vk::{
     CommandBuffer, CommandBufferAllocateInfo, CommandBufferLevel, CommandPool,
-    CommandPoolCreateFlags, CommandPoolCreateInfo, ImageUsageFlags, ShaderModuleCreateInfo,
-    SharingMode,
+    CommandPoolCreateFlags, CommandPoolCreateInfo, SampleCountFlags, ShaderModuleCreateInfo,
 };
 
+#[cfg(target_os = "windows")]
+#[cfg(feature = "window")]
+use crate::{target::surface::Surface, target::swapchain::Swapchain, PhysicalDevice};
 use crate::{
-    target::surface::Surface, target::swapchain::Swapchain, Destroy, Instance, PhysicalDevice,
-    Pipeline, Queue, RenderPass, Shader, ShaderKind, Spirv, VlError, VlResult,
+    Destroy, Instance, Pipeline, Queue, RenderPass, Shader, ShaderKind, Spirv, VlError, VlResult,
 };
 
 /// Represents a logical device.
 pub struct LogicalDevice {
     pub(crate) inner: ash::Device,
+    /// The sample counts the physical device supports for color attachments.
+    pub(crate) sample_counts: SampleCountFlags,
 }
 
 impl LogicalDevice {
@@ -69,13 +72,15 @@ impl LogicalDevice {
         }
     }
 
+    #[cfg(target_os = "windows")]
+    #[cfg(feature = "window")]
     pub(crate) fn create_swapchain(
         &self,
         instance: &Instance,
         physical_device: PhysicalDevice,
         surface: &Surface,
     ) -> VlResult<Swapchain> {
-        use ash::vk::SwapchainCreateInfoKHR;
+        use ash::vk::{ImageUsageFlags, SharingMode, SwapchainCreateInfoKHR};
 
         let surface_capabilities = match unsafe {
             surface
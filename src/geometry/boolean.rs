@@ -0,0 +1,287 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Vec2;
+
+use super::{FillRule, Outline, PathGeometry};
+
+/// Specifies how two shapes are combined.
+///
+/// # Value Meaning
+/// * `Union` - The area covered by either shape.
+/// * `Intersection` - The area covered by both shapes.
+/// * `Difference` - The area of the first shape that is not covered by the second one.
+/// * `Xor` - The area covered by exactly one of the shapes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Xor => a != b,
+        }
+    }
+}
+
+/// Distance from an edge at which the areas on both of its sides are probed.
+const PROBE: f64 = 1e-5;
+/// Points closer than this are considered the same when contours are linked.
+const SNAP: f64 = 1e-6;
+
+impl PathGeometry {
+    /// Combines the filled area of this geometry with the one of `other` and returns the outline of the result.
+    ///
+    /// Each operand is filled with the fill rules of its own contours. Curves are flattened with the
+    /// tolerance of this geometry, so the result only contains straight lines. Holes are wound in the
+    /// opposite direction of the outer contours, so the result fills correctly with either fill rule.
+    /// It takes its color, tolerance and stroke style from this geometry.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use vulx::{geometry::{BooleanOp, PathGeometry}, Vec2};
+    /// let mut a = PathGeometry::new();
+    /// a.circle(Vec2::new(100.0, 100.0), 50.0);
+    /// let mut b = PathGeometry::new();
+    /// b.circle(Vec2::new(150.0, 100.0), 50.0);
+    /// let lens = a.boolean(&b, BooleanOp::Intersection);
+    /// ```
+    pub fn boolean(&self, other: &PathGeometry, op: BooleanOp) -> PathGeometry {
+        let tolerance = self.path_tolerance();
+        let operands = [self.fill_groups(tolerance), other.fill_groups(tolerance)];
+        let inside = |p: Vec2<f64>| {
+            let [a, b] = operands.each_ref().map(|groups| {
+                groups
+                    .iter()
+                    .any(|(contours, rule)| rule.is_inside(winding(contours, p)))
+            });
+            op.apply(a, b)
+        };
+
+        let mut segments = vec![];
+        for groups in &operands {
+            for (contours, _) in groups {
+                for contour in contours {
+                    for i in 0..contour.len() {
+                        let (a, b) = (contour[i], contour[(i + 1) % contour.len()]);
+                        if a != b {
+                            segments.push((a, b));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Keep the pieces that separate the inside of the result from the outside,
+        // oriented so that the inside is on their left.
+        let mut seen = HashSet::new();
+        let mut edges = vec![];
+        for (a, b) in split_segments(&segments) {
+            let (ka, kb) = (key(a), key(b));
+            if ka == kb || !seen.insert(if ka < kb { (ka, kb) } else { (kb, ka) }) {
+                continue;
+            }
+            let direction = (b - a).normalize();
+            let normal = Vec2::new(-direction.y, direction.x) * PROBE;
+            let middle = (a + b) / 2.0;
+            match (inside(middle + normal), inside(middle - normal)) {
+                (true, false) => edges.push((a, b)),
+                (false, true) => edges.push((b, a)),
+                _ => {}
+            }
+        }
+
+        let mut result = PathGeometry::new();
+        result.color(self.color);
+        result.tolerance(self.tolerance);
+        result.scale = self.scale;
+        result.stroke_style(self.stroke_style.clone());
+        for contour in link(&edges) {
+            let points: Vec<_> = contour
+                .iter()
+                .map(|p| Vec2::new(p.x as f32, p.y as f32))
+                .collect();
+            result.polygon(&points);
+        }
+        result
+    }
+
+    pub fn union(&self, other: &PathGeometry) -> PathGeometry {
+        self.boolean(other, BooleanOp::Union)
+    }
+
+    pub fn intersection(&self, other: &PathGeometry) -> PathGeometry {
+        self.boolean(other, BooleanOp::Intersection)
+    }
+
+    pub fn difference(&self, other: &PathGeometry) -> PathGeometry {
+        self.boolean(other, BooleanOp::Difference)
+    }
+
+    pub fn xor(&self, other: &PathGeometry) -> PathGeometry {
+        self.boolean(other, BooleanOp::Xor)
+    }
+
+    /// Closed polygons of every group of contours that is filled together, with its fill rule.
+    fn fill_groups(&self, tolerance: f32) -> Vec<(Vec<Vec<Vec2<f64>>>, FillRule)> {
+        let polygons = |outlines: &[Outline]| -> Vec<Vec<Vec2<f64>>> {
+            outlines
+                .iter()
+                .map(|o| {
+                    o.points
+                        .iter()
+                        .map(|v| Vec2::new(v.pos.x as f64, v.pos.y as f64))
+                        .collect()
+                })
+                .collect()
+        };
+        let mut groups = vec![(polygons(&self.outlines), FillRule::NonZero)];
+        for figure in &self.figures {
+            groups.push((polygons(&figure.flatten(tolerance)), figure.fill_rule));
+        }
+        groups
+    }
+}
+
+fn winding(contours: &[Vec<Vec2<f64>>], p: Vec2<f64>) -> i32 {
+    let mut winding = 0;
+    for contour in contours {
+        for i in 0..contour.len() {
+            let (a, b) = (contour[i], contour[(i + 1) % contour.len()]);
+            let side = (b - a).perp(&(p - a));
+            if a.y <= p.y && b.y > p.y && side > 0.0 {
+                winding += 1;
+            } else if b.y <= p.y && a.y > p.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    winding
+}
+
+/// Splits the segments at all points where they cross or touch each other.
+fn split_segments(segments: &[(Vec2<f64>, Vec2<f64>)]) -> Vec<(Vec2<f64>, Vec2<f64>)> {
+    const EPSILON: f64 = 1e-9;
+
+    // Split points of every segment, as (parameter along the segment, point).
+    let mut splits: Vec<Vec<(f64, Vec2<f64>)>> = vec![vec![]; segments.len()];
+    let param = |(a, b): (Vec2<f64>, Vec2<f64>), p: Vec2<f64>| {
+        let d = b - a;
+        (p - a).dot(&d) / d.norm_squared()
+    };
+
+    for i in 0..segments.len() {
+        let (a0, a1) = segments[i];
+        let r = a1 - a0;
+        for j in i + 1..segments.len() {
+            let (b0, b1) = segments[j];
+            let s = b1 - b0;
+            let denominator = r.perp(&s);
+            let qp = b0 - a0;
+
+            if denominator.abs() <= EPSILON * r.norm() * s.norm() {
+                // Parallel. Overlapping collinear segments split each other at their end points.
+                if qp.perp(&r).abs() > EPSILON * r.norm() * (1.0 + qp.norm()) {
+                    continue;
+                }
+                for p in [b0, b1] {
+                    let t = param(segments[i], p);
+                    if t > EPSILON && t < 1.0 - EPSILON {
+                        splits[i].push((t, p));
+                    }
+                }
+                for p in [a0, a1] {
+                    let u = param(segments[j], p);
+                    if u > EPSILON && u < 1.0 - EPSILON {
+                        splits[j].push((u, p));
+                    }
+                }
+                continue;
+            }
+
+            let t = qp.perp(&s) / denominator;
+            let u = qp.perp(&r) / denominator;
+            let range = -EPSILON..=1.0 + EPSILON;
+            if !range.contains(&t) || !range.contains(&u) {
+                continue;
+            }
+            // Reuse existing end points, so that touching contours stay connected exactly.
+            let near = |x: f64, v: f64| (x - v).abs() <= EPSILON;
+            let point = if near(t, 0.0) {
+                a0
+            } else if near(t, 1.0) {
+                a1
+            } else if near(u, 0.0) {
+                b0
+            } else if near(u, 1.0) {
+                b1
+            } else {
+                a0 + r * t
+            };
+            if t > EPSILON && t < 1.0 - EPSILON {
+                splits[i].push((t, point));
+            }
+            if u > EPSILON && u < 1.0 - EPSILON {
+                splits[j].push((u, point));
+            }
+        }
+    }
+
+    let mut pieces = vec![];
+    for (&(a, b), mut points) in segments.iter().zip(splits) {
+        points.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut start = a;
+        for (_, p) in points.into_iter().chain([(1.0, b)]) {
+            if key(start) != key(p) {
+                pieces.push((start, p));
+                start = p;
+            }
+        }
+    }
+    pieces
+}
+
+fn key(p: Vec2<f64>) -> (i64, i64) {
+    ((p.x / SNAP).round() as i64, (p.y / SNAP).round() as i64)
+}
+
+/// Connects directed edges end to start into closed contours.
+fn link(edges: &[(Vec2<f64>, Vec2<f64>)]) -> Vec<Vec<Vec2<f64>>> {
+    let mut starting: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (i, (a, _)) in edges.iter().enumerate() {
+        starting.entry(key(*a)).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut contours = vec![];
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let start = key(edges[first].0);
+        let mut contour = vec![edges[first].0];
+        let mut end = edges[first].1;
+        while key(end) != start {
+            let next = starting
+                .get(&key(end))
+                .and_then(|candidates| candidates.iter().find(|i| !used[**i]).copied());
+            let Some(next) = next else {
+                break;
+            };
+            used[next] = true;
+            contour.push(end);
+            end = edges[next].1;
+        }
+        if contour.len() > 2 {
+            contours.push(contour);
+        }
+    }
+    contours
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use crate::{Vec2, Vec4};
+
+use super::{
+    tessellate::{collect_edges, for_each_span, Edge, EPSILON},
+    FillRule, IndexBuffer, Outline, VertexData,
+};
+
+/// Corners sharper than this are not mitered, so the fringe does not spike out.
+const MAX_MITER: f64 = 2.0;
+
+/// A filled interval of a horizontal band, bounded by the edges on either side.
+#[derive(Clone, Copy)]
+struct Span {
+    left: Edge,
+    right: Edge,
+}
+
+impl Span {
+    fn interval(&self, y: f64) -> (f64, f64) {
+        (self.left.x_at(y), self.right.x_at(y))
+    }
+
+    fn color_at(&self, x: f64, y: f64) -> Vec4<f32> {
+        let (x0, x1) = self.interval(y);
+        let t = if x1 - x0 > EPSILON {
+            ((x - x0) / (x1 - x0)) as f32
+        } else {
+            0.0
+        };
+        self.left.color_at(y).lerp(&self.right.color_at(y), t)
+    }
+}
+
+/// A piece of the boundary of the filled area, with the normal pointing out of it.
+struct Boundary {
+    ends: [Vec2<f64>; 2],
+    colors: [Vec4<f32>; 2],
+    normal: Vec2<f64>,
+}
+
+/// Appends a fringe around the area the outlines fill under `rule`, which fades from the
+/// color at the boundary to transparent over `width`. Drawn with alpha blending next to the
+/// fill, it smooths the edges without multisampling.
+///
+/// The boundary is found from the same bands the fill is tessellated with, so it follows
+/// holes and self-intersections, and edges shared by adjacent contours get no fringe.
+pub(crate) fn feather_outlines(
+    outlines: &[Outline],
+    rule: FillRule,
+    width: f32,
+    out: &mut IndexBuffer,
+) {
+    let edges = collect_edges(outlines);
+    let mut bands: Vec<(f64, f64, Vec<Span>)> = vec![];
+    for_each_span(&edges, rule, |y0, y1, left, right| {
+        let span = Span {
+            left: *left,
+            right: *right,
+        };
+        match bands.last_mut() {
+            Some((band_y0, _, spans)) if *band_y0 == y0 => {
+                // Spans that touch along their whole height are one, as the edge between them is inside.
+                match spans.last_mut() {
+                    Some(last) if touches(last, &span, y0, y1) => last.right = span.right,
+                    _ => spans.push(span),
+                }
+            }
+            _ => bands.push((y0, y1, vec![span])),
+        }
+    });
+
+    let mut boundaries = vec![];
+    let mut above: (f64, &[Span]) = (f64::NAN, &[]);
+    for (y0, y1, spans) in &bands {
+        let (y0, y1) = (*y0, *y1);
+        for span in spans {
+            boundaries.push(side(&span.left, y0, y1, -1.0));
+            boundaries.push(side(&span.right, y0, y1, 1.0));
+        }
+        // Where a band does not continue the one above, both are bounded horizontally.
+        if (above.0 - y0).abs() > EPSILON {
+            horizontal(above.1, &[], above.0, 1.0, &mut boundaries);
+            above.1 = &[];
+        }
+        horizontal(above.1, spans, y0, 1.0, &mut boundaries);
+        horizontal(spans, above.1, y0, -1.0, &mut boundaries);
+        above = (y1, spans);
+    }
+    horizontal(above.1, &[], above.0, 1.0, &mut boundaries);
+
+    // The fringes of neighboring boundaries share the offset at their common end, so corners have no gaps.
+    let grid = width as f64 * 1e-3;
+    let key = |p: Vec2<f64>| ((p.x / grid).round() as i64, (p.y / grid).round() as i64);
+    let mut normals: HashMap<(i64, i64), Vec<Vec2<f64>>> = HashMap::new();
+    for boundary in &boundaries {
+        for end in boundary.ends {
+            normals.entry(key(end)).or_default().push(boundary.normal);
+        }
+    }
+    let offset = |p: Vec2<f64>, normal: Vec2<f64>| match normals[&key(p)][..] {
+        [a, b] if 1.0 + a.dot(&b) > EPSILON => {
+            let miter = (a + b) / (1.0 + a.dot(&b));
+            if miter.norm() > MAX_MITER {
+                miter.normalize() * MAX_MITER
+            } else {
+                miter
+            }
+        }
+        _ => normal,
+    };
+
+    let width = width as f64;
+    let vertex = |p: Vec2<f64>, color: Vec4<f32>| VertexData {
+        pos: Vec4::new(p.x as f32, p.y as f32, 0.0, 1.0),
+        color,
+    };
+    for boundary in boundaries {
+        let [a, b] = boundary.ends;
+        if (b - a).norm() < EPSILON {
+            continue;
+        }
+        let [color_a, color_b] = boundary.colors;
+        let transparent = |color: Vec4<f32>| Vec4::new(color.x, color.y, color.z, 0.0);
+        let inner_a = out.push_vertex(vertex(a, color_a));
+        let inner_b = out.push_vertex(vertex(b, color_b));
+        let outer_b = out.push_vertex(vertex(
+            b + offset(b, boundary.normal) * width,
+            transparent(color_b),
+        ));
+        let outer_a = out.push_vertex(vertex(
+            a + offset(a, boundary.normal) * width,
+            transparent(color_a),
+        ));
+        out.push_triangle(inner_a, inner_b, outer_b);
+        out.push_triangle(inner_a, outer_b, outer_a);
+    }
+}
+
+/// Turns the triangles into outlines that all wind the same way, so that their union
+/// is filled under [`FillRule::NonZero`].
+pub(crate) fn triangle_outlines(triangles: &IndexBuffer) -> Vec<Outline> {
+    triangles
+        .indices
+        .chunks_exact(3)
+        .map(|indices| {
+            let [a, b, c] = [0, 1, 2].map(|i| triangles.data[indices[i] as usize]);
+            let area = (b.pos.x - a.pos.x) * (c.pos.y - a.pos.y)
+                - (c.pos.x - a.pos.x) * (b.pos.y - a.pos.y);
+            let points = if area < 0.0 {
+                vec![a, c, b]
+            } else {
+                vec![a, b, c]
+            };
+            Outline {
+                points,
+                closed: true,
+            }
+        })
+        .collect()
+}
+
+fn touches(left: &Span, right: &Span, y0: f64, y1: f64) -> bool {
+    (left.right.x_at(y0) - right.left.x_at(y0)).abs() < EPSILON
+        && (left.right.x_at(y1) - right.left.x_at(y1)).abs() < EPSILON
+}
+
+/// The part of `edge` within the band, bounding the fill on its left (`side` -1) or right (`side` 1).
+fn side(edge: &Edge, y0: f64, y1: f64, side: f64) -> Boundary {
+    let a = Vec2::new(edge.x_at(y0), y0);
+    let b = Vec2::new(edge.x_at(y1), y1);
+    let direction = (b - a).normalize();
+    Boundary {
+        ends: [a, b],
+        colors: [edge.color_at(y0), edge.color_at(y1)],
+        normal: Vec2::new(direction.y, -direction.x) * side,
+    }
+}
+
+/// Adds the parts of `spans` at height `y` that `cover` does not reach, facing `direction` in y.
+fn horizontal(spans: &[Span], cover: &[Span], y: f64, direction: f64, out: &mut Vec<Boundary>) {
+    for span in spans {
+        let (mut x0, x1) = span.interval(y);
+        for covered in cover.iter().map(|c| c.interval(y)) {
+            if covered.1 <= x0 || covered.0 >= x1 {
+                continue;
+            }
+            if covered.0 > x0 {
+                out.push(horizontal_boundary(span, x0, covered.0, y, direction));
+            }
+            x0 = x0.max(covered.1);
+        }
+        if x1 > x0 {
+            out.push(horizontal_boundary(span, x0, x1, y, direction));
+        }
+    }
+}
+
+fn horizontal_boundary(span: &Span, x0: f64, x1: f64, y: f64, direction: f64) -> Boundary {
+    Boundary {
+        ends: [Vec2::new(x0, y), Vec2::new(x1, y)],
+        colors: [span.color_at(x0, y), span.color_at(x1, y)],
+        normal: Vec2::new(0.0, direction),
+    }
+}
@@ -0,0 +1,236 @@
+use ash::vk::IndexType;
+
+use crate::{
+    Instance, IntoPath, LogicalDevice, PhysicalDevice, PrimitiveTopology, Vec2, Vertex,
+    VertexFormat, VlError, VlResult,
+};
+
+use super::{as_bytes, Path, VertexData};
+
+/// Indices into the vertices of a [`Mesh`], in 16 or 32 bits.
+///
+/// # Value Meaning
+/// * `U16` - Halves the size of the index buffer for meshes with at most 65536 vertices.
+/// * `U32` - Indices for meshes of any size.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<u32> {
+        match self {
+            Indices::U16(indices) => indices.get(i).map(|&i| i as u32),
+            Indices::U32(indices) => indices.get(i).copied(),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.len()).map(|i| self.get(i).unwrap())
+    }
+
+    /// Picks the same index width as `self` for `indices`.
+    fn with_width(&self, indices: Vec<u32>) -> Self {
+        match self {
+            Indices::U16(_) => Indices::U16(indices.into_iter().map(|i| i as u16).collect()),
+            Indices::U32(_) => Indices::U32(indices),
+        }
+    }
+}
+
+impl From<&[u16]> for Indices {
+    fn from(indices: &[u16]) -> Self {
+        Indices::U16(indices.to_vec())
+    }
+}
+
+impl From<&[u32]> for Indices {
+    fn from(indices: &[u32]) -> Self {
+        Indices::U32(indices.to_vec())
+    }
+}
+
+impl From<Vec<u16>> for Indices {
+    fn from(indices: Vec<u16>) -> Self {
+        Indices::U16(indices)
+    }
+}
+
+impl From<Vec<u32>> for Indices {
+    fn from(indices: Vec<u32>) -> Self {
+        Indices::U32(indices)
+    }
+}
+
+/// # Mesh
+/// Vertices and indices supplied by the user, uploaded as they are.
+///
+/// The vertices must match the vertex layout of the pipeline of the render target, see
+/// [`PipelineBuilder::vertex`](crate::PipelineBuilder::vertex); the default shaders read [`VertexData`].
+/// Strips and fans are converted to a triangle list when the mesh is uploaded, so the mesh can be
+/// drawn with the default `TriangleList` pipeline. Triangles are not rewound: faces that are
+/// counterclockwise on screen are culled.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{geometry::{Mesh, VertexData}, PrimitiveTopology, Vec2, Vec4};
+/// let red = Vec4::new(1.0, 0.0, 0.0, 1.0);
+/// let vertices = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]
+///     .map(|(x, y)| VertexData::new(Vec2::new(x, y), red));
+/// let quad = Mesh::new(&vertices, &[0u16, 1, 2, 0, 2, 3][..], PrimitiveTopology::TriangleList).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Mesh<V: Vertex = VertexData> {
+    vertices: Vec<V>,
+    indices: Indices,
+    topology: PrimitiveTopology,
+}
+
+impl<V: Vertex> Mesh<V> {
+    /// Returns an error if an index does not refer to one of the vertices.
+    pub fn new(
+        vertices: &[V],
+        indices: impl Into<Indices>,
+        topology: PrimitiveTopology,
+    ) -> VlResult<Self> {
+        let indices = indices.into();
+        if indices.iter().any(|i| i as usize >= vertices.len()) {
+            return Err(VlError::InvalidState("Index out of range of the vertices."));
+        }
+        Ok(Self {
+            vertices: vertices.to_vec(),
+            indices,
+            topology,
+        })
+    }
+
+    /// Draws the vertices in the order they are given.
+    pub fn from_vertices(vertices: &[V], topology: PrimitiveTopology) -> Self {
+        let indices = (0..vertices.len() as u32).collect();
+        Self {
+            vertices: vertices.to_vec(),
+            indices: Indices::U32(indices),
+            topology,
+        }
+    }
+
+    pub fn vertices(&self) -> &[V] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &Indices {
+        &self.indices
+    }
+
+    pub fn topology(&self) -> PrimitiveTopology {
+        self.topology
+    }
+
+    /// The indices rearranged into a triangle list.
+    pub(crate) fn triangle_list(&self) -> Indices {
+        let count = self.indices.len();
+        let index = |i: usize| self.indices.get(i).unwrap();
+        let list = match self.topology {
+            PrimitiveTopology::TriangleList => return self.indices.clone(),
+            // Every other triangle of a strip is flipped to keep the winding of the first one.
+            PrimitiveTopology::TriangleStrip => (2..count)
+                .flat_map(|i| match i % 2 {
+                    0 => [index(i - 2), index(i - 1), index(i)],
+                    _ => [index(i - 1), index(i - 2), index(i)],
+                })
+                .collect(),
+            PrimitiveTopology::TriangleFan => (2..count)
+                .flat_map(|i| [index(0), index(i - 1), index(i)])
+                .collect(),
+        };
+        self.indices.with_width(list)
+    }
+
+    /// Positions of the triangles, read from the float attribute at location 0.
+    fn triangles(&self, list: &Indices) -> Vec<[Vec2<f32>; 3]> {
+        let attribute = V::attributes().into_iter().find(|a| {
+            a.location == 0
+                && matches!(
+                    a.format,
+                    VertexFormat::Vec2 | VertexFormat::Vec3 | VertexFormat::Vec4
+                )
+        });
+        let Some(attribute) = attribute else {
+            return vec![];
+        };
+        let bytes = as_bytes(&self.vertices);
+        let stride = V::stride() as usize;
+        let position = |index: u32| {
+            let start = index as usize * stride + attribute.offset as usize;
+            let float = |offset: usize| {
+                f32::from_ne_bytes(
+                    bytes[start + offset..start + offset + 4]
+                        .try_into()
+                        .unwrap(),
+                )
+            };
+            Vec2::new(float(0), float(4))
+        };
+        let list: Vec<u32> = list.iter().collect();
+        list.chunks_exact(3)
+            .map(|t| [position(t[0]), position(t[1]), position(t[2])])
+            .collect()
+    }
+}
+
+impl<V: Vertex> IntoPath for Mesh<V> {
+    fn into_path(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+    ) -> Path {
+        let list = self.triangle_list();
+        let triangles = self.triangles(&list);
+        let (indices, index_type) = match &list {
+            Indices::U16(indices) => (as_bytes(indices), IndexType::UINT16),
+            Indices::U32(indices) => (as_bytes(indices), IndexType::UINT32),
+        };
+        Path::from_bytes(
+            instance,
+            physical_device,
+            device,
+            as_bytes(&self.vertices),
+            indices,
+            index_type,
+            triangles,
+        )
+    }
+
+    /// Meshes have no outline, so their stroke is empty.
+    /// Draw them with a pipeline in `PolygonMode::Line` to see their edges.
+    fn into_stroke_path(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        _thickness: f64,
+    ) -> Path {
+        Path::from_bytes(
+            instance,
+            physical_device,
+            device,
+            &[],
+            &[],
+            IndexType::UINT32,
+            vec![],
+        )
+    }
+}
@@ -0,0 +1,883 @@
+mod boolean;
+mod feather;
+mod mesh;
+mod path;
+mod query;
+mod shapes;
+mod stroke;
+mod tessellate;
+mod transform;
+
+use std::ffi::c_void;
+
+use crate::{
+    identity, Destroy, Instance, IntoPath, LogicalDevice, Mat4, PhysicalDevice, Stage, Vec2, Vec3,
+    Vec4, VlError, VlResult,
+};
+use ash::vk::{
+    BufferCreateInfo, DeviceMemory, IndexType, MappedMemoryRange, MemoryAllocateInfo,
+    MemoryMapFlags, MemoryPropertyFlags, PhysicalDeviceMemoryProperties,
+};
+pub use boolean::*;
+pub use mesh::*;
+pub use path::*;
+pub use query::*;
+pub use shapes::*;
+pub use stroke::*;
+pub use tessellate::*;
+pub use transform::*;
+
+/// # Represents a line segment
+/// ## Members
+/// * `start` - starting coordinate.
+/// * `end` - ending coordinate.
+pub struct Line {
+    start: Vec2<f32>,
+    end: Vec2<f32>,
+}
+
+impl Line {
+    /// # Example
+    /// ```no_run
+    /// use vulx::{Line,Vec2};
+    /// let line = Line::new(Vec2::new(30.0,30.0),Vec2::new(100.0,70.0));
+    /// ```
+    pub fn new(start: Vec2<f32>, end: Vec2<f32>) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the starting point.
+    pub fn start(&self) -> Vec2<f32> {
+        self.start
+    }
+
+    /// Returns the ending point.
+    pub fn end(&self) -> Vec2<f32> {
+        self.end
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub(crate) enum BufferUsage {
+    Vertex,
+    Uniform,
+    Index,
+    /// The source of a copy into an image.
+    Staging,
+    Storage,
+}
+
+#[doc(hidden)]
+pub(crate) struct Buffer {
+    pub(crate) buffer: ash::vk::Buffer,
+    pub(crate) mem_prop: PhysicalDeviceMemoryProperties,
+    pub(crate) memory: Option<DeviceMemory>,
+    pub(crate) write_mem: Option<*mut c_void>,
+    pub(crate) size: usize,
+}
+
+impl Buffer {
+    pub fn new(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        size: usize,
+        usage: BufferUsage,
+    ) -> Self {
+        let usage = match usage {
+            BufferUsage::Vertex => ash::vk::BufferUsageFlags::VERTEX_BUFFER,
+            BufferUsage::Uniform => ash::vk::BufferUsageFlags::UNIFORM_BUFFER,
+            BufferUsage::Index => ash::vk::BufferUsageFlags::INDEX_BUFFER,
+            BufferUsage::Staging => ash::vk::BufferUsageFlags::TRANSFER_SRC,
+            BufferUsage::Storage => ash::vk::BufferUsageFlags::STORAGE_BUFFER,
+        };
+        let create_info = BufferCreateInfo::builder()
+            .size(size as u64)
+            .usage(usage)
+            .sharing_mode(ash::vk::SharingMode::EXCLUSIVE)
+            .build();
+        let buffer = unsafe { device.inner.create_buffer(&create_info, None) }.unwrap();
+
+        let mem_prop = unsafe {
+            instance
+                .inner
+                .get_physical_device_memory_properties(physical_device.0)
+        };
+
+        Self {
+            buffer,
+            mem_prop,
+            memory: None,
+            write_mem: None,
+            size,
+        }
+    }
+
+    pub fn allocate_data(&mut self, data: *const c_void, device: &LogicalDevice) {
+        self.allocate(device);
+        if let Some(write_mem) = self.write_mem {
+            unsafe { libc::memcpy(write_mem, data, self.size) };
+        }
+    }
+
+    /// Allocates host visible memory for the buffer and keeps it mapped.
+    pub(crate) fn allocate(&mut self, device: &LogicalDevice) {
+        let mem_req = unsafe { device.inner.get_buffer_memory_requirements(self.buffer) };
+        let mut create_info = MemoryAllocateInfo::builder().allocation_size(mem_req.size);
+
+        let mut suitable_memory_found = false;
+
+        for i in 0..self.mem_prop.memory_type_count {
+            if (mem_req.memory_type_bits & (1 << i)) != 0
+                && (self.mem_prop.memory_types[i as usize].property_flags
+                    & MemoryPropertyFlags::HOST_VISIBLE)
+                    .as_raw()
+                    != 0
+            {
+                create_info = create_info.memory_type_index(i);
+                suitable_memory_found = true;
+                break;
+            }
+        }
+
+        if !suitable_memory_found {
+            panic!("No memory available");
+        }
+
+        let memory;
+        unsafe {
+            memory = device.inner.allocate_memory(&create_info, None).unwrap();
+            device
+                .inner
+                .bind_buffer_memory(self.buffer, memory, 0)
+                .unwrap();
+            let write_mem = device
+                .inner
+                .map_memory(memory, 0, self.size as u64, MemoryMapFlags::empty())
+                .unwrap();
+
+            self.write_mem = Some(write_mem);
+            self.memory = Some(memory);
+        }
+    }
+
+    pub fn write(&self, data: *const c_void, size: usize) -> VlResult<()> {
+        if size > self.size {
+            return Err(VlError::OutOfMemory);
+        }
+        match self.write_mem {
+            Some(memory) => unsafe {
+                libc::memcpy(memory, data, size);
+            },
+            None => return Err(VlError::InvalidState("Memory is not allocated.")),
+        };
+
+        Ok(())
+    }
+
+    pub fn flush_memory(&self, device: &LogicalDevice) -> VlResult<()> {
+        match self.memory {
+            Some(memory) => {
+                let mapped_memory_range = MappedMemoryRange::builder()
+                    .memory(memory)
+                    .offset(0)
+                    .size(self.size as u64)
+                    .build();
+
+                unsafe {
+                    device
+                        .inner
+                        .flush_mapped_memory_ranges(&[mapped_memory_range])
+                        .unwrap()
+                };
+            }
+            None => return Err(VlError::InvalidState("Memory is not allocated.")),
+        };
+
+        Ok(())
+    }
+
+    pub fn unmap_memory(&mut self, device: &LogicalDevice) -> VlResult<()> {
+        match self.memory {
+            Some(memory) => {
+                unsafe {
+                    device.inner.unmap_memory(memory);
+                };
+            }
+            None => return Err(VlError::InvalidState("Memory is not allocated.")),
+        };
+        self.write_mem = None;
+
+        Ok(())
+    }
+}
+
+impl Destroy for Buffer {
+    fn destroy_with_instance(&self, instance: &Instance) {}
+
+    fn destroy_with_device(&self, device: &LogicalDevice) {
+        unsafe {
+            if let Some(x) = self.memory {
+                device.inner.free_memory(x, None)
+            }
+            device.inner.destroy_buffer(self.buffer, None);
+        }
+    }
+}
+
+pub struct Path {
+    pub(crate) buffers: Vec<Buffer>,
+    pub(crate) index_buffers: Vec<(Buffer, usize)>,
+    pub(crate) index_type: IndexType,
+    /// CPU copy of the uploaded triangles, used for hit testing.
+    pub(crate) triangles: Vec<[Vec2<f32>; 3]>,
+}
+
+impl Path {
+    /// Uploads each vertex/index pair into its own vertex and index buffer.
+    pub(crate) fn new<'a>(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        index_buffer: impl IntoIterator<Item = &'a IndexBuffer>,
+    ) -> Self {
+        let mut index_buffers = vec![];
+        let mut buffers = vec![];
+        let mut triangles = vec![];
+        for i in index_buffer {
+            if i.indices.is_empty() {
+                continue;
+            }
+            let position = |index: u32| {
+                let pos = i.data[index as usize].pos;
+                Vec2::new(pos.x, pos.y)
+            };
+            triangles.extend(
+                i.indices
+                    .chunks_exact(3)
+                    .map(|t| [position(t[0]), position(t[1]), position(t[2])]),
+            );
+            buffers.push(upload(
+                instance,
+                physical_device,
+                device,
+                as_bytes(&i.data),
+                BufferUsage::Vertex,
+            ));
+            index_buffers.push((
+                upload(
+                    instance,
+                    physical_device,
+                    device,
+                    as_bytes(&i.indices),
+                    BufferUsage::Index,
+                ),
+                i.indices.len(),
+            ));
+        }
+
+        Self {
+            buffers,
+            index_buffers,
+            index_type: IndexType::UINT32,
+            triangles,
+        }
+    }
+
+    /// Uploads raw vertex and index data as one draw call.
+    /// `triangles` are the positions used for hit testing.
+    pub(crate) fn from_bytes(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        vertices: &[u8],
+        indices: &[u8],
+        index_type: IndexType,
+        triangles: Vec<[Vec2<f32>; 3]>,
+    ) -> Self {
+        let index_size = match index_type {
+            IndexType::UINT16 => 2,
+            _ => 4,
+        };
+        if vertices.is_empty() || indices.is_empty() {
+            return Self {
+                buffers: vec![],
+                index_buffers: vec![],
+                index_type,
+                triangles,
+            };
+        }
+        Self {
+            buffers: vec![upload(
+                instance,
+                physical_device,
+                device,
+                vertices,
+                BufferUsage::Vertex,
+            )],
+            index_buffers: vec![(
+                upload(
+                    instance,
+                    physical_device,
+                    device,
+                    indices,
+                    BufferUsage::Index,
+                ),
+                indices.len() / index_size,
+            )],
+            index_type,
+            triangles,
+        }
+    }
+
+    /// Returns true if the pixel at `point` of the image is covered by this path when it is drawn
+    /// with `transform` on `stage`.
+    ///
+    /// `point` is in window coordinates, with the origin at the top-left corner.
+    pub fn hit_test(&self, stage: &Stage, point: Vec2<f32>, transform: &Transform) -> bool {
+        let Some(p) = stage
+            .window_to_world(point)
+            .zip(transform.inverse())
+            .map(|(world, inverse)| inverse.apply(world))
+        else {
+            return false;
+        };
+        self.triangles.iter().any(|[a, b, c]| {
+            let d0 = (b - a).perp(&(p - a));
+            let d1 = (c - b).perp(&(p - b));
+            let d2 = (a - c).perp(&(p - c));
+            (d0 >= 0.0 && d1 >= 0.0 && d2 >= 0.0) || (d0 <= 0.0 && d1 <= 0.0 && d2 <= 0.0)
+        })
+    }
+}
+
+impl Destroy for Path {
+    fn destroy_with_instance(&self, instance: &Instance) {}
+
+    fn destroy_with_device(&self, device: &LogicalDevice) {
+        for buffer in &self.buffers {
+            buffer.destroy_with_device(device);
+        }
+        for (index_buffer, _) in &self.index_buffers {
+            index_buffer.destroy_with_device(device);
+        }
+    }
+}
+
+/// Creates a host visible buffer holding `data`.
+fn upload(
+    instance: &Instance,
+    physical_device: PhysicalDevice,
+    device: &LogicalDevice,
+    data: &[u8],
+    usage: BufferUsage,
+) -> Buffer {
+    let mut buffer = Buffer::new(instance, physical_device, device, data.len(), usage);
+    buffer.allocate_data(data.as_ptr() as *const c_void, device);
+    buffer.flush_memory(device).unwrap();
+    buffer.unmap_memory(device).unwrap();
+    buffer
+}
+
+/// Views a slice of plain data as its bytes.
+pub(crate) fn as_bytes<T: Copy>(data: &[T]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
+}
+
+/// # Tessellation
+/// Triangles generated on the CPU that have not been uploaded yet.
+///
+/// Render targets pack the tessellations of a frame into shared buffers, see [`IntoPath::fill_tessellation`].
+pub struct Tessellation {
+    pub(crate) index_buffers: Vec<IndexBuffer>,
+}
+
+/// # VertexData
+/// The vertex read by the default shaders: a position in the xy plane and an RGBA color.
+///
+/// Only `x` and `y` of `pos` are read; see [`Vertex`](crate::Vertex) for other formats.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VertexData {
+    pub pos: Vec4<f32>,
+    pub color: Vec4<f32>,
+}
+
+impl VertexData {
+    pub fn new(position: Vec2<f32>, color: Vec4<f32>) -> Self {
+        Self {
+            pos: Vec4::new(position.x, position.y, 0.0, 1.0),
+            color,
+        }
+    }
+}
+
+/// # InstanceData
+/// The data of one instance of an instanced draw, see [`BatchedRenderTarget::fill_instanced`](crate::BatchedRenderTarget::fill_instanced).
+///
+/// The default shaders apply `transform` to the path before the transform of the draw and
+/// multiply the vertex colors by `color`. `custom` is passed to the vertex shader at location 7
+/// for user shaders.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InstanceData {
+    pub transform: Mat4<f32>,
+    pub color: Vec4<f32>,
+    pub custom: Vec4<f32>,
+}
+
+impl InstanceData {
+    pub fn new(transform: Transform, color: impl Into<Vec4<f32>>) -> Self {
+        Self {
+            transform: transform.matrix(),
+            color: color.into(),
+            custom: Vec4::zeros(),
+        }
+    }
+
+    /// Sets the data passed to user shaders.
+    pub fn custom(mut self, custom: Vec4<f32>) -> Self {
+        self.custom = custom;
+        self
+    }
+}
+
+impl Default for InstanceData {
+    /// Draws the path unchanged.
+    fn default() -> Self {
+        Self::new(Transform::identity(), Vec4::new(1.0, 1.0, 1.0, 1.0))
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone, Default)]
+pub(crate) struct IndexBuffer {
+    pub(crate) data: Vec<VertexData>,
+    pub(crate) indices: Vec<u32>,
+}
+
+impl IndexBuffer {
+    pub(crate) fn push_vertex(&mut self, vertex: VertexData) -> u32 {
+        self.data.push(vertex);
+        (self.data.len() - 1) as u32
+    }
+
+    /// Adds a triangle, flipping its winding if needed so that it is not culled as a back face.
+    pub(crate) fn push_triangle(&mut self, a: u32, b: u32, c: u32) {
+        let (pa, pb, pc) = (
+            self.data[a as usize].pos,
+            self.data[b as usize].pos,
+            self.data[c as usize].pos,
+        );
+        let area = (pb.x - pa.x) * (pc.y - pa.y) - (pc.x - pa.x) * (pb.y - pa.y);
+        if area < 0.0 {
+            self.indices.extend_from_slice(&[a, c, b]);
+        } else {
+            self.indices.extend_from_slice(&[a, b, c]);
+        }
+    }
+}
+
+/// A flattened polyline, used to generate stroke geometry.
+#[doc(hidden)]
+pub(crate) struct Outline {
+    pub(crate) points: Vec<VertexData>,
+    pub(crate) closed: bool,
+}
+
+#[doc(hidden)]
+pub(crate) struct Mvp {
+    pub(crate) model: Mat4<f32>,
+    pub(crate) view: Mat4<f32>,
+    pub(crate) projection: Mat4<f32>,
+}
+
+impl Mvp {
+    pub fn new(model: Mat4<f32>, view: Mat4<f32>, projection: Mat4<f32>) -> Self {
+        Self {
+            model,
+            view,
+            projection,
+        }
+    }
+}
+
+impl Default for Mvp {
+    fn default() -> Self {
+        Self::new(identity(1.0), identity(1.0), identity(1.0))
+    }
+}
+
+/// # PathGeometry
+/// Represents complex shapes that can be represented by rectangles, circles, and other figures.
+///
+/// Besides the fixed shapes, arbitrary contours made of lines and Bézier curves can be built.
+/// ## Example
+/// ```no_run
+/// use vulx::{geometry::PathGeometry, Vec2, Vec4};
+/// let mut path = PathGeometry::new();
+/// path.color(Vec4::new(1.0, 0.0, 0.0, 1.0));
+/// path.move_to(Vec2::new(100.0, 100.0));
+/// path.quad_to(Vec2::new(200.0, 0.0), Vec2::new(300.0, 100.0));
+/// path.cubic_to(Vec2::new(300.0, 200.0), Vec2::new(100.0, 200.0), Vec2::new(100.0, 100.0));
+/// path.close();
+/// ```
+pub struct PathGeometry {
+    index_buffer: Vec<IndexBuffer>,
+    outlines: Vec<Outline>,
+    figures: Vec<Figure>,
+    color: Vec4<f32>,
+    fill_rule: FillRule,
+    tolerance: f32,
+    scale: f32,
+    stroke_style: StrokeStyle,
+    anti_alias: bool,
+}
+
+impl PathGeometry {
+    pub fn new() -> Self {
+        Self {
+            index_buffer: vec![],
+            outlines: vec![],
+            figures: vec![],
+            color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            fill_rule: FillRule::default(),
+            tolerance: DEFAULT_TOLERANCE,
+            scale: 1.0,
+            stroke_style: StrokeStyle::default(),
+            anti_alias: false,
+        }
+    }
+
+    /// Specifies the color of the contours built after this call.
+    pub fn color(&mut self, color: impl Into<Vec4<f32>>) {
+        self.color = color.into();
+    }
+
+    /// Specifies how overlapping contours built after this call are filled.
+    /// Contours sharing a color and fill rule are filled as one shape, so holes can be cut out.
+    pub fn fill_rule(&mut self, fill_rule: FillRule) {
+        self.fill_rule = fill_rule;
+    }
+
+    /// Specifies the maximum distance, in pixels, between a curve, or a round join or cap of a stroke,
+    /// and the lines that approximate it.
+    /// Smaller values produce smoother curves with more vertices.
+    pub fn tolerance(&mut self, tolerance: f32) {
+        self.tolerance = tolerance.max(f32::EPSILON);
+    }
+
+    /// Adapts the number of segments of curves, circles and arcs to how large they appear on the stage.
+    pub fn fit_to_stage(&mut self, stage: &Stage) {
+        self.pixel_scale(stage.pixel_scale());
+    }
+
+    /// Specifies how many pixels one unit of geometry covers.
+    pub(crate) fn pixel_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    /// Smooths the edges of fills and strokes without multisampling.
+    ///
+    /// A fringe one pixel wide is added around the geometry that fades to transparent, so the
+    /// geometry must be drawn with alpha blending, as render targets do. Fills appear about half
+    /// a pixel larger, while strokes are narrowed to keep their thickness.
+    /// The pixel size is the one set with [`PathGeometry::fit_to_stage`].
+    pub fn anti_alias(&mut self, anti_alias: bool) {
+        self.anti_alias = anti_alias;
+    }
+
+    /// Flattening tolerance in path units.
+    fn path_tolerance(&self) -> f32 {
+        self.tolerance / self.scale
+    }
+
+    /// The width of a pixel in path units.
+    fn pixel_width(&self) -> f32 {
+        1.0 / self.scale
+    }
+
+    /// Starts a new contour at `point`.
+    pub fn move_to(&mut self, point: Vec2<f32>) {
+        self.push_command(PathCommand::MoveTo(point));
+    }
+
+    /// Adds a straight line from the current point to `point`.
+    pub fn line_to(&mut self, point: Vec2<f32>) {
+        self.push_command(PathCommand::LineTo(point));
+    }
+
+    /// Adds a quadratic Bézier curve from the current point to `point`.
+    pub fn quad_to(&mut self, control: Vec2<f32>, point: Vec2<f32>) {
+        self.push_command(PathCommand::QuadTo(control, point));
+    }
+
+    /// Adds a cubic Bézier curve from the current point to `point`.
+    pub fn cubic_to(&mut self, control1: Vec2<f32>, control2: Vec2<f32>, point: Vec2<f32>) {
+        self.push_command(PathCommand::CubicTo(control1, control2, point));
+    }
+
+    /// Closes the current contour with a straight line back to its starting point.
+    pub fn close(&mut self) {
+        self.push_command(PathCommand::Close);
+    }
+
+    /// Adds a closed polygon. It may be concave or intersect itself.
+    pub fn polygon(&mut self, points: &[Vec2<f32>]) {
+        if let Some((first, rest)) = points.split_first() {
+            self.move_to(*first);
+            for point in rest {
+                self.line_to(*point);
+            }
+            self.close();
+        }
+    }
+
+    fn push_command(&mut self, command: PathCommand) {
+        match self.figures.last_mut() {
+            Some(figure) if figure.color == self.color && figure.fill_rule == self.fill_rule => {
+                figure.commands.push(command)
+            }
+            _ => {
+                let mut figure = Figure::new(self.color, self.fill_rule);
+                figure.commands.push(command);
+                self.figures.push(figure);
+            }
+        }
+    }
+
+    /// Specifies the joins, caps and miter limit used when this geometry is stroked.
+    pub fn stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+
+    /// Draws a triangle.
+    pub fn triangle(&mut self, vert: Vec3<Vec4<f32>>, color: Vec3<Vec4<f32>>) {
+        let index_buffer = IndexBuffer {
+            data: vec![
+                VertexData {
+                    pos: vert[0],
+                    color: color[0],
+                },
+                VertexData {
+                    pos: vert[1],
+                    color: color[1],
+                },
+                VertexData {
+                    pos: vert[2],
+                    color: color[2],
+                },
+            ],
+            indices: vec![0, 1, 2],
+        };
+        self.outline(&index_buffer, &[0, 1, 2]);
+        self.index_buffer.push(index_buffer);
+    }
+
+    /// Draws a rectangle.
+    pub fn rectangle(&mut self, vert: Vec4<Vec4<f32>>, color: Vec4<Vec4<f32>>) {
+        let index_buffer = IndexBuffer {
+            data: vec![
+                VertexData {
+                    pos: vert[0],
+                    color: color[0],
+                },
+                VertexData {
+                    pos: vert[1],
+                    color: color[1],
+                },
+                VertexData {
+                    pos: vert[2],
+                    color: color[2],
+                },
+                VertexData {
+                    pos: vert[3],
+                    color: color[3],
+                },
+            ],
+            indices: vec![0, 1, 2, 1, 0, 3],
+        };
+        self.outline(&index_buffer, &[0, 2, 1, 3]);
+        self.index_buffer.push(index_buffer);
+    }
+
+    fn outline(&mut self, index_buffer: &IndexBuffer, order: &[usize]) {
+        self.outlines.push(Outline {
+            points: order.iter().map(|i| index_buffer.data[*i]).collect(),
+            closed: true,
+        });
+    }
+
+    /// Get the number of vertices.
+    pub fn size(&self) -> usize {
+        let mut size = 0;
+        for i in &self.index_buffer {
+            size += i.data.len();
+        }
+        size += self.fill_figures().data.len();
+        if self.anti_alias {
+            size += self.fill_fringe().data.len();
+        }
+        size
+    }
+
+    /// Tessellates the contours built with `move_to`, `line_to`, etc.
+    fn fill_figures(&self) -> IndexBuffer {
+        let mut index_buffer = IndexBuffer::default();
+        for figure in &self.figures {
+            let outlines = figure.flatten(self.path_tolerance());
+            fill_outlines(&outlines, figure.fill_rule, figure.color, &mut index_buffer);
+        }
+        index_buffer
+    }
+
+    /// Feathers the edges of the shapes and of the contours built with `move_to`, `line_to`, etc.
+    fn fill_fringe(&self) -> IndexBuffer {
+        let mut index_buffer = IndexBuffer::default();
+        for outline in self.outlines.iter().filter(|o| o.closed) {
+            feather::feather_outlines(
+                std::slice::from_ref(outline),
+                FillRule::NonZero,
+                self.pixel_width(),
+                &mut index_buffer,
+            );
+        }
+        for figure in &self.figures {
+            let outlines = figure.flatten(self.path_tolerance());
+            feather::feather_outlines(
+                &outlines,
+                figure.fill_rule,
+                self.pixel_width(),
+                &mut index_buffer,
+            );
+        }
+        index_buffer
+    }
+
+    /// Strokes the outline, narrowed by a pixel that the feathered edges make up for.
+    /// Strokes thinner than that are kept wider but fainter.
+    fn stroke_feathered(&self, outline: &Outline, thickness: f32, out: &mut IndexBuffer) {
+        let width = self.pixel_width();
+        let core = (thickness - width).max(width * 0.5);
+        let mut stroke = IndexBuffer::default();
+        stroke_outline(
+            outline,
+            core,
+            &self.stroke_style,
+            self.path_tolerance(),
+            &mut stroke,
+        );
+        feather::feather_outlines(
+            &feather::triangle_outlines(&stroke),
+            FillRule::NonZero,
+            width,
+            &mut stroke,
+        );
+        let coverage = (thickness / (core + width)).min(1.0);
+        let first = out.data.len() as u32;
+        out.data.extend(stroke.data.into_iter().map(|mut vertex| {
+            vertex.color.w *= coverage;
+            vertex
+        }));
+        out.indices
+            .extend(stroke.indices.into_iter().map(|index| index + first));
+    }
+
+    /// Moves every point of the geometry, including curve control points, with `f`.
+    pub(crate) fn map_points(&mut self, f: impl Fn(Vec2<f32>) -> Vec2<f32>) {
+        let map_vertex = |vertex: &mut VertexData| {
+            let p = f(Vec2::new(vertex.pos.x, vertex.pos.y));
+            vertex.pos.x = p.x;
+            vertex.pos.y = p.y;
+        };
+        for index_buffer in &mut self.index_buffer {
+            index_buffer.data.iter_mut().for_each(map_vertex);
+        }
+        for outline in &mut self.outlines {
+            outline.points.iter_mut().for_each(map_vertex);
+        }
+        for command in self.figures.iter_mut().flat_map(|f| &mut f.commands) {
+            *command = match *command {
+                PathCommand::MoveTo(p) => PathCommand::MoveTo(f(p)),
+                PathCommand::LineTo(p) => PathCommand::LineTo(f(p)),
+                PathCommand::QuadTo(c, p) => PathCommand::QuadTo(f(c), f(p)),
+                PathCommand::CubicTo(c1, c2, p) => PathCommand::CubicTo(f(c1), f(c2), f(p)),
+                PathCommand::Close => PathCommand::Close,
+            };
+        }
+    }
+}
+
+impl Default for PathGeometry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoPath for PathGeometry {
+    fn into_path(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+    ) -> Path {
+        let tessellation = self.fill_tessellation().unwrap();
+        Path::new(
+            instance,
+            physical_device,
+            device,
+            &tessellation.index_buffers,
+        )
+    }
+
+    fn into_stroke_path(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        thickness: f64,
+    ) -> Path {
+        let tessellation = self.stroke_tessellation(thickness).unwrap();
+        Path::new(
+            instance,
+            physical_device,
+            device,
+            &tessellation.index_buffers,
+        )
+    }
+
+    fn fill_tessellation(&mut self) -> Option<Tessellation> {
+        let mut index_buffers = self.index_buffer.clone();
+        index_buffers.push(self.fill_figures());
+        if self.anti_alias {
+            index_buffers.push(self.fill_fringe());
+        }
+        Some(Tessellation { index_buffers })
+    }
+
+    fn stroke_tessellation(&mut self, thickness: f64) -> Option<Tessellation> {
+        let mut index_buffer = IndexBuffer::default();
+        let flattened = self
+            .figures
+            .iter()
+            .flat_map(|figure| figure.flatten(self.path_tolerance()))
+            .collect::<Vec<_>>();
+        for outline in self.outlines.iter().chain(&flattened) {
+            if self.anti_alias {
+                self.stroke_feathered(outline, thickness as f32, &mut index_buffer);
+            } else {
+                stroke_outline(
+                    outline,
+                    thickness as f32,
+                    &self.stroke_style,
+                    self.path_tolerance(),
+                    &mut index_buffer,
+                );
+            }
+        }
+        Some(Tessellation {
+            index_buffers: vec![index_buffer],
+        })
+    }
+}
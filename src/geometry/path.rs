@@ -0,0 +1,147 @@
+use crate::{Vec2, Vec4};
+
+use super::{FillRule, Outline, VertexData};
+
+/// Default maximum distance between a curve and its flattened polyline, in pixels.
+pub(crate) const DEFAULT_TOLERANCE: f32 = 0.25;
+
+/// A single drawing command of a path.
+///
+/// # Value Meaning
+/// * `MoveTo` - Starts a new contour at the point.
+/// * `LineTo` - Straight line to the point.
+/// * `QuadTo` - Quadratic Bézier curve with one control point.
+/// * `CubicTo` - Cubic Bézier curve with two control points.
+/// * `Close` - Closes the current contour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Vec2<f32>),
+    LineTo(Vec2<f32>),
+    QuadTo(Vec2<f32>, Vec2<f32>),
+    CubicTo(Vec2<f32>, Vec2<f32>, Vec2<f32>),
+    Close,
+}
+
+/// A group of contours that are filled together with one color and fill rule.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub(crate) struct Figure {
+    pub(crate) commands: Vec<PathCommand>,
+    pub(crate) color: Vec4<f32>,
+    pub(crate) fill_rule: FillRule,
+}
+
+impl Figure {
+    pub(crate) fn new(color: Vec4<f32>, fill_rule: FillRule) -> Self {
+        Self {
+            commands: vec![],
+            color,
+            fill_rule,
+        }
+    }
+
+    /// Converts the commands into polylines.
+    /// Curves are subdivided so that they deviate from the polyline by at most `tolerance`.
+    pub(crate) fn flatten(&self, tolerance: f32) -> Vec<Outline> {
+        let mut outlines = vec![];
+        let mut points: Vec<Vec2<f32>> = vec![];
+        let mut start = Vec2::new(0.0, 0.0);
+        let mut current = start;
+
+        let mut finish = |points: &mut Vec<Vec2<f32>>, closed: bool| {
+            if points.len() > 1 || (points.len() == 1 && closed) {
+                outlines.push(Outline {
+                    points: points.iter().map(|p| self.vertex(*p)).collect(),
+                    closed,
+                });
+            }
+            points.clear();
+        };
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(p) => {
+                    finish(&mut points, false);
+                    points.push(p);
+                    start = p;
+                    current = p;
+                    continue;
+                }
+                PathCommand::Close => {
+                    finish(&mut points, true);
+                    current = start;
+                    continue;
+                }
+                _ => {}
+            }
+
+            if points.is_empty() {
+                points.push(current);
+                start = current;
+            }
+            match *command {
+                PathCommand::LineTo(p) => {
+                    points.push(p);
+                    current = p;
+                }
+                PathCommand::QuadTo(c, p) => {
+                    flatten_quad(current, c, p, tolerance, &mut points);
+                    current = p;
+                }
+                PathCommand::CubicTo(c1, c2, p) => {
+                    flatten_cubic(current, c1, c2, p, tolerance, &mut points);
+                    current = p;
+                }
+                PathCommand::MoveTo(_) | PathCommand::Close => unreachable!(),
+            }
+        }
+        finish(&mut points, false);
+
+        outlines
+    }
+
+    fn vertex(&self, p: Vec2<f32>) -> VertexData {
+        VertexData {
+            pos: Vec4::new(p.x, p.y, 0.0, 1.0),
+            color: self.color,
+        }
+    }
+}
+
+fn flatten_quad(
+    p0: Vec2<f32>,
+    p1: Vec2<f32>,
+    p2: Vec2<f32>,
+    tolerance: f32,
+    out: &mut Vec<Vec2<f32>>,
+) {
+    let dd = (p0 - p1 * 2.0 + p2).norm();
+    let segments = ((dd / (4.0 * tolerance)).sqrt().ceil() as usize).max(1);
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+        out.push(p0 * (mt * mt) + p1 * (2.0 * mt * t) + p2 * (t * t));
+    }
+}
+
+fn flatten_cubic(
+    p0: Vec2<f32>,
+    p1: Vec2<f32>,
+    p2: Vec2<f32>,
+    p3: Vec2<f32>,
+    tolerance: f32,
+    out: &mut Vec<Vec2<f32>>,
+) {
+    let dd = (p0 - p1 * 2.0 + p2).norm().max((p1 - p2 * 2.0 + p3).norm());
+    let segments = ((3.0 * dd / (4.0 * tolerance)).sqrt().ceil() as usize).max(1);
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+        out.push(
+            p0 * (mt * mt * mt)
+                + p1 * (3.0 * mt * mt * t)
+                + p2 * (3.0 * mt * t * t)
+                + p3 * (t * t * t),
+        );
+    }
+}
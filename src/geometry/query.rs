@@ -0,0 +1,193 @@
+use crate::Vec2;
+
+use super::{FillRule, Outline, PathGeometry};
+
+/// How much finer than for drawing curves are flattened to measure their length.
+const LENGTH_REFINEMENT: f32 = 64.0;
+
+/// # Bounds
+/// An axis-aligned rectangle given by its minimum and maximum corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Vec2<f32>,
+    pub max: Vec2<f32>,
+}
+
+impl Bounds {
+    pub fn size(&self) -> Vec2<f32> {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec2<f32> {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Returns true if `point` lies inside or on the border.
+    pub fn contains(&self, point: Vec2<f32>) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+}
+
+impl PathGeometry {
+    /// Returns the smallest rectangle enclosing all contours and shapes, or `None` if the geometry is empty.
+    ///
+    /// Curves are measured on their flattened polyline, so the bounds are tight rather than
+    /// enclosing the control points.
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut points = self
+            .all_outlines(self.path_tolerance())
+            .into_iter()
+            .flat_map(|o| o.points)
+            .map(|v| Vec2::new(v.pos.x, v.pos.y));
+        let first = points.next()?;
+        Some(points.fold(
+            Bounds {
+                min: first,
+                max: first,
+            },
+            |b, p| Bounds {
+                min: b.min.inf(&p),
+                max: b.max.sup(&p),
+            },
+        ))
+    }
+
+    /// Returns true if `point` lies inside the filled area of the geometry under `fill_rule`.
+    /// All contours are treated as one shape.
+    pub fn contains(&self, point: Vec2<f32>, fill_rule: FillRule) -> bool {
+        let outlines = self.all_outlines(self.path_tolerance());
+        outlines_contain(&outlines, point, fill_rule)
+    }
+
+    /// Returns true if `point` lies on the stroke of the given width.
+    ///
+    /// Joins and caps are treated as round, so points just outside sharp miter corners or
+    /// butt ends may also be reported.
+    pub fn stroke_contains(&self, point: Vec2<f32>, width: f32) -> bool {
+        let half = width / 2.0;
+        self.all_outlines(self.path_tolerance())
+            .iter()
+            .any(|outline| segments(outline).any(|(a, b)| distance_to_segment(point, a, b) <= half))
+    }
+
+    /// Returns the total length of all contours, including the closing segment of closed ones.
+    pub fn length(&self) -> f32 {
+        // Chords are shorter than the curves they approximate.
+        self.all_outlines(self.path_tolerance() / LENGTH_REFINEMENT)
+            .iter()
+            .flat_map(segments)
+            .map(|(a, b)| (b - a).norm())
+            .sum()
+    }
+
+    /// Rasterizes the signed distance to the outlines into `width` by `height` bytes, one per pixel,
+    /// with the center of the first pixel at `origin` + 0.5.
+    ///
+    /// A byte of 128 lies on an outline; greater values are inside under `fill_rule`. Distances
+    /// beyond `spread` pixels are clamped to 0 or 255.
+    pub(crate) fn signed_distance_field(
+        &self,
+        origin: Vec2<f32>,
+        width: usize,
+        height: usize,
+        spread: f32,
+        fill_rule: FillRule,
+    ) -> Vec<u8> {
+        let outlines = self.all_outlines(self.path_tolerance());
+        let mut field = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let point = origin + Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let distance = outlines
+                    .iter()
+                    .flat_map(|outline| {
+                        segments_closed(outline).map(|(a, b)| distance_to_segment(point, a, b))
+                    })
+                    .fold(f32::INFINITY, f32::min);
+                let signed = if outlines_contain(&outlines, point, fill_rule) {
+                    distance
+                } else {
+                    -distance
+                };
+                let value = 0.5 + signed / (2.0 * spread);
+                field.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+        field
+    }
+
+    /// Flattened outlines of both the fixed shapes and the contours.
+    fn all_outlines(&self, tolerance: f32) -> Vec<Outline> {
+        let mut outlines: Vec<Outline> = self
+            .outlines
+            .iter()
+            .map(|o| Outline {
+                points: o.points.clone(),
+                closed: o.closed,
+            })
+            .collect();
+        for figure in &self.figures {
+            outlines.extend(figure.flatten(tolerance));
+        }
+        outlines
+    }
+}
+
+/// Returns true if `point` lies inside the flattened outlines under `fill_rule`.
+fn outlines_contain(outlines: &[Outline], point: Vec2<f32>, fill_rule: FillRule) -> bool {
+    let mut winding = 0;
+    for outline in outlines {
+        let points = &outline.points;
+        for i in 0..points.len() {
+            let a = points[i].pos;
+            let b = points[(i + 1) % points.len()].pos;
+            let side = (b.x - a.x) * (point.y - a.y) - (point.x - a.x) * (b.y - a.y);
+            if a.y <= point.y && b.y > point.y && side > 0.0 {
+                winding += 1;
+            } else if b.y <= point.y && a.y > point.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    fill_rule.is_inside(winding)
+}
+
+/// Iterates over the segments of an outline. A single point yields one segment of zero length.
+fn segments(outline: &Outline) -> impl Iterator<Item = (Vec2<f32>, Vec2<f32>)> + '_ {
+    let points = &outline.points;
+    let count = match points.len() {
+        0 => 0,
+        1 => 1,
+        n if outline.closed => n,
+        n => n - 1,
+    };
+    (0..count).map(move |i| {
+        let a = points[i].pos;
+        let b = points[(i + 1) % points.len()].pos;
+        (Vec2::new(a.x, a.y), Vec2::new(b.x, b.y))
+    })
+}
+
+/// Iterates over the segments of an outline as a filled area sees it, always closed.
+fn segments_closed(outline: &Outline) -> impl Iterator<Item = (Vec2<f32>, Vec2<f32>)> + '_ {
+    let points = &outline.points;
+    (0..points.len()).map(move |i| {
+        let a = points[i].pos;
+        let b = points[(i + 1) % points.len()].pos;
+        (Vec2::new(a.x, a.y), Vec2::new(b.x, b.y))
+    })
+}
+
+fn distance_to_segment(p: Vec2<f32>, a: Vec2<f32>, b: Vec2<f32>) -> f32 {
+    let ab = b - a;
+    let length = ab.norm_squared();
+    let t = if length > 0.0 {
+        ((p - a).dot(&ab) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p - (a + ab * t)).norm()
+}
@@ -0,0 +1,142 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::{Vec2, Vec4};
+
+use super::PathGeometry;
+
+/// Specifies how the ends of an arc are connected.
+///
+/// # Value Meaning
+/// * `Open` - Only the curve itself. Filling it behaves like `Chord`.
+/// * `Chord` - The ends are connected with a straight line.
+/// * `Pie` - The ends are connected to the center, like a pie chart slice.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum ArcKind {
+    #[default]
+    Open,
+    Chord,
+    Pie,
+}
+
+impl PathGeometry {
+    /// Adds a circle.
+    pub fn circle(&mut self, center: Vec2<f32>, radius: f32) {
+        self.ellipse(center, Vec2::new(radius, radius));
+    }
+
+    /// Adds an axis-aligned ellipse with the given horizontal and vertical radii.
+    pub fn ellipse(&mut self, center: Vec2<f32>, radii: Vec2<f32>) {
+        self.move_to(center + Vec2::new(radii.x, 0.0));
+        for (c1, c2, p) in arc_to_cubics(center, radii, 0.0, 0.0, TAU) {
+            self.cubic_to(c1, c2, p);
+        }
+        self.close();
+    }
+
+    /// Adds an elliptical arc.
+    ///
+    /// Angles are in radians, measured from the positive x axis towards the positive y axis.
+    /// A negative `sweep` draws the arc in the opposite direction.
+    pub fn arc(
+        &mut self,
+        center: Vec2<f32>,
+        radii: Vec2<f32>,
+        start: f32,
+        sweep: f32,
+        kind: ArcKind,
+    ) {
+        let sweep = sweep.clamp(-TAU, TAU);
+        let from = center + Vec2::new(radii.x * start.cos(), radii.y * start.sin());
+        match kind {
+            ArcKind::Pie => {
+                self.move_to(center);
+                self.line_to(from);
+            }
+            ArcKind::Open | ArcKind::Chord => self.move_to(from),
+        }
+        for (c1, c2, p) in arc_to_cubics(center, radii, 0.0, start, sweep) {
+            self.cubic_to(c1, c2, p);
+        }
+        if kind != ArcKind::Open {
+            self.close();
+        }
+    }
+
+    /// Adds a rectangle with rounded corners.
+    ///
+    /// `radii` holds the radius of each corner in the order top-left, top-right, bottom-right, bottom-left.
+    /// Radii that do not fit into the rectangle are scaled down proportionally.
+    pub fn rounded_rectangle(&mut self, position: Vec2<f32>, size: Vec2<f32>, radii: Vec4<f32>) {
+        let radii = radii.map(|r| r.max(0.0));
+        let mut scale: f32 = 1.0;
+        for (sum, side) in [
+            (radii[0] + radii[1], size.x),
+            (radii[3] + radii[2], size.x),
+            (radii[0] + radii[3], size.y),
+            (radii[1] + radii[2], size.y),
+        ] {
+            if sum > side {
+                scale = scale.min(side / sum);
+            }
+        }
+        let [tl, tr, br, bl] = [radii[0], radii[1], radii[2], radii[3]].map(|r| r * scale);
+
+        let (left, top) = (position.x, position.y);
+        let (right, bottom) = (position.x + size.x, position.y + size.y);
+        let corners = [
+            (Vec2::new(right - tr, top + tr), tr, -FRAC_PI_2),
+            (Vec2::new(right - br, bottom - br), br, 0.0),
+            (Vec2::new(left + bl, bottom - bl), bl, FRAC_PI_2),
+            (Vec2::new(left + tl, top + tl), tl, FRAC_PI_2 * 2.0),
+        ];
+
+        self.move_to(Vec2::new(left + tl, top));
+        for (center, radius, start) in corners {
+            let from = center + Vec2::new(radius * start.cos(), radius * start.sin());
+            self.line_to(from);
+            if radius > 0.0 {
+                for (c1, c2, p) in
+                    arc_to_cubics(center, Vec2::new(radius, radius), 0.0, start, FRAC_PI_2)
+                {
+                    self.cubic_to(c1, c2, p);
+                }
+            }
+        }
+        self.close();
+    }
+}
+
+/// Approximates an elliptical arc with cubic Bézier curves of at most a quarter turn each.
+///
+/// `rotation` rotates the ellipse axes. Returns `(control1, control2, end)` for every curve.
+pub(crate) fn arc_to_cubics(
+    center: Vec2<f32>,
+    radii: Vec2<f32>,
+    rotation: f32,
+    start: f32,
+    sweep: f32,
+) -> Vec<(Vec2<f32>, Vec2<f32>, Vec2<f32>)> {
+    let count = ((sweep.abs() / FRAC_PI_2).ceil() as usize).max(1);
+    let step = sweep / count as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let (sin_r, cos_r) = rotation.sin_cos();
+    let point = |x: f32, y: f32| {
+        let (x, y) = (x * radii.x, y * radii.y);
+        center + Vec2::new(x * cos_r - y * sin_r, x * sin_r + y * cos_r)
+    };
+
+    let mut curves = Vec::with_capacity(count);
+    let mut angle = start;
+    for _ in 0..count {
+        let next = angle + step;
+        let (sin0, cos0) = angle.sin_cos();
+        let (sin1, cos1) = next.sin_cos();
+        curves.push((
+            point(cos0 - k * sin0, sin0 + k * cos0),
+            point(cos1 + k * sin1, sin1 - k * cos1),
+            point(cos1, sin1),
+        ));
+        angle = next;
+    }
+    curves
+}
@@ -0,0 +1,457 @@
+use crate::{Vec2, Vec4};
+
+use super::{
+    feather::triangle_outlines, fill_outlines, FillRule, IndexBuffer, Outline, VertexData,
+};
+
+/// Specifies how the corners between two stroked segments are drawn.
+///
+/// # Value Meaning
+/// * `Miter` - Extends the outer edges until they meet. Falls back to `Bevel` past the miter limit.
+/// * `Round` - Rounds the corner with an arc.
+/// * `Bevel` - Cuts the corner off with a straight edge.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Specifies how the ends of an open stroke are drawn.
+///
+/// # Value Meaning
+/// * `Butt` - The stroke ends exactly at the end point.
+/// * `Round` - A half circle is added at the end point.
+/// * `Square` - The stroke is extended by half of its thickness.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+/// # StrokeStyle
+/// Describes how an outline is turned into stroke geometry.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::geometry::{LineCap, LineJoin, StrokeStyle};
+/// let style = StrokeStyle::new()
+///     .line_join(LineJoin::Round)
+///     .line_cap(LineCap::Square)
+///     .miter_limit(4.0)
+///     .dash_array(&[6.0, 3.0])
+///     .dash_offset(1.5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub(crate) join: LineJoin,
+    pub(crate) cap: LineCap,
+    pub(crate) miter_limit: f32,
+    pub(crate) dash_array: Vec<f32>,
+    pub(crate) dash_offset: f32,
+}
+
+impl StrokeStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn line_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn line_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Limit of the ratio between the miter length and the stroke thickness.
+    /// Miter joins exceeding it are drawn as bevel joins.
+    pub fn miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit.max(1.0);
+        self
+    }
+
+    /// Lengths of alternating dashes and gaps, starting with a dash.
+    /// An odd number of lengths is repeated to yield an even number, as in SVG.
+    /// An empty array, or one with negative lengths or a zero sum, draws a solid stroke.
+    ///
+    /// Each dash gets the line caps of the style, so `&[0.0, gap]` with round caps draws dots.
+    pub fn dash_array(mut self, dash_array: &[f32]) -> Self {
+        self.dash_array = dash_array.to_vec();
+        if self.dash_array.len() % 2 == 1 {
+            self.dash_array.extend_from_slice(dash_array);
+        }
+        self
+    }
+
+    /// Distance into the dash pattern at which the stroke starts.
+    pub fn dash_offset(mut self, dash_offset: f32) -> Self {
+        self.dash_offset = dash_offset;
+        self
+    }
+
+    fn is_dashed(&self) -> bool {
+        !self.dash_array.is_empty()
+            && self.dash_array.iter().all(|d| d.is_finite() && *d >= 0.0)
+            && self.dash_array.iter().sum::<f32>() > 0.0
+    }
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            miter_limit: 4.0,
+            dash_array: vec![],
+            dash_offset: 0.0,
+        }
+    }
+}
+
+/// Appends the stroke of the outline to `out`.
+///
+/// `tolerance` is the maximum distance between a round join or cap and its polygonal
+/// approximation, in path units. The segments, joins and caps overlap each other, so the stroke
+/// is filled as their union. Its triangles do not overlap, and a translucent stroke is not
+/// darker at its joins or where it crosses itself.
+#[doc(hidden)]
+pub(crate) fn stroke_outline(
+    outline: &Outline,
+    thickness: f32,
+    style: &StrokeStyle,
+    tolerance: f32,
+    out: &mut IndexBuffer,
+) {
+    let Some(first) = outline.points.first() else {
+        return;
+    };
+    let mut pieces = IndexBuffer::default();
+    if style.is_dashed() {
+        for dash in dash_outline(outline, &style.dash_array, style.dash_offset) {
+            stroke_contour(&dash, thickness, style, tolerance, &mut pieces);
+        }
+    } else {
+        stroke_contour(outline, thickness, style, tolerance, &mut pieces);
+    }
+    fill_outlines(
+        &triangle_outlines(&pieces),
+        FillRule::NonZero,
+        first.color,
+        out,
+    );
+}
+
+/// Splits the outline into the dashes of the pattern.
+///
+/// The pattern runs continuously over all segments of the outline, so dashes bend around corners
+/// and follow flattened curves. On a closed outline, a dash crossing the starting point is kept whole.
+pub(crate) fn dash_outline(outline: &Outline, pattern: &[f32], offset: f32) -> Vec<Outline> {
+    let mut points = outline.points.clone();
+    if outline.closed && points.len() > 1 {
+        points.push(points[0]);
+    }
+    let Some(first) = points.first() else {
+        return vec![];
+    };
+
+    let total: f32 = pattern.iter().sum();
+    let mut offset = offset.rem_euclid(total);
+    let mut index = 0;
+    // Stops at the start of a dash rather than skipping it, even if it has no length.
+    while offset > 0.0 && offset >= pattern[index] {
+        offset -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+    let mut remaining = pattern[index] - offset;
+    let starts_on = index % 2 == 0;
+    let mut on = starts_on;
+
+    let mut dashes = vec![];
+    let mut current = if on { vec![*first] } else { vec![] };
+    for segment in points.windows(2) {
+        let (a, b) = (xy(&segment[0]), xy(&segment[1]));
+        let length = (b - a).norm();
+        let mut distance = 0.0;
+        while length - distance > remaining {
+            distance += remaining;
+            let split = vertex(&segment[0], a + (b - a) * (distance / length));
+            if on {
+                current.push(split);
+                dashes.push(Outline {
+                    points: std::mem::take(&mut current),
+                    closed: false,
+                });
+            } else {
+                current = vec![split];
+            }
+            on = !on;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= length - distance;
+        if on {
+            current.push(segment[1]);
+        }
+    }
+    if on && !current.is_empty() {
+        if outline.closed && starts_on {
+            if dashes.is_empty() {
+                // The pattern never turned off, the outline stays solid.
+                return vec![Outline {
+                    points: outline.points.clone(),
+                    closed: true,
+                }];
+            }
+            // The last dash continues into the first one.
+            let first = dashes.remove(0);
+            current.extend(first.points.into_iter().skip(1));
+        }
+        dashes.push(Outline {
+            points: current,
+            closed: false,
+        });
+    }
+    dashes
+}
+
+fn stroke_contour(
+    outline: &Outline,
+    thickness: f32,
+    style: &StrokeStyle,
+    tolerance: f32,
+    out: &mut IndexBuffer,
+) {
+    let half = thickness / 2.0;
+    if half <= 0.0 {
+        return;
+    }
+
+    let mut points: Vec<VertexData> = Vec::with_capacity(outline.points.len());
+    for p in &outline.points {
+        match points.last() {
+            Some(last) if xy(last) == xy(p) => {}
+            _ => points.push(*p),
+        }
+    }
+    if outline.closed && points.len() > 1 && xy(&points[0]) == xy(&points[points.len() - 1]) {
+        points.pop();
+    }
+
+    match points.len() {
+        0 => return,
+        1 => {
+            stroke_dot(&points[0], half, style.cap, tolerance, out);
+            return;
+        }
+        _ => {}
+    }
+
+    let closed = outline.closed && points.len() > 2;
+    let count = points.len();
+    let segments = if closed { count } else { count - 1 };
+
+    for i in 0..segments {
+        let a = points[i];
+        let b = points[(i + 1) % count];
+        let dir = (xy(&b) - xy(&a)).normalize();
+        let normal = Vec2::new(-dir.y, dir.x) * half;
+
+        let mut start = xy(&a);
+        let mut end = xy(&b);
+        if !closed && style.cap == LineCap::Square {
+            if i == 0 {
+                start -= dir * half;
+            }
+            if i == segments - 1 {
+                end += dir * half;
+            }
+        }
+
+        let v0 = out.push_vertex(vertex(&a, start + normal));
+        let v1 = out.push_vertex(vertex(&b, end + normal));
+        let v2 = out.push_vertex(vertex(&b, end - normal));
+        let v3 = out.push_vertex(vertex(&a, start - normal));
+        out.push_triangle(v0, v1, v2);
+        out.push_triangle(v0, v2, v3);
+    }
+
+    let joins = if closed { 0..count } else { 1..count - 1 };
+    for i in joins {
+        let prev = points[(i + count - 1) % count];
+        let current = points[i];
+        let next = points[(i + 1) % count];
+        let d0 = (xy(&current) - xy(&prev)).normalize();
+        let d1 = (xy(&next) - xy(&current)).normalize();
+        stroke_join(&current, d0, d1, half, style, tolerance, out);
+    }
+
+    if !closed && style.cap == LineCap::Round {
+        let first = (xy(&points[1]) - xy(&points[0])).normalize();
+        let last = (xy(&points[count - 1]) - xy(&points[count - 2])).normalize();
+        let start_normal = Vec2::new(-first.y, first.x);
+        let end_normal = Vec2::new(-last.y, last.x);
+        stroke_arc(
+            &points[0],
+            start_normal,
+            -start_normal,
+            -first,
+            half,
+            tolerance,
+            out,
+        );
+        stroke_arc(
+            &points[count - 1],
+            -end_normal,
+            end_normal,
+            last,
+            half,
+            tolerance,
+            out,
+        );
+    }
+}
+
+fn stroke_join(
+    point: &VertexData,
+    d0: Vec2<f32>,
+    d1: Vec2<f32>,
+    half: f32,
+    style: &StrokeStyle,
+    tolerance: f32,
+    out: &mut IndexBuffer,
+) {
+    let cross = d0.perp(&d1);
+    let dot = d0.dot(&d1);
+    if cross.abs() < 1e-6 && dot > 0.0 {
+        return;
+    }
+
+    // The join is only needed on the outer side of the corner.
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+    let n0 = Vec2::new(-d0.y, d0.x) * side;
+    let n1 = Vec2::new(-d1.y, d1.x) * side;
+    let center = xy(point);
+
+    match style.join {
+        LineJoin::Round => {
+            let through = if (n0 + n1).norm() > 1e-6 {
+                (n0 + n1).normalize()
+            } else {
+                d0
+            };
+            stroke_arc(point, n0, n1, through, half, tolerance, out);
+        }
+        LineJoin::Miter if (n0 + n1).norm() > 1e-6 => {
+            let miter = (n0 + n1).normalize();
+            let ratio = 1.0 / miter.dot(&n0);
+            if ratio <= style.miter_limit {
+                let c = out.push_vertex(vertex(point, center));
+                let o0 = out.push_vertex(vertex(point, center + n0 * half));
+                let tip = out.push_vertex(vertex(point, center + miter * half * ratio));
+                let o1 = out.push_vertex(vertex(point, center + n1 * half));
+                out.push_triangle(c, o0, tip);
+                out.push_triangle(c, tip, o1);
+            } else {
+                stroke_bevel(point, n0, n1, half, out);
+            }
+        }
+        _ => stroke_bevel(point, n0, n1, half, out),
+    }
+}
+
+fn stroke_bevel(
+    point: &VertexData,
+    n0: Vec2<f32>,
+    n1: Vec2<f32>,
+    half: f32,
+    out: &mut IndexBuffer,
+) {
+    let center = xy(point);
+    let c = out.push_vertex(vertex(point, center));
+    let o0 = out.push_vertex(vertex(point, center + n0 * half));
+    let o1 = out.push_vertex(vertex(point, center + n1 * half));
+    out.push_triangle(c, o0, o1);
+}
+
+/// Fans an arc of radius `half` around `point`, sweeping from `from` to `to` through `through`.
+/// All three directions are expected to be unit vectors.
+fn stroke_arc(
+    point: &VertexData,
+    from: Vec2<f32>,
+    to: Vec2<f32>,
+    through: Vec2<f32>,
+    half: f32,
+    tolerance: f32,
+    out: &mut IndexBuffer,
+) {
+    let start = from.y.atan2(from.x);
+    let mut sweep = to.y.atan2(to.x) - start;
+    let middle = through.y.atan2(through.x) - start;
+    let middle = middle.rem_euclid(std::f32::consts::TAU);
+    sweep = sweep.rem_euclid(std::f32::consts::TAU);
+    // Sweep in the direction that passes through `through`.
+    if middle > sweep {
+        sweep -= std::f32::consts::TAU;
+    }
+
+    let steps = arc_segments(half, sweep.abs(), tolerance);
+    let center = xy(point);
+    let c = out.push_vertex(vertex(point, center));
+    let mut previous = out.push_vertex(vertex(point, center + from * half));
+    for step in 1..=steps {
+        let angle = start + sweep * step as f32 / steps as f32;
+        let next = out.push_vertex(vertex(
+            point,
+            center + Vec2::new(angle.cos(), angle.sin()) * half,
+        ));
+        out.push_triangle(c, previous, next);
+        previous = next;
+    }
+}
+
+fn stroke_dot(point: &VertexData, half: f32, cap: LineCap, tolerance: f32, out: &mut IndexBuffer) {
+    match cap {
+        LineCap::Butt => {}
+        LineCap::Round => {
+            let right = Vec2::new(1.0, 0.0);
+            let down = Vec2::new(0.0, 1.0);
+            stroke_arc(point, right, -right, down, half, tolerance, out);
+            stroke_arc(point, -right, right, -down, half, tolerance, out);
+        }
+        LineCap::Square => {
+            let center = xy(point);
+            let v0 = out.push_vertex(vertex(point, center + Vec2::new(-half, -half)));
+            let v1 = out.push_vertex(vertex(point, center + Vec2::new(half, -half)));
+            let v2 = out.push_vertex(vertex(point, center + Vec2::new(half, half)));
+            let v3 = out.push_vertex(vertex(point, center + Vec2::new(-half, half)));
+            out.push_triangle(v0, v1, v2);
+            out.push_triangle(v0, v2, v3);
+        }
+    }
+}
+
+/// Number of segments needed to approximate an arc within `tolerance`.
+pub(crate) fn arc_segments(radius: f32, sweep: f32, tolerance: f32) -> usize {
+    if radius <= tolerance {
+        return 1.max((sweep / std::f32::consts::FRAC_PI_2).ceil() as usize);
+    }
+    let step = 2.0 * (1.0 - tolerance / radius).acos();
+    ((sweep / step).ceil() as usize).max(1)
+}
+
+fn xy(v: &VertexData) -> Vec2<f32> {
+    Vec2::new(v.pos.x, v.pos.y)
+}
+
+fn vertex(source: &VertexData, pos: Vec2<f32>) -> VertexData {
+    VertexData {
+        pos: Vec4::new(pos.x, pos.y, source.pos.z, source.pos.w),
+        color: source.color,
+    }
+}
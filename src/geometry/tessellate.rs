@@ -0,0 +1,207 @@
+use crate::{Vec2, Vec4};
+
+use super::{IndexBuffer, Outline, VertexData};
+
+/// Specifies which areas of overlapping or self-intersecting contours are filled.
+///
+/// # Value Meaning
+/// * `NonZero` - A point is inside if the contours wind around it a non-zero number of times.
+/// * `EvenOdd` - A point is inside if a ray from it crosses the contours an odd number of times.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    pub(crate) fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+pub(crate) const EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Edge {
+    pub(crate) top: Vec2<f64>,
+    pub(crate) bottom: Vec2<f64>,
+    /// +1 if the contour goes downwards along this edge, -1 otherwise.
+    pub(crate) winding: i32,
+    /// The colors of the contour at `top` and `bottom`.
+    pub(crate) colors: [Vec4<f32>; 2],
+}
+
+impl Edge {
+    pub(crate) fn x_at(&self, y: f64) -> f64 {
+        let dy = self.bottom.y - self.top.y;
+        if dy.abs() < EPSILON {
+            return self.top.x;
+        }
+        self.top.x + (self.bottom.x - self.top.x) * (y - self.top.y) / dy
+    }
+
+    /// The color of the contour at height `y`, interpolated between the end points.
+    pub(crate) fn color_at(&self, y: f64) -> Vec4<f32> {
+        let dy = self.bottom.y - self.top.y;
+        if dy.abs() < EPSILON {
+            return self.colors[0];
+        }
+        let t = ((y - self.top.y) / dy).clamp(0.0, 1.0) as f32;
+        self.colors[0].lerp(&self.colors[1], t)
+    }
+}
+
+/// Collects the non-horizontal edges of the outlines. Every outline is closed implicitly.
+pub(crate) fn collect_edges(outlines: &[Outline]) -> Vec<Edge> {
+    let mut edges = vec![];
+    for outline in outlines {
+        let points = &outline.points;
+        for i in 0..points.len() {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            let colors = [a.color, b.color];
+            let (a, b) = (a.pos, b.pos);
+            let (a, b) = (
+                Vec2::new(a.x as f64, a.y as f64),
+                Vec2::new(b.x as f64, b.y as f64),
+            );
+            if (a.y - b.y).abs() < EPSILON {
+                continue;
+            }
+            if a.y < b.y {
+                edges.push(Edge {
+                    top: a,
+                    bottom: b,
+                    winding: 1,
+                    colors,
+                });
+            } else {
+                edges.push(Edge {
+                    top: b,
+                    bottom: a,
+                    winding: -1,
+                    colors: [colors[1], colors[0]],
+                });
+            }
+        }
+    }
+    edges.sort_by(|a, b| a.top.y.total_cmp(&b.top.y));
+    edges
+}
+
+/// Returns the sorted y coordinates at which the set of edges or their order changes,
+/// i.e. all end points and all crossings.
+fn scanlines(edges: &[Edge]) -> Vec<f64> {
+    let mut ys = vec![];
+    for (i, a) in edges.iter().enumerate() {
+        ys.push(a.top.y);
+        ys.push(a.bottom.y);
+        for b in &edges[i + 1..] {
+            if b.top.y > a.bottom.y {
+                break;
+            }
+            if let Some(y) = crossing(a, b) {
+                ys.push(y);
+            }
+        }
+    }
+    ys.sort_by(f64::total_cmp);
+    ys.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+    ys
+}
+
+fn crossing(a: &Edge, b: &Edge) -> Option<f64> {
+    let r = a.bottom - a.top;
+    let s = b.bottom - b.top;
+    let denominator = r.perp(&s);
+    if denominator.abs() < EPSILON {
+        return None;
+    }
+    let qp = b.top - a.top;
+    let t = qp.perp(&s) / denominator;
+    let u = qp.perp(&r) / denominator;
+    if t > EPSILON && t < 1.0 - EPSILON && u > EPSILON && u < 1.0 - EPSILON {
+        Some(a.top.y + r.y * t)
+    } else {
+        None
+    }
+}
+
+/// Calls `span` with `(y0, y1, left, right)` for every horizontal band that lies inside the
+/// outlines, where `left` and `right` are the edges bounding the band.
+pub(crate) fn for_each_span(
+    edges: &[Edge],
+    rule: FillRule,
+    mut span: impl FnMut(f64, f64, &Edge, &Edge),
+) {
+    let ys = scanlines(edges);
+    let mut next = 0;
+    let mut active: Vec<Edge> = vec![];
+    for band in ys.windows(2) {
+        let (y0, y1) = (band[0], band[1]);
+        active.retain(|e| e.bottom.y > y0 + EPSILON);
+        while next < edges.len() && edges[next].top.y < y1 - EPSILON {
+            active.push(edges[next]);
+            next += 1;
+        }
+        if y1 - y0 < EPSILON {
+            continue;
+        }
+
+        let middle = (y0 + y1) / 2.0;
+        let mut crossing: Vec<&Edge> = active
+            .iter()
+            .filter(|e| e.top.y <= y0 + EPSILON && e.bottom.y >= y1 - EPSILON)
+            .collect();
+        crossing.sort_by(|a, b| a.x_at(middle).total_cmp(&b.x_at(middle)));
+
+        let mut winding = 0;
+        let mut left: Option<&Edge> = None;
+        for edge in crossing {
+            let was_inside = rule.is_inside(winding);
+            winding += edge.winding;
+            let inside = rule.is_inside(winding);
+            if !was_inside && inside {
+                left = Some(edge);
+            } else if was_inside && !inside {
+                if let Some(l) = left.take() {
+                    span(y0, y1, l, edge);
+                }
+            }
+        }
+    }
+}
+
+/// Tessellates the outlines as one shape under the given fill rule and appends the result to `out`.
+///
+/// Contours may be concave, intersect themselves or each other, and contain holes.
+#[doc(hidden)]
+pub(crate) fn fill_outlines(
+    outlines: &[Outline],
+    rule: FillRule,
+    color: Vec4<f32>,
+    out: &mut IndexBuffer,
+) {
+    let edges = collect_edges(outlines);
+    let vertex = |x: f64, y: f64| VertexData {
+        pos: Vec4::new(x as f32, y as f32, 0.0, 1.0),
+        color,
+    };
+    for_each_span(&edges, rule, |y0, y1, left, right| {
+        let (l0, l1) = (left.x_at(y0), left.x_at(y1));
+        let (r0, r1) = (right.x_at(y0), right.x_at(y1));
+        let a = out.push_vertex(vertex(l0, y0));
+        let b = out.push_vertex(vertex(r0, y0));
+        let c = out.push_vertex(vertex(r1, y1));
+        let d = out.push_vertex(vertex(l1, y1));
+        if r0 - l0 > EPSILON {
+            out.push_triangle(a, b, c);
+        }
+        if r1 - l1 > EPSILON {
+            out.push_triangle(a, c, d);
+        }
+    });
+}
@@ -0,0 +1,125 @@
+use crate::{Mat4, Vec2, Vec3, Vec4};
+
+use super::PathGeometry;
+
+/// # Transform
+/// An affine transformation in 2D or 3D, stored as a 4x4 matrix.
+///
+/// Transforms are combined with [`Transform::then`], which applies the transforms in reading order.
+/// Angles are in radians. In 2D, positive angles rotate from the positive x axis towards the positive y axis.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{geometry::Transform, Vec2};
+/// // Rotate a quarter turn around (50, 50), then move 100 pixels to the right.
+/// let transform = Transform::rotation_around(std::f32::consts::FRAC_PI_2, Vec2::new(50.0, 50.0))
+///     .then(&Transform::translation(100.0, 0.0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    matrix: Mat4<f32>,
+}
+
+impl Transform {
+    /// The transform that leaves every point where it is.
+    pub fn identity() -> Self {
+        Self {
+            matrix: Mat4::identity(),
+        }
+    }
+
+    /// Creates a transform from a matrix acting on column vectors.
+    pub fn from_matrix(matrix: Mat4<f32>) -> Self {
+        Self { matrix }
+    }
+
+    /// Creates a 2D transform from the six values `[a b c d e f]`,
+    /// mapping `(x, y)` to `(a * x + c * y + e, b * x + d * y + f)` like SVG and CSS `matrix()`.
+    pub fn from_2d(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Self::from_matrix(Mat4::new(
+            a, c, 0.0, e, b, d, 0.0, f, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ))
+    }
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self::translation_3d(x, y, 0.0)
+    }
+
+    pub fn translation_3d(x: f32, y: f32, z: f32) -> Self {
+        Self::from_matrix(Mat4::new_translation(&Vec3::new(x, y, z)))
+    }
+
+    pub fn scaling(x: f32, y: f32) -> Self {
+        Self::scaling_3d(x, y, 1.0)
+    }
+
+    pub fn scaling_3d(x: f32, y: f32, z: f32) -> Self {
+        Self::from_matrix(Mat4::new_nonuniform_scaling(&Vec3::new(x, y, z)))
+    }
+
+    /// Rotation around the origin in the xy plane.
+    pub fn rotation(angle: f32) -> Self {
+        Self::rotation_3d(Vec3::new(0.0, 0.0, 1.0), angle)
+    }
+
+    /// Rotation around `center` in the xy plane.
+    pub fn rotation_around(angle: f32, center: Vec2<f32>) -> Self {
+        Self::translation(-center.x, -center.y)
+            .then(&Self::rotation(angle))
+            .then(&Self::translation(center.x, center.y))
+    }
+
+    /// Rotation around `axis`, which passes through the origin.
+    pub fn rotation_3d(axis: Vec3<f32>, angle: f32) -> Self {
+        match nalgebra::Unit::try_new(axis, f32::EPSILON) {
+            Some(axis) => Self::from_matrix(Mat4::from_axis_angle(&axis, angle)),
+            None => Self::identity(),
+        }
+    }
+
+    /// Skews along the x axis by `x` and along the y axis by `y`, like CSS `skew()`.
+    pub fn skew(x: f32, y: f32) -> Self {
+        Self::from_2d(1.0, y.tan(), x.tan(), 1.0, 0.0, 0.0)
+    }
+
+    /// Returns the transform that applies `self` first and then `next`.
+    pub fn then(&self, next: &Transform) -> Self {
+        Self::from_matrix(next.matrix * self.matrix)
+    }
+
+    /// Returns the transform that undoes this one, or `None` if it collapses space, e.g. a zero scale.
+    pub fn inverse(&self) -> Option<Self> {
+        self.matrix.try_inverse().map(Self::from_matrix)
+    }
+
+    pub fn matrix(&self) -> Mat4<f32> {
+        self.matrix
+    }
+
+    /// Transforms a point in the xy plane.
+    pub fn apply(&self, point: Vec2<f32>) -> Vec2<f32> {
+        let p = self.apply_3d(Vec3::new(point.x, point.y, 0.0));
+        Vec2::new(p.x, p.y)
+    }
+
+    pub fn apply_3d(&self, point: Vec3<f32>) -> Vec3<f32> {
+        let p = self.matrix * Vec4::new(point.x, point.y, point.z, 1.0);
+        Vec3::new(p.x, p.y, p.z) / p.w
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl PathGeometry {
+    /// Transforms all contours and shapes built so far.
+    ///
+    /// Stroke geometry is generated from the transformed outline, so the stroke thickness is not scaled.
+    /// Contours live in the xy plane; their z coordinate after a 3D transform is dropped.
+    pub fn transform(&mut self, transform: &Transform) {
+        self.map_points(|p| transform.apply(p));
+    }
+}
@@ -7,7 +7,7 @@ use ash::vk::{
     SampleCountFlags, SharingMode,
 };
 
-use crate::{Destroy, Instance, LogicalDevice, PhysicalDevice, RenderPass};
+use crate::{Destroy, Instance, LogicalDevice, PhysicalDevice, RenderPass, VlResult};
 
 #[allow(non_camel_case_types)]
 pub enum ImageType {
@@ -24,10 +24,65 @@ impl Into<ash::vk::ImageType> for ImageType {
     }
 }
 
+/// Indicates how many samples each pixel is rendered with
+///
+/// Edges are smoothed by rendering to a multisampled image, which is then resolved
+/// into a single sampled one, see [`RenderPass::multisampled`].
+/// A count the device does not support falls back to the highest supported count below it.
+///
+/// # Value Meaning
+/// * `One` - No multisampling, so edges are aliased.
+/// * `Two` - 2 samples per pixel.
+/// * `Four` - 4 samples per pixel. Supported by all devices.
+/// * `Eight` - 8 samples per pixel.
+/// * `Sixteen` - 16 samples per pixel.
+/// * `Max` - The highest count the device supports.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum SampleCount {
+    #[default]
+    One,
+    Two,
+    Four,
+    Eight,
+    Sixteen,
+    Max,
+}
+
+impl SampleCount {
+    /// The count used on `device`.
+    pub(crate) fn flags(self, device: &LogicalDevice) -> SampleCountFlags {
+        self.supported_by(device.sample_counts)
+    }
+
+    /// The count used when `supported` are the counts a device supports.
+    pub(crate) fn supported_by(self, supported: SampleCountFlags) -> SampleCountFlags {
+        let requested = match self {
+            SampleCount::One => SampleCountFlags::TYPE_1,
+            SampleCount::Two => SampleCountFlags::TYPE_2,
+            SampleCount::Four => SampleCountFlags::TYPE_4,
+            SampleCount::Eight => SampleCountFlags::TYPE_8,
+            SampleCount::Sixteen => SampleCountFlags::TYPE_16,
+            SampleCount::Max => SampleCountFlags::TYPE_64,
+        };
+        [
+            SampleCountFlags::TYPE_64,
+            SampleCountFlags::TYPE_32,
+            SampleCountFlags::TYPE_16,
+            SampleCountFlags::TYPE_8,
+            SampleCountFlags::TYPE_4,
+            SampleCountFlags::TYPE_2,
+        ]
+        .into_iter()
+        .find(|&count| count.as_raw() <= requested.as_raw() && supported.contains(count))
+        .unwrap_or(SampleCountFlags::TYPE_1)
+    }
+}
+
 pub struct ImageBuilder {
     width: u32,
     height: u32,
     image_type: ImageType,
+    samples: SampleCount,
 }
 
 impl ImageBuilder {
@@ -45,12 +100,34 @@ impl ImageBuilder {
         self
     }
 
+    /// Creates a multisampled image to render to, resolved into a single sampled image.
+    /// Multisampled images live in device memory, so they cannot be mapped.
+    pub fn samples(mut self, samples: SampleCount) -> Self {
+        self.samples = samples;
+        self
+    }
+
     pub fn build(
         mut self,
         instance: &Instance,
         physical_device: PhysicalDevice,
         device: &LogicalDevice,
     ) -> Image {
+        let samples = self.samples.flags(device);
+        // Only single sampled images can be read back by the host.
+        let (tiling, usage, memory_flags) = if samples == SampleCountFlags::TYPE_1 {
+            (
+                ImageTiling::LINEAR,
+                ImageUsageFlags::COLOR_ATTACHMENT,
+                MemoryPropertyFlags::HOST_VISIBLE,
+            )
+        } else {
+            (
+                ImageTiling::OPTIMAL,
+                ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSIENT_ATTACHMENT,
+                MemoryPropertyFlags::DEVICE_LOCAL,
+            )
+        };
         let create_info = ImageCreateInfo::builder()
             .image_type(self.image_type.into())
             .extent(
@@ -63,11 +140,11 @@ impl ImageBuilder {
             .mip_levels(1)
             .array_layers(1)
             .format(Format::R8G8B8A8_UNORM)
-            .tiling(ImageTiling::LINEAR)
+            .tiling(tiling)
             .initial_layout(ImageLayout::UNDEFINED)
-            .usage(ImageUsageFlags::COLOR_ATTACHMENT)
+            .usage(usage)
             .sharing_mode(SharingMode::EXCLUSIVE)
-            .samples(SampleCountFlags::TYPE_1)
+            .samples(samples)
             .build();
         let inner = unsafe { device.inner.create_image(&create_info, None).unwrap() };
 
@@ -84,10 +161,7 @@ impl ImageBuilder {
 
         for i in 0..mem_prop.memory_type_count {
             if (mem_req.memory_type_bits & (1 << i)) != 0
-                && (mem_prop.memory_types[i as usize].property_flags
-                    & MemoryPropertyFlags::HOST_VISIBLE)
-                    .as_raw()
-                    != 0
+                && (mem_prop.memory_types[i as usize].property_flags & memory_flags).as_raw() != 0
             {
                 create_info = create_info.memory_type_index(i);
                 suitable_memory_found = true;
@@ -119,6 +193,7 @@ impl Default for ImageBuilder {
             width: 100,
             height: 100,
             image_type: ImageType::e2D,
+            samples: SampleCount::One,
         }
     }
 }
@@ -222,6 +297,27 @@ impl ImageView {
         };
         Ok(FrameBuffer { inner })
     }
+
+    /// Creates a frame buffer for a multisampled render pass. This view is rendered to,
+    /// and `resolve` receives the resolved image.
+    pub fn create_resolving_frame_buffer(
+        &self,
+        device: &LogicalDevice,
+        render_pass: &RenderPass,
+        resolve: &ImageView,
+        width: u32,
+        height: u32,
+    ) -> VlResult<FrameBuffer> {
+        let create_info = FramebufferCreateInfo::builder()
+            .width(width)
+            .height(height)
+            .layers(1)
+            .render_pass(render_pass.inner)
+            .attachments(&[self.inner, resolve.inner])
+            .build();
+        let inner = unsafe { device.inner.create_framebuffer(&create_info, None) }?;
+        Ok(FrameBuffer { inner })
+    }
 }
 
 impl Destroy for ImageView {
@@ -2,7 +2,9 @@ use crate::{
     Destroy, HardwareError, LogicalDevice, PhysicalDevice, QueueProperties, VlError, VlResult,
 };
 use ash::{
-    vk::{DeviceCreateInfo, DeviceQueueCreateInfo, InstanceCreateInfo},
+    vk::{
+        DeviceCreateInfo, DeviceQueueCreateInfo, InstanceCreateInfo, PhysicalDeviceFeatures, TRUE,
+    },
     Entry,
 };
 
@@ -181,12 +183,24 @@ impl Instance {
             .queue_family_index(queue_family_index as u32)
             .queue_priorities(&[1.0])
             .build()];
+        // Samplers only use anisotropic filtering where the device supports it.
+        let supported = unsafe { self.inner.get_physical_device_features(device.0) };
+        let features = PhysicalDeviceFeatures::builder()
+            .sampler_anisotropy(supported.sampler_anisotropy == TRUE)
+            .build();
         let create_info = DeviceCreateInfo::builder()
             .queue_create_infos(&queue_infos)
             .enabled_extension_names(&[ash::extensions::khr::Swapchain::name().as_ptr()])
+            .enabled_features(&features)
             .build();
         let inner = unsafe { self.inner.create_device(device.0, &create_info, None) }.unwrap();
-        LogicalDevice { inner }
+        let sample_counts = unsafe { self.inner.get_physical_device_properties(device.0) }
+            .limits
+            .framebuffer_color_sample_counts;
+        LogicalDevice {
+            inner,
+            sample_counts,
+        }
     }
 
     pub fn destroy<D>(&self, object: &D)
@@ -1,5 +1,7 @@
 #![feature(offset_of)]
 
+pub mod brush;
+mod color;
 mod device;
 pub mod geometry;
 mod image;
@@ -9,10 +11,15 @@ mod queue;
 mod renderpass;
 mod shader;
 mod stage;
+pub mod svg;
 pub mod target;
+pub mod text;
+mod texture;
 mod types;
 #[cfg(feature = "util")]
 pub mod util;
+mod vertex;
+pub use color::*;
 pub use device::*;
 pub use image::*;
 pub use instance::*;
@@ -21,8 +28,10 @@ pub use queue::*;
 pub use renderpass::*;
 pub use shader::*;
 pub use stage::*;
+pub use texture::*;
 use thiserror::Error;
 pub use types::*;
+pub use vertex::*;
 
 pub type VlResult<T> = std::result::Result<T, VlError>;
 
@@ -38,6 +47,8 @@ pub enum VlError {
     InvalidState(&'static str),
     #[error("Out of memory.")]
     OutOfMemory,
+    #[error("Failed to parse at position {0}.\nCause:`{1}`")]
+    ParseError(usize, &'static str),
 }
 
 #[derive(Debug, Error)]
@@ -46,6 +57,15 @@ pub enum HardwareError {
     NoSuitableDevice,
 }
 
+/// Moves the position of a [`VlError::ParseError`] by `offset` bytes, for errors found in a
+/// part of a larger text. Other errors are returned unchanged.
+pub(crate) fn offset_error(error: VlError, offset: usize) -> VlError {
+    match error {
+        VlError::ParseError(position, cause) => VlError::ParseError(offset + position, cause),
+        error => error,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::geometry::PathGeometry;
@@ -84,4 +104,960 @@ mod tests {
 
         assert_eq!(triangle.size(), VERTEX_SIZE);
     }
+
+    #[test]
+    fn fill_rule_hole() {
+        use crate::geometry::{fill_outlines, FillRule, IndexBuffer, Outline, VertexData};
+
+        let square = |min: f32, max: f32| Outline {
+            points: [(min, min), (max, min), (max, max), (min, max)]
+                .iter()
+                .map(|(x, y)| VertexData {
+                    pos: Vec4::new(*x, *y, 0.0, 1.0),
+                    color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+                })
+                .collect(),
+            closed: true,
+        };
+        let outlines = [square(0.0, 10.0), square(2.0, 8.0)];
+        let area = |rule: FillRule| {
+            let mut index_buffer = IndexBuffer::default();
+            fill_outlines(
+                &outlines,
+                rule,
+                Vec4::new(1.0, 1.0, 1.0, 1.0),
+                &mut index_buffer,
+            );
+            index_buffer
+                .indices
+                .chunks(3)
+                .map(|t| {
+                    let (a, b, c) = (
+                        index_buffer.data[t[0] as usize].pos,
+                        index_buffer.data[t[1] as usize].pos,
+                        index_buffer.data[t[2] as usize].pos,
+                    );
+                    ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)) / 2.0
+                })
+                .sum::<f32>()
+        };
+
+        assert!((area(FillRule::NonZero) - 100.0).abs() < 1e-3);
+        assert!((area(FillRule::EvenOdd) - 64.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn curve_flattening() {
+        use crate::geometry::{Figure, FillRule, PathCommand};
+
+        let (p0, p1, p2, p3) = (
+            Vec2::new(0.0, 0.0),
+            Vec2::new(30.0, 90.0),
+            Vec2::new(70.0, -60.0),
+            Vec2::new(100.0, 20.0),
+        );
+        let mut figure = Figure::new(Vec4::new(1.0, 1.0, 1.0, 1.0), FillRule::NonZero);
+        figure.commands = vec![PathCommand::MoveTo(p0), PathCommand::CubicTo(p1, p2, p3)];
+        for tolerance in [1.0, 0.1] {
+            let points: Vec<_> = figure.flatten(tolerance)[0]
+                .points
+                .iter()
+                .map(|v| Vec2::new(v.pos.x, v.pos.y))
+                .collect();
+            for i in 0..=1000 {
+                let t = i as f32 / 1000.0;
+                let mt = 1.0 - t;
+                let on_curve = p0 * (mt * mt * mt)
+                    + p1 * (3.0 * mt * mt * t)
+                    + p2 * (3.0 * mt * t * t)
+                    + p3 * (t * t * t);
+                let distance = points
+                    .windows(2)
+                    .map(|s| {
+                        let segment = s[1] - s[0];
+                        let t = ((on_curve - s[0]).dot(&segment) / segment.norm_squared())
+                            .clamp(0.0, 1.0);
+                        (s[0] + segment * t - on_curve).norm()
+                    })
+                    .fold(f32::INFINITY, f32::min);
+                assert!(distance <= tolerance * 1.01);
+            }
+        }
+    }
+
+    #[test]
+    fn primitives_fit_pixel_scale() {
+        use crate::geometry::ArcKind;
+
+        let shapes: [fn(&mut PathGeometry); 4] = [
+            |p| p.circle(Vec2::new(0.0, 0.0), 50.0),
+            |p| p.ellipse(Vec2::new(0.0, 0.0), Vec2::new(50.0, 20.0)),
+            |p| {
+                p.arc(
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(50.0, 50.0),
+                    0.0,
+                    3.0,
+                    ArcKind::Pie,
+                )
+            },
+            |p| {
+                p.rounded_rectangle(
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(100.0, 60.0),
+                    Vec4::repeat(20.0),
+                )
+            },
+        ];
+        for shape in shapes {
+            let outline = |scale: f32| {
+                let mut path = PathGeometry::new();
+                path.pixel_scale(scale);
+                shape(&mut path);
+                (path.bounds().unwrap(), path.size())
+            };
+            let (bounds, segments) = outline(1.0);
+            let (zoomed_bounds, zoomed_segments) = outline(4.0);
+            // The shape keeps its size, but four times the pixels need about twice the segments.
+            assert!((bounds.min - zoomed_bounds.min).norm() < 0.25);
+            assert!((bounds.max - zoomed_bounds.max).norm() < 0.25);
+            assert!(zoomed_segments as f32 >= segments as f32 * 1.5);
+        }
+    }
+
+    #[test]
+    fn stroke_joins_and_caps() {
+        use crate::geometry::{
+            stroke_outline, IndexBuffer, LineCap, LineJoin, Outline, StrokeStyle, VertexData,
+        };
+
+        let polyline = |points: &[(f32, f32)]| Outline {
+            points: points
+                .iter()
+                .map(|(x, y)| VertexData {
+                    pos: Vec4::new(*x, *y, 0.0, 1.0),
+                    color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+                })
+                .collect(),
+            closed: false,
+        };
+        // Horizontal extent of the stroke with a thickness of 2.
+        let extent = |outline: &Outline, style: StrokeStyle| {
+            let mut index_buffer = IndexBuffer::default();
+            stroke_outline(outline, 2.0, &style, 0.25, &mut index_buffer);
+            index_buffer
+                .data
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(min, max), v| {
+                    (min.min(v.pos.x), max.max(v.pos.x))
+                })
+        };
+
+        // The corner at (10, 0) is about 11 degrees, so its miter is about 10 times the half thickness.
+        let acute = polyline(&[(0.0, 0.0), (10.0, 0.0), (0.0, 2.0)]);
+        let miter = extent(&acute, StrokeStyle::new().miter_limit(20.0)).1;
+        assert!(miter > 19.0 && miter < 21.0);
+        let limited = extent(&acute, StrokeStyle::new()).1;
+        let bevel = extent(&acute, StrokeStyle::new().line_join(LineJoin::Bevel)).1;
+        assert_eq!(limited, bevel);
+        assert!(bevel <= 11.0);
+
+        let line = polyline(&[(0.0, 0.0), (10.0, 0.0)]);
+        assert_eq!(extent(&line, StrokeStyle::new()), (0.0, 10.0));
+        let square = StrokeStyle::new().line_cap(LineCap::Square);
+        assert_eq!(extent(&line, square), (-1.0, 11.0));
+
+        // The segments and the join are filled as their union, so no area is covered twice.
+        let corner = polyline(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        let mut index_buffer = IndexBuffer::default();
+        stroke_outline(&corner, 2.0, &StrokeStyle::new(), 0.25, &mut index_buffer);
+        let area: f32 = index_buffer
+            .indices
+            .chunks(3)
+            .map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| index_buffer.data[t[i] as usize].pos);
+                ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.0
+            })
+            .sum();
+        assert!((area - 40.0).abs() < 1e-3);
+
+        // Round caps get more segments when the path is zoomed in, like curves.
+        let round_caps = |scale: f32| {
+            let mut path = PathGeometry::new();
+            path.pixel_scale(scale);
+            path.stroke_style(StrokeStyle::new().line_cap(LineCap::Round));
+            path.move_to(Vec2::new(0.0, 0.0));
+            path.line_to(Vec2::new(10.0, 0.0));
+            path.stroke_tessellation(10.0).unwrap().index_buffers[0]
+                .data
+                .len()
+        };
+        assert!(round_caps(4.0) > round_caps(1.0));
+    }
+
+    #[test]
+    fn feathered_edges() {
+        use crate::geometry::FillRule;
+
+        let mut path = PathGeometry::new();
+        path.fill_rule(FillRule::EvenOdd);
+        let square = |min: f32, max: f32| {
+            [(min, min), (max, min), (max, max), (min, max)].map(|(x, y)| Vec2::new(x, y))
+        };
+        path.polygon(&square(0.0, 10.0));
+        path.polygon(&square(3.0, 7.0));
+        let area = |path: &mut PathGeometry| -> f32 {
+            let tessellation = path.fill_tessellation().unwrap();
+            let mut area = 0.0;
+            for buffer in &tessellation.index_buffers {
+                for t in buffer.indices.chunks(3) {
+                    let [a, b, c] = [0, 1, 2].map(|i| buffer.data[t[i] as usize].pos);
+                    area += ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.0;
+                }
+            }
+            area
+        };
+        let plain = area(&mut path);
+        path.anti_alias(true);
+        // One pixel around the outside, with square corners, and one pixel into the hole.
+        assert!((area(&mut path) - plain - (44.0 + 12.0)).abs() < 1e-3);
+
+        let fringe = path
+            .fill_tessellation()
+            .unwrap()
+            .index_buffers
+            .pop()
+            .unwrap();
+        let outer = fringe.data.iter().filter(|v| v.color.w == 0.0).count();
+        assert_eq!(outer * 2, fringe.data.len());
+    }
+
+    #[test]
+    fn svg_path_data() {
+        let path = svg::parse_path(
+            "M10 10 h80 v80 H10 z m20 20 a10 5 30 1 0 20 0 q5-5 10 0 t10 0 s5 5 10 0",
+        );
+        assert!(path.is_ok());
+
+        match svg::parse_path("M 10 10 L 20 x") {
+            Err(VlError::ParseError(position, _)) => assert_eq!(position, 13),
+            _ => panic!("malformed path data must be rejected"),
+        }
+        assert!(svg::parse_path("L 10 10").is_err());
+    }
+
+    #[test]
+    fn svg_document() {
+        let document = svg::Document::parse(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 50 50" width="100" height="100">
+                <g transform="translate(5 5) rotate(45)" style="fill: #f80; opacity: 0.5">
+                    <rect width="10" height="10" rx="2"/>
+                    <polyline points="0,0 10,10 20,0" fill="none" stroke="blue"/>
+                </g>
+            </svg>"#,
+        );
+        assert_eq!(document.unwrap().size(), Vec2::new(100.0, 100.0));
+
+        // Properties of the root element are inherited like those of a group.
+        let document = svg::Document::parse(
+            r#"<svg fill="none"><rect width="10" height="10"/><rect width="10" height="10" stroke="red"/></svg>"#,
+        )
+        .unwrap();
+        assert_eq!(document.elements.len(), 1);
+        assert!(document.elements[0].fill.is_none());
+
+        // A nested viewport is placed at its position and maps its view box onto its size.
+        let document = svg::Document::parse(
+            r#"<svg width="100" height="100">
+                <svg x="20" y="10" width="40" height="40" viewBox="0 0 10 10">
+                    <rect width="10" height="10" rx="4" ry="2"/>
+                </svg>
+            </svg>"#,
+        )
+        .unwrap();
+        let bounds = document.elements[0]
+            .fill
+            .as_ref()
+            .unwrap()
+            .bounds()
+            .unwrap();
+        assert!((bounds.min - Vec2::new(20.0, 10.0)).norm() < 1e-4);
+        assert!((bounds.max - Vec2::new(60.0, 50.0)).norm() < 1e-4);
+
+        let text = r#"<svg><path d="M 0 0 L 1 x"/></svg>"#;
+        match svg::Document::parse(text) {
+            Err(VlError::ParseError(position, _)) => assert_eq!(&text[position..position + 1], "x"),
+            _ => panic!("malformed path data must be rejected"),
+        }
+    }
+
+    #[test]
+    fn dashed_outline() {
+        use crate::geometry::{
+            dash_outline, stroke_outline, IndexBuffer, LineCap, Outline, StrokeStyle, VertexData,
+        };
+
+        let square = Outline {
+            points: [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]
+                .map(|(x, y)| VertexData {
+                    pos: Vec4::new(x, y, 0.0, 1.0),
+                    color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+                })
+                .to_vec(),
+            closed: true,
+        };
+        assert_eq!(dash_outline(&square, &[5.0, 5.0], 0.0).len(), 4);
+
+        // The dash crossing the starting point is joined, and bends around the corner.
+        let dashes = dash_outline(&square, &[5.0, 5.0], 2.5);
+        assert_eq!(dashes.len(), 4);
+        let corner = &dashes[3].points;
+        assert_eq!(corner.len(), 3);
+        assert_eq!(corner[0].pos.y, 2.5);
+        assert_eq!(corner[2].pos.x, 2.5);
+
+        // Zero-length dashes are kept, so that their caps draw dots, starting with the first one.
+        let line = Outline {
+            points: vec![square.points[0], square.points[1]],
+            closed: false,
+        };
+        let dots = dash_outline(&line, &[0.0, 4.0], 0.0);
+        let starts: Vec<_> = dots.iter().map(|d| d.points[0].pos.x).collect();
+        assert_eq!(starts, [0.0, 4.0, 8.0]);
+        assert!(dots
+            .iter()
+            .all(|d| d.points.iter().all(|p| p.pos == d.points[0].pos)));
+        let mut index_buffer = IndexBuffer::default();
+        let style = StrokeStyle::new()
+            .line_cap(LineCap::Round)
+            .dash_array(&[0.0, 4.0]);
+        stroke_outline(&line, 2.0, &style, 0.25, &mut index_buffer);
+        let left = index_buffer
+            .data
+            .iter()
+            .map(|v| v.pos.x)
+            .fold(f32::MAX, f32::min);
+        assert!((left + 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn draw_list_packing() {
+        use crate::geometry::{IndexBuffer, Tessellation, Transform, VertexData};
+        use crate::target::{batch::Geometry, DrawList};
+
+        let triangles = |count: u32| Tessellation {
+            index_buffers: vec![IndexBuffer {
+                data: vec![
+                    VertexData::new(Vec2::new(0.0, 0.0), Vec4::new(1.0, 1.0, 1.0, 1.0));
+                    count as usize + 2
+                ],
+                indices: (0..count).flat_map(|i| [0, i + 1, i + 2]).collect(),
+            }],
+        };
+        let moved = Transform::translation(10.0, 0.0);
+        let mut draw_list = DrawList::new();
+        draw_list.push_tessellation(triangles(1), &[Transform::identity()]);
+        draw_list.push_tessellation(triangles(2), &[moved]);
+        // Continues the previous draw call, so its indices are offset by the vertices before it.
+        draw_list.push_tessellation(triangles(1), &[moved]);
+
+        let ranges: Vec<_> = draw_list
+            .draws
+            .iter()
+            .map(|draw| match draw.geometry {
+                Geometry::Batch(range) => {
+                    (range.first_index, range.index_count, range.vertex_offset)
+                }
+                _ => panic!("tessellations are drawn from the shared buffers"),
+            })
+            .collect();
+        assert_eq!(ranges, [(0, 3, 0), (3, 9, 3)]);
+        assert_eq!(draw_list.vertices.len(), 10);
+        assert_eq!(&draw_list.indices[3..], [0, 1, 2, 0, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn retained_handles() {
+        use crate::geometry::{Buffer, IndexBuffer, Path, Tessellation, Transform, VertexData};
+        use crate::target::{batch::Geometry, DrawList, InstanceRange};
+
+        // Paths are never bound here, so they need no device memory.
+        let path = |index_count: usize| Path {
+            buffers: vec![],
+            index_buffers: vec![(
+                Buffer {
+                    buffer: ash::vk::Buffer::null(),
+                    mem_prop: Default::default(),
+                    memory: None,
+                    write_mem: None,
+                    size: 0,
+                },
+                index_count,
+            )],
+            index_type: ash::vk::IndexType::UINT32,
+            triangles: vec![],
+        };
+        let triangle = || Tessellation {
+            index_buffers: vec![IndexBuffer {
+                data: vec![VertexData::new(Vec2::new(0.0, 0.0), Vec4::new(1.0, 1.0, 1.0, 1.0)); 3],
+                indices: vec![0, 1, 2],
+            }],
+        };
+        let identity = [Transform::identity()];
+        let mut draw_list = DrawList::new();
+        let handle = draw_list.retain(path(6), None);
+
+        // Immediate draws come and go around the retained path without changing it.
+        for _ in 0..2 {
+            draw_list.push_tessellation(triangle(), &identity);
+            draw_list
+                .push_retained(handle, &identity, InstanceRange::SINGLE)
+                .unwrap();
+            draw_list.push_tessellation(triangle(), &identity);
+            assert!(matches!(draw_list.draws[1].geometry, Geometry::Retained(h) if h == handle));
+            assert_eq!(
+                draw_list.retained.get(handle).unwrap().index_buffers[0].1,
+                6
+            );
+            draw_list.clear();
+            assert!(draw_list.draws.is_empty());
+        }
+
+        // Freeing the handle makes it invalid and lets the next path take its slot.
+        draw_list.free_retained(handle).unwrap();
+        assert!(draw_list
+            .push_retained(handle, &identity, InstanceRange::SINGLE)
+            .is_err());
+        assert!(draw_list.free_retained(handle).is_err());
+        let next = draw_list.retain(path(3), Some(2.0));
+        assert_eq!(next.index, handle.index);
+        assert_ne!(next, handle);
+        assert_eq!(draw_list.retained_thickness(next).unwrap(), Some(2.0));
+    }
+
+    #[test]
+    fn instance_ranges() {
+        use crate::geometry::{IndexBuffer, InstanceData, Tessellation, Transform, VertexData};
+        use crate::target::{DrawList, InstanceRange};
+
+        assert_eq!(std::mem::size_of::<InstanceData>(), 96);
+        let neutral = InstanceData::default();
+        assert_eq!(neutral.transform, Transform::identity().matrix());
+        assert_eq!(neutral.color, Vec4::new(1.0, 1.0, 1.0, 1.0));
+
+        let mut draw_list = DrawList::new();
+        let instances = [InstanceData::new(Transform::translation(1.0, 0.0), Vec4::zeros()); 3];
+        let first = draw_list.push_instances(&instances);
+        let second = draw_list.push_instances(&instances[..2]);
+        // Instance 0 is the neutral one shared by all draws that are not instanced.
+        assert!(first == InstanceRange { first: 1, count: 3 });
+        assert!(second == InstanceRange { first: 4, count: 2 });
+        assert!(draw_list.instances[0] == neutral);
+
+        // Draws that are not instanced read the neutral instance without adding any.
+        let triangle = Tessellation {
+            index_buffers: vec![IndexBuffer {
+                data: vec![VertexData::new(Vec2::new(0.0, 0.0), neutral.color); 3],
+                indices: vec![0, 1, 2],
+            }],
+        };
+        draw_list.push_tessellation(triangle, &[Transform::identity()]);
+        assert!(draw_list.draws[0].instances == InstanceRange::SINGLE);
+        assert_eq!(draw_list.instances.len(), 6);
+
+        draw_list.clear();
+        assert_eq!(draw_list.instances, [neutral]);
+        assert!(draw_list.push_instances(&instances[..1]) == InstanceRange { first: 1, count: 1 });
+    }
+
+    #[test]
+    fn font_metrics_and_outlines() {
+        use crate::text::Font;
+
+        // The demo font of ttf-parser, with only an `A`, and a `GPOS` table that kerns `AA` by -40 units.
+        let font = Font::new(include_bytes!("text/demo.ttf").to_vec()).unwrap();
+        assert!(Font::new(vec![0; 16]).is_err());
+
+        let size = 20.0;
+        let a = font.glyph('A').unwrap();
+        assert_eq!(font.glyph('B'), None);
+        assert!((font.scale(size) - 0.02).abs() < 1e-6);
+        assert!((font.advance(a, size) - 10.8).abs() < 1e-4);
+        assert!((font.kerning(a, a, size) + 0.8).abs() < 1e-4);
+        assert_eq!(font.kerning(a, 0, size), 0.0);
+
+        // Font units with the y axis up become pixels with the y axis down, placed on the baseline.
+        let mut path = PathGeometry::new();
+        assert!(font.glyph_outline(a, size, Vec2::new(10.0, 50.0), &mut path));
+        let bounds = path.bounds().unwrap();
+        assert!((bounds.min - Vec2::new(10.12, 36.88)).norm() < 1e-4);
+        assert!((bounds.max - Vec2::new(20.82, 50.0)).norm() < 1e-4);
+
+        // `draw_text` advances by the glyph metrics without kerning, and draws the missing glyph for `B`.
+        let mut path = PathGeometry::new();
+        let end = font.text_outline("AAB", size, Vec2::new(10.0, 50.0), &mut path);
+        assert!((end - Vec2::new(10.0 + 10.8 * 2.0 + 12.0, 50.0)).norm() < 1e-4);
+        assert!((path.bounds().unwrap().max.x - 43.6).abs() < 1e-4);
+    }
+
+    #[test]
+    fn text_layout() {
+        use crate::text::{Font, TextAlign, TextLayout};
+
+        // At 10 pixels per em, `A` advances 5.4, `AA` is kerned by -0.4, a space draws the
+        // missing glyph and advances 6.0, and lines are 14.24 apart with an ascender of 10.24.
+        let font = Font::new(include_bytes!("text/demo.ttf").to_vec()).unwrap();
+        let origin = Vec2::new(100.0, 200.0);
+        let positions = |run: &crate::text::GlyphRun| -> Vec<(f32, f32)> {
+            run.glyphs()
+                .iter()
+                .map(|g| (g.position.x - origin.x, g.position.y - origin.y))
+                .collect()
+        };
+        let close = |a: &[(f32, f32)], b: &[(f32, f32)]| {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4)
+        };
+
+        // Breaks between words and drops the spaces at the end of a line.
+        let run = TextLayout::new(&font, 10.0)
+            .max_width(12.0)
+            .layout("AA AA A", origin);
+        let clusters: Vec<usize> = run.glyphs().iter().map(|g| g.cluster).collect();
+        assert_eq!(clusters, [0, 1, 3, 4, 6]);
+        assert!(close(
+            &positions(&run),
+            &[
+                (0.0, 10.24),
+                (5.0, 10.24),
+                (0.0, 24.48),
+                (5.0, 24.48),
+                (0.0, 38.72),
+            ]
+        ));
+
+        // Spreads the slack over the spaces of every line but the last one.
+        let run = TextLayout::new(&font, 10.0)
+            .max_width(25.0)
+            .align(TextAlign::Justify)
+            .layout("A A A AA", origin);
+        assert_eq!(run.line_count(), 2);
+        assert!(close(
+            &positions(&run),
+            &[
+                (0.0, 10.24),
+                (5.4, 10.24),
+                (19.6, 10.24),
+                (0.0, 24.48),
+                (5.4, 24.48),
+                (11.4, 24.48),
+                (16.4, 24.48),
+            ]
+        ));
+
+        // Without a maximum width, lines are aligned within the widest one.
+        for (align, offset) in [
+            (TextAlign::Left, 0.0),
+            (TextAlign::Center, 2.5),
+            (TextAlign::Right, 5.0),
+        ] {
+            let run = TextLayout::new(&font, 10.0)
+                .align(align)
+                .layout("A\nAA", origin);
+            assert!(close(
+                &positions(&run),
+                &[(offset, 10.24), (0.0, 24.48), (5.0, 24.48)]
+            ));
+            let bounds = run.bounds();
+            assert!((bounds.min.x - origin.x).abs() < 1e-4);
+            assert!((bounds.max.x - origin.x - 10.4).abs() < 1e-4);
+        }
+
+        // From the ascender of the first line to the descender of the last one.
+        let run = TextLayout::new(&font, 10.0)
+            .line_spacing(1.5)
+            .layout("A\nA\nA", origin);
+        let bounds = run.bounds();
+        assert_eq!(run.line_count(), 3);
+        assert_eq!(bounds.min.y, origin.y);
+        assert!((bounds.size().y - (10.24 + 2.0 * 14.24 * 1.5 + 4.0)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn blend_factors() {
+        use ash::vk::{BlendFactor as F, BlendOp, PipelineColorBlendAttachmentState};
+
+        let factors = |mode: BlendMode| {
+            let state = PipelineColorBlendAttachmentState::from(mode);
+            assert_eq!(
+                (state.color_blend_op, state.alpha_blend_op),
+                (BlendOp::ADD, BlendOp::ADD)
+            );
+            (
+                state.blend_enable == ash::vk::TRUE,
+                state.src_color_blend_factor,
+                state.dst_color_blend_factor,
+                state.src_alpha_blend_factor,
+                state.dst_alpha_blend_factor,
+            )
+        };
+        // Straight alpha weighs the color by its alpha, premultiplied colors already are.
+        assert_eq!(
+            factors(BlendMode::Alpha),
+            (
+                true,
+                F::SRC_ALPHA,
+                F::ONE_MINUS_SRC_ALPHA,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA
+            )
+        );
+        assert_eq!(
+            factors(BlendMode::Premultiplied),
+            (
+                true,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA
+            )
+        );
+        assert_eq!(
+            factors(BlendMode::Additive),
+            (true, F::SRC_ALPHA, F::ONE, F::ONE, F::ONE)
+        );
+        assert_eq!(
+            factors(BlendMode::Multiply),
+            (
+                true,
+                F::DST_COLOR,
+                F::ONE_MINUS_SRC_ALPHA,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA
+            )
+        );
+        assert_eq!(
+            factors(BlendMode::Screen),
+            (
+                true,
+                F::ONE,
+                F::ONE_MINUS_SRC_COLOR,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA
+            )
+        );
+        assert_eq!(
+            factors(BlendMode::Source),
+            (false, F::ONE, F::ZERO, F::ONE, F::ZERO)
+        );
+
+        // The Porter-Duff operators weigh the color and the alpha the same.
+        for (mode, src, dst) in [
+            (BlendMode::Clear, F::ZERO, F::ZERO),
+            (BlendMode::Destination, F::ZERO, F::ONE),
+            (BlendMode::DestinationOver, F::ONE_MINUS_DST_ALPHA, F::ONE),
+            (BlendMode::SourceIn, F::DST_ALPHA, F::ZERO),
+            (BlendMode::DestinationIn, F::ZERO, F::SRC_ALPHA),
+            (BlendMode::SourceOut, F::ONE_MINUS_DST_ALPHA, F::ZERO),
+            (BlendMode::DestinationOut, F::ZERO, F::ONE_MINUS_SRC_ALPHA),
+            (BlendMode::SourceAtop, F::DST_ALPHA, F::ONE_MINUS_SRC_ALPHA),
+            (
+                BlendMode::DestinationAtop,
+                F::ONE_MINUS_DST_ALPHA,
+                F::SRC_ALPHA,
+            ),
+            (
+                BlendMode::Xor,
+                F::ONE_MINUS_DST_ALPHA,
+                F::ONE_MINUS_SRC_ALPHA,
+            ),
+        ] {
+            assert_eq!(factors(mode), (true, src, dst, src, dst));
+        }
+    }
+
+    #[test]
+    fn transform_order() {
+        use crate::geometry::Transform;
+
+        let transform =
+            Transform::rotation_around(std::f32::consts::FRAC_PI_2, Vec2::new(10.0, 0.0))
+                .then(&Transform::translation(5.0, 0.0));
+        let p = transform.apply(Vec2::new(20.0, 0.0));
+        assert!((p - Vec2::new(15.0, 10.0)).norm() < 1e-5);
+
+        let back = transform.inverse().unwrap().apply(p);
+        assert!((back - Vec2::new(20.0, 0.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn geometry_queries() {
+        use crate::geometry::FillRule;
+
+        let mut path = PathGeometry::new();
+        path.polygon(&[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ]);
+        path.circle(Vec2::new(5.0, 5.0), 2.0);
+
+        let bounds = path.bounds().unwrap();
+        assert_eq!(
+            (bounds.min, bounds.max),
+            (Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0))
+        );
+        assert!(path.contains(Vec2::new(1.0, 1.0), FillRule::EvenOdd));
+        assert!(!path.contains(Vec2::new(5.0, 5.0), FillRule::EvenOdd));
+        assert!(!path.contains(Vec2::new(11.0, 5.0), FillRule::NonZero));
+        assert!(path.stroke_contains(Vec2::new(10.5, 5.0), 2.0));
+        assert!(!path.stroke_contains(Vec2::new(2.5, 2.5), 2.0));
+        assert!((path.length() - (40.0 + 4.0 * std::f32::consts::PI)).abs() < 0.01);
+    }
+
+    #[test]
+    fn distance_field() {
+        use crate::geometry::FillRule;
+
+        let mut square = PathGeometry::new();
+        square.polygon(&[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ]);
+        let field =
+            square.signed_distance_field(Vec2::new(-2.0, -2.0), 14, 14, 2.0, FillRule::NonZero);
+        let at = |x: usize, y: usize| field[y * 14 + x];
+        assert_eq!(field.len(), 14 * 14);
+        assert_eq!(at(7, 7), 255);
+        assert_eq!(at(0, 0), 0);
+        // Half a pixel inside the left edge and half a pixel outside of it.
+        assert_eq!(at(2, 7), 159);
+        assert_eq!(at(1, 7), 96);
+    }
+
+    #[test]
+    fn gradient_encoding() {
+        use crate::brush::{Brush, LinearGradient, SpreadMode};
+
+        let red = Vec4::new(1.0, 0.0, 0.0, 1.0);
+        let blue = Vec4::new(0.0, 0.0, 1.0, 1.0);
+        let gradient = LinearGradient::new(Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0))
+            .stop(1.0, blue)
+            .stop(-0.5, red)
+            .spread(SpreadMode::Reflect);
+        let offsets: Vec<f32> = gradient.stops().iter().map(|s| s.offset).collect();
+        assert_eq!(offsets, vec![0.0, 1.0]);
+
+        let data = Brush::from(gradient).encode();
+        assert_eq!(data.len(), 2 + 2 * 2);
+        assert_eq!(data[0], Vec4::new(0.0, 2.0, 2.0, 0.0));
+        assert_eq!(data[1], Vec4::new(0.0, 0.0, 10.0, 0.0));
+        assert_eq!((data[2], data[4]), (red, blue));
+    }
+
+    #[test]
+    fn texture_data() {
+        let mut bytes = vec![];
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(png::ColorType::GrayscaleAlpha);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[10, 255, 200, 128]).unwrap();
+        writer.finish().unwrap();
+
+        let data = TextureData::from_png(&bytes).unwrap();
+        assert_eq!((data.width(), data.height()), (2, 1));
+        assert_eq!(data.format(), TextureFormat::Rgba8);
+        assert_eq!(data.pixels(), &[10, 10, 10, 255, 200, 200, 200, 128]);
+
+        assert!(TextureData::from_png(&bytes[..20]).is_err());
+        assert!(TextureData::new(2, 2, TextureFormat::R8, vec![0; 3]).is_err());
+    }
+
+    #[test]
+    fn sample_count_fallback() {
+        use ash::vk::SampleCountFlags as S;
+        let supported = S::TYPE_1 | S::TYPE_2 | S::TYPE_4 | S::TYPE_8;
+        assert_eq!(SampleCount::One.supported_by(supported), S::TYPE_1);
+        assert_eq!(SampleCount::Four.supported_by(supported), S::TYPE_4);
+        assert_eq!(SampleCount::Sixteen.supported_by(supported), S::TYPE_8);
+        assert_eq!(SampleCount::Max.supported_by(supported), S::TYPE_8);
+        assert_eq!(
+            SampleCount::Two.supported_by(S::TYPE_1 | S::TYPE_4),
+            S::TYPE_1
+        );
+    }
+
+    #[test]
+    fn sampler_mip_levels() {
+        assert_eq!(Sampler::default().mip_levels(16, 4), 5);
+        assert_eq!(
+            Sampler::builder()
+                .lod_range(0.0, 2.0)
+                .build()
+                .mip_levels(16, 4),
+            3
+        );
+        assert_eq!(
+            Sampler::builder()
+                .lod_range(0.0, 0.0)
+                .build()
+                .mip_levels(1, 1),
+            1
+        );
+    }
+
+    #[test]
+    fn color_conversions() {
+        let close = |a: Color, b: Color| {
+            let (a, b): (Vec4<f32>, Vec4<f32>) = (a.into(), b.into());
+            assert!((a - b).abs().max() < 1e-4, "{a:?} != {b:?}");
+        };
+
+        let orange: Color = "#ffa500".parse().unwrap();
+        assert_eq!(Color::named("Orange"), Some(orange));
+        close(" rgb(255 165 0 / 100%)".parse().unwrap(), orange);
+        close("hsl(38.8235, 100%, 50%)".parse().unwrap(), orange);
+        assert!(matches!(
+            "rgb(1, 2)".parse::<Color>(),
+            Err(VlError::ParseError(4, _))
+        ));
+        assert!(Color::from_hex("#12345").is_err());
+
+        for color in [
+            orange,
+            Color::HSV(200.0, 0.5, 0.25),
+            Color::RGB(0.1, 0.9, 0.4),
+        ] {
+            close(color.to_hsla(), color);
+            close(color.to_hsva(), color);
+            close(color.to_linear(), color);
+            close(color.to_oklab(), color);
+        }
+        let Color::OKLab(l, a, b, _) = Color::RGB(1.0, 1.0, 1.0).to_oklab() else {
+            unreachable!()
+        };
+        assert!((l - 1.0).abs() < 1e-4 && a.abs() < 1e-4 && b.abs() < 1e-4);
+
+        let red = Color::HSV(350.0, 1.0, 1.0);
+        let mixed = red.mix(Color::HSV(10.0, 1.0, 1.0), 0.5, ColorSpace::HSV);
+        close(mixed, Color::RGB(1.0, 0.0, 0.0));
+        close(
+            red.mix(Color::RGB(0.0, 0.0, 1.0), 0.0, ColorSpace::OKLab),
+            red,
+        );
+    }
+
+    #[test]
+    fn boolean_ops() {
+        use crate::geometry::{BooleanOp, FillRule};
+
+        let square = |x: f32, y: f32| {
+            let mut path = PathGeometry::new();
+            path.polygon(&[
+                Vec2::new(x, y),
+                Vec2::new(x + 10.0, y),
+                Vec2::new(x + 10.0, y + 10.0),
+                Vec2::new(x, y + 10.0),
+            ]);
+            path
+        };
+        let (a, b) = (square(0.0, 0.0), square(5.0, 5.0));
+        let probes =
+            [(2.0, 2.0), (7.0, 7.0), (12.0, 12.0), (20.0, 20.0)].map(|(x, y)| Vec2::new(x, y));
+        for (op, expected, length) in [
+            (BooleanOp::Union, [true, true, true, false], 60.0),
+            (BooleanOp::Intersection, [false, true, false, false], 20.0),
+            (BooleanOp::Difference, [true, false, false, false], 40.0),
+            (BooleanOp::Xor, [true, false, true, false], 80.0),
+        ] {
+            let result = a.boolean(&b, op);
+            for (p, inside) in probes.iter().zip(expected) {
+                assert_eq!(
+                    result.contains(*p, FillRule::NonZero),
+                    inside,
+                    "{op:?} at {p:?}"
+                );
+            }
+            assert!((result.length() - length).abs() < 1e-3, "{op:?}");
+        }
+    }
+
+    #[test]
+    fn vertex_layout() {
+        use std::mem::offset_of;
+
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct MeshVertex {
+            position: [f32; 3],
+            uv: [f32; 2],
+            color: [u8; 4],
+        }
+
+        impl Vertex for MeshVertex {
+            fn attributes() -> Vec<VertexAttribute> {
+                vec![
+                    VertexAttribute::new(0, VertexFormat::Vec3, offset_of!(MeshVertex, position)),
+                    VertexAttribute::new(1, VertexFormat::Vec2, offset_of!(MeshVertex, uv)),
+                    VertexAttribute::new(2, VertexFormat::Unorm8x4, offset_of!(MeshVertex, color)),
+                ]
+            }
+        }
+
+        let layout = VertexLayout::of::<MeshVertex>();
+        assert_eq!(layout.stride, 24);
+        let attributes = layout.attribute_descriptions();
+        assert_eq!(
+            attributes.iter().map(|a| a.offset).collect::<Vec<_>>(),
+            [0, 12, 20]
+        );
+        assert_eq!(attributes[2].format, ash::vk::Format::R8G8B8A8_UNORM);
+        assert_eq!(layout.binding_descriptions().len(), 1);
+
+        let layout = VertexLayout::default();
+        assert_eq!((layout.stride, layout.instance_stride), (32, 96));
+        let locations: Vec<_> = layout
+            .attribute_descriptions()
+            .iter()
+            .map(|a| (a.binding, a.location, a.offset))
+            .collect();
+        assert_eq!(
+            locations[2..],
+            [
+                (1, 2, 0),
+                (1, 3, 16),
+                (1, 4, 32),
+                (1, 5, 48),
+                (1, 6, 64),
+                (1, 7, 80)
+            ]
+        );
+    }
+
+    #[test]
+    fn mesh_topology() {
+        use crate::geometry::{Indices, Mesh, VertexData};
+
+        let vertices: Vec<_> = (0..5)
+            .map(|i| VertexData::new(Vec2::new(i as f32, 0.0), Vec4::new(1.0, 1.0, 1.0, 1.0)))
+            .collect();
+        let strip = Mesh::from_vertices(&vertices, PrimitiveTopology::TriangleStrip);
+        assert_eq!(
+            strip.triangle_list(),
+            Indices::U32(vec![0, 1, 2, 2, 1, 3, 2, 3, 4])
+        );
+        let fan = Mesh::new(
+            &vertices,
+            &[0u16, 1, 2, 3][..],
+            PrimitiveTopology::TriangleFan,
+        )
+        .unwrap();
+        assert_eq!(fan.triangle_list(), Indices::U16(vec![0, 1, 2, 0, 2, 3]));
+        assert!(Mesh::new(&vertices, vec![0u32, 1, 5], PrimitiveTopology::TriangleList).is_err());
+    }
 }
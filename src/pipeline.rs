@@ -1,8 +1,9 @@
 use crate::{
     Image, Instance, LogicalDevice, PhysicalDevice, RenderPass, Shader, ShaderKind, Spirv, Stage,
-    VlResult,
+    Vertex, VertexLayout, VlResult,
 };
 
+#[deprecated(since = "0.0.1", note = "Implement the `Vertex` trait instead.")]
 pub enum VertexDataLayout {
     Vertex2Color3,
     Vertex3Color3,
@@ -46,6 +47,101 @@ impl Into<ash::vk::PolygonMode> for PolygonMode {
     }
 }
 
+/// Indicates how the output of a pipeline is combined with what was drawn before it
+///
+/// `Alpha` and `Additive` take colors as they are. The other modes expect colors premultiplied by
+/// their alpha. In the Porter-Duff operators, the source is the output and the destination the image.
+///
+/// # Value Meaning
+/// * `Alpha` - The output is drawn over the image by its alpha.
+/// * `Premultiplied` - As `Alpha`, for premultiplied colors. This is the Porter-Duff source over.
+/// * `Additive` - The output, weighted by its alpha, is added to the image. It can only lighten.
+/// * `Multiply` - The colors are multiplied. It can only darken.
+/// * `Screen` - The inverted colors are multiplied. It can only lighten.
+/// * `Clear` - The image is cleared to transparent where the output covers it.
+/// * `Source` - The output replaces the image, alpha included.
+/// * `Destination` - The image is kept, so nothing is drawn.
+/// * `DestinationOver` - The output is drawn behind the image.
+/// * `SourceIn` - The output is kept where the image is opaque, and the image is dropped.
+/// * `DestinationIn` - The image is kept where the output is opaque.
+/// * `SourceOut` - The output is kept where the image is transparent, and the image is dropped.
+/// * `DestinationOut` - The image is kept where the output is transparent.
+/// * `SourceAtop` - The output is drawn over the image, only where the image is opaque.
+/// * `DestinationAtop` - The image is drawn over the output, only where the output is opaque.
+/// * `Xor` - Each is kept where the other is transparent.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum BlendMode {
+    #[default]
+    Alpha,
+    Premultiplied,
+    Additive,
+    Multiply,
+    Screen,
+    Clear,
+    Source,
+    Destination,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+}
+
+impl From<BlendMode> for ash::vk::PipelineColorBlendAttachmentState {
+    fn from(mode: BlendMode) -> Self {
+        use ash::vk::BlendFactor as F;
+        // The factors of the output and of the image. The Porter-Duff operators weigh
+        // the color and the alpha the same.
+        let same = |src, dst| (src, dst, src, dst);
+        let (src_color, dst_color, src_alpha, dst_alpha) = match mode {
+            BlendMode::Alpha => (
+                F::SRC_ALPHA,
+                F::ONE_MINUS_SRC_ALPHA,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA,
+            ),
+            BlendMode::Premultiplied => same(F::ONE, F::ONE_MINUS_SRC_ALPHA),
+            BlendMode::Additive => (F::SRC_ALPHA, F::ONE, F::ONE, F::ONE),
+            BlendMode::Multiply => (
+                F::DST_COLOR,
+                F::ONE_MINUS_SRC_ALPHA,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA,
+            ),
+            BlendMode::Screen => (
+                F::ONE,
+                F::ONE_MINUS_SRC_COLOR,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA,
+            ),
+            BlendMode::Clear => same(F::ZERO, F::ZERO),
+            BlendMode::Source => same(F::ONE, F::ZERO),
+            BlendMode::Destination => same(F::ZERO, F::ONE),
+            BlendMode::DestinationOver => same(F::ONE_MINUS_DST_ALPHA, F::ONE),
+            BlendMode::SourceIn => same(F::DST_ALPHA, F::ZERO),
+            BlendMode::DestinationIn => same(F::ZERO, F::SRC_ALPHA),
+            BlendMode::SourceOut => same(F::ONE_MINUS_DST_ALPHA, F::ZERO),
+            BlendMode::DestinationOut => same(F::ZERO, F::ONE_MINUS_SRC_ALPHA),
+            BlendMode::SourceAtop => same(F::DST_ALPHA, F::ONE_MINUS_SRC_ALPHA),
+            BlendMode::DestinationAtop => same(F::ONE_MINUS_DST_ALPHA, F::SRC_ALPHA),
+            BlendMode::Xor => same(F::ONE_MINUS_DST_ALPHA, F::ONE_MINUS_SRC_ALPHA),
+        };
+        ash::vk::PipelineColorBlendAttachmentState::builder()
+            .color_write_mask(ash::vk::ColorComponentFlags::RGBA)
+            .blend_enable(mode != BlendMode::Source)
+            .src_color_blend_factor(src_color)
+            .dst_color_blend_factor(dst_color)
+            .color_blend_op(ash::vk::BlendOp::ADD)
+            .src_alpha_blend_factor(src_alpha)
+            .dst_alpha_blend_factor(dst_alpha)
+            .alpha_blend_op(ash::vk::BlendOp::ADD)
+            .build()
+    }
+}
+
 pub struct PipelineBuilder<'a> {
     renderpass: Option<&'a RenderPass>,
     device: Option<&'a LogicalDevice>,
@@ -54,7 +150,9 @@ pub struct PipelineBuilder<'a> {
     stage: Option<&'a mut Stage>,
     mode: PolygonMode,
     topology: PrimitiveTopology,
+    vertex_layout: VertexLayout,
     line_width: f32,
+    blend_mode: BlendMode,
     width: u32,
     height: u32,
 }
@@ -64,6 +162,8 @@ impl<'a> PipelineBuilder<'a> {
         self.device = Some(device);
         self
     }
+    /// The render pass the pipeline draws in. The pipeline renders with its sample count,
+    /// see [`RenderPass::multisampled`].
     pub fn render_pass(mut self, renderpass: &'a RenderPass) -> Self {
         self.renderpass = Some(renderpass);
         self
@@ -86,6 +186,22 @@ impl<'a> PipelineBuilder<'a> {
         self.topology = topology;
         self
     }
+    /// Reads vertices of type `V` in the vertex shader. Defaults to the vertex of the default shaders.
+    /// This removes the per-instance data, see [`PipelineBuilder::instance`].
+    pub fn vertex<V: Vertex>(mut self) -> Self {
+        self.vertex_layout = VertexLayout::of::<V>();
+        self
+    }
+    /// Reads `I` once per instance in the vertex shader. Defaults to [`InstanceData`](crate::geometry::InstanceData).
+    /// Call it after [`PipelineBuilder::vertex`].
+    pub fn instance<I: Vertex>(mut self) -> Self {
+        self.vertex_layout = self.vertex_layout.with_instance::<I>();
+        self
+    }
+    pub fn vertex_layout(mut self, vertex_layout: VertexLayout) -> Self {
+        self.vertex_layout = vertex_layout;
+        self
+    }
     pub fn width(mut self, width: u32) -> Self {
         self.width = width;
         self
@@ -100,6 +216,12 @@ impl<'a> PipelineBuilder<'a> {
         self
     }
 
+    /// How the output is combined with what was drawn before it. Defaults to [`BlendMode::Alpha`].
+    pub fn blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
     pub fn stage(mut self, stage: &'a mut Stage) -> Self {
         self.stage = Some(stage);
         self
@@ -143,9 +265,11 @@ impl<'a> PipelineBuilder<'a> {
             stage,
             self.mode.into(),
             self.topology.into(),
+            &self.vertex_layout,
             self.width,
             self.height,
             self.line_width,
+            self.blend_mode,
         )
     }
 }
@@ -160,9 +284,11 @@ impl<'a> Default for PipelineBuilder<'a> {
             stage: None,
             mode: Default::default(),
             topology: Default::default(),
+            vertex_layout: Default::default(),
             width: 800,
             height: 600,
             line_width: 1.0,
+            blend_mode: Default::default(),
         }
     }
 }
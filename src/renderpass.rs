@@ -1,25 +1,36 @@
-use std::{ffi::CString, mem::offset_of};
+use std::ffi::CString;
 
 use ash::vk::{
-    AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp,
-    ColorComponentFlags, CullModeFlags, DescriptorBufferInfo, DescriptorPoolCreateInfo,
-    DescriptorPoolSize, DescriptorSetAllocateInfo, DescriptorSetLayoutBinding,
-    DescriptorSetLayoutCreateInfo, DescriptorType, Extent2D, Format, FrontFace,
-    GraphicsPipelineCreateInfo, ImageLayout, Offset2D, PipelineBindPoint, PipelineCache,
+    AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp, CullModeFlags,
+    DescriptorBufferInfo, DescriptorPoolCreateInfo, DescriptorPoolSize, DescriptorSetAllocateInfo,
+    DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorType, Extent2D, Format,
+    FrontFace, GraphicsPipelineCreateInfo, ImageLayout, Offset2D, PipelineBindPoint, PipelineCache,
     PipelineColorBlendAttachmentState, PipelineColorBlendStateCreateInfo,
-    PipelineInputAssemblyStateCreateInfo, PipelineLayoutCreateInfo,
+    PipelineInputAssemblyStateCreateInfo, PipelineLayout, PipelineLayoutCreateInfo,
     PipelineMultisampleStateCreateInfo, PipelineRasterizationStateCreateInfo,
     PipelineShaderStageCreateInfo, PipelineVertexInputStateCreateInfo,
-    PipelineViewportStateCreateInfo, PolygonMode, PrimitiveTopology, Rect2D, RenderPassCreateInfo,
-    SampleCountFlags, ShaderStageFlags, SubpassDescription, VertexInputAttributeDescription,
-    VertexInputBindingDescription, VertexInputRate, Viewport, WriteDescriptorSet,
+    PipelineViewportStateCreateInfo, PolygonMode, PrimitiveTopology, PushConstantRange, Rect2D,
+    RenderPassCreateInfo, SampleCountFlags, ShaderStageFlags, SubpassDescription, Viewport,
+    WriteDescriptorSet,
 };
 
 use crate::{
-    geometry::{Mvp, VertexData},
-    LogicalDevice, Pipeline, Shader, Stage, StageDescriptor, VlError, VlResult,
+    geometry::Mvp, BlendMode, LogicalDevice, Mat4, Pipeline, SampleCount, Shader, Stage,
+    StageDescriptor, VertexLayout, VlError, VlResult,
 };
 
+/// The fixed function state of a pipeline, see [`RenderPass::create_graphics_pipeline`].
+#[derive(Clone, Copy)]
+pub(crate) struct PipelineState<'a> {
+    pub(crate) mode: PolygonMode,
+    pub(crate) topology: PrimitiveTopology,
+    pub(crate) vertex_layout: &'a VertexLayout,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) line_width: f32,
+    pub(crate) blend: BlendMode,
+}
+
 #[derive(Default)]
 pub struct SubPass(SubpassDescription);
 
@@ -41,6 +52,7 @@ impl SubPass {
 pub struct RenderPassBuilder<'a> {
     device: Option<&'a LogicalDevice>,
     subpasses: &'a [SubPass],
+    samples: SampleCount,
 }
 
 impl<'a> RenderPassBuilder<'a> {
@@ -54,22 +66,50 @@ impl<'a> RenderPassBuilder<'a> {
         self
     }
 
+    /// Renders with multisampling, see [`RenderPass::multisampled`].
+    pub fn samples(mut self, samples: SampleCount) -> Self {
+        self.samples = samples;
+        self
+    }
+
     pub fn build(self) -> Result<RenderPass, ()> {
         let device = match self.device {
             Some(x) => x,
             None => return Err(()),
         };
-        Ok(RenderPass::new(device, self.subpasses))
+        Ok(RenderPass::multisampled(
+            device,
+            self.subpasses,
+            self.samples,
+        ))
     }
 }
 
 pub struct RenderPass {
     pub(crate) inner: ash::vk::RenderPass,
+    /// The sample count of the color attachment, which all pipelines of the render pass use.
+    pub(crate) samples: SampleCountFlags,
 }
 
 impl RenderPass {
     pub fn new(device: &LogicalDevice, subpasses: &[SubPass]) -> Self {
-        let attachment_descs = vec![AttachmentDescription::builder()
+        Self::multisampled(device, subpasses, SampleCount::One)
+    }
+
+    /// Creates a render pass that renders to a multisampled color attachment and resolves it
+    /// into a single sampled one, which then holds the image. Frame buffers for it are created
+    /// with [`ImageView::create_resolving_frame_buffer`](crate::ImageView::create_resolving_frame_buffer),
+    /// from an image built with the same [`SampleCount`].
+    ///
+    /// With [`SampleCount::One`], or if the device does not support multisampling, this is the
+    /// same as [`RenderPass::new`].
+    pub fn multisampled(
+        device: &LogicalDevice,
+        subpasses: &[SubPass],
+        samples: SampleCount,
+    ) -> Self {
+        let samples = samples.flags(device);
+        let color = AttachmentDescription::builder()
             .format(Format::R8G8B8A8_UNORM)
             .samples(SampleCountFlags::TYPE_1)
             .load_op(AttachmentLoadOp::CLEAR)
@@ -78,11 +118,42 @@ impl RenderPass {
             .stencil_store_op(AttachmentStoreOp::DONT_CARE)
             .initial_layout(ImageLayout::UNDEFINED)
             .final_layout(ImageLayout::PRESENT_SRC_KHR)
-            .build()];
+            .build();
+        let attachment_descs = if samples == SampleCountFlags::TYPE_1 {
+            vec![color]
+        } else {
+            // The samples are only needed until they are resolved at the end of the subpass.
+            let multisampled = AttachmentDescription {
+                samples,
+                store_op: AttachmentStoreOp::DONT_CARE,
+                final_layout: ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                ..color
+            };
+            let resolve = AttachmentDescription {
+                load_op: AttachmentLoadOp::DONT_CARE,
+                ..color
+            };
+            vec![multisampled, resolve]
+        };
 
+        // The references of the subpasses are created here, so they outlive the creation.
+        let color_attachments = [AttachmentReference::builder()
+            .attachment(0)
+            .layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .build()];
+        let resolve_attachments = [AttachmentReference::builder()
+            .attachment(1)
+            .layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .build()];
         let mut subpass = vec![];
         for i in subpasses {
-            subpass.push(i.0);
+            let mut description = i.0;
+            description.color_attachment_count = color_attachments.len() as u32;
+            description.p_color_attachments = color_attachments.as_ptr();
+            if samples != SampleCountFlags::TYPE_1 {
+                description.p_resolve_attachments = resolve_attachments.as_ptr();
+            }
+            subpass.push(description);
         }
 
         let create_info = RenderPassCreateInfo::builder()
@@ -91,7 +162,7 @@ impl RenderPass {
             .dependencies(&[])
             .build();
         let inner = unsafe { device.inner.create_render_pass(&create_info, None) }.unwrap();
-        Self { inner }
+        Self { inner, samples }
     }
     pub fn create_pipeline(
         &self,
@@ -100,9 +171,11 @@ impl RenderPass {
         stage: &mut Stage,
         mode: PolygonMode,
         topology: PrimitiveTopology,
+        vertex_layout: &VertexLayout,
         width: u32,
         height: u32,
         line_width: f32,
+        blend: BlendMode,
     ) -> VlResult<Vec<Pipeline>> {
         if shaders.is_empty() {
             return Err(VlError::MissingParameter("shaders"));
@@ -168,25 +241,70 @@ impl RenderPass {
             device.inner.update_descriptor_sets(&[write_desc_set], &[]);
         }
 
-        let vertex_binding_description = vec![VertexInputBindingDescription::builder()
-            .binding(0)
-            .stride(std::mem::size_of::<VertexData>() as u32)
-            .input_rate(VertexInputRate::VERTEX)
+        // The per-draw transform, see `CommandBuffer::draw_path`.
+        let push_constant_ranges = vec![PushConstantRange::builder()
+            .stage_flags(ShaderStageFlags::VERTEX)
+            .offset(0)
+            .size(std::mem::size_of::<Mat4<f32>>() as u32)
             .build()];
-        let vertex_input_description = vec![
-            VertexInputAttributeDescription::builder()
-                .binding(0)
-                .location(0)
-                .format(Format::R32G32_SFLOAT)
-                .offset(offset_of!(VertexData, pos) as u32)
-                .build(),
-            VertexInputAttributeDescription::builder()
-                .binding(0)
-                .location(1)
-                .format(Format::R32G32B32A32_SFLOAT)
-                .offset(offset_of!(VertexData, color) as u32)
-                .build(),
-        ];
+        let layout_create_info = PipelineLayoutCreateInfo::builder()
+            .set_layouts(&[desc_set_layout])
+            .push_constant_ranges(&push_constant_ranges)
+            .build();
+
+        let pipeline_layout = match unsafe {
+            device
+                .inner
+                .create_pipeline_layout(&layout_create_info, None)
+        } {
+            Ok(p) => p,
+            Err(e) => {
+                return Err(VlError::from(e));
+            }
+        };
+
+        let state = PipelineState {
+            mode,
+            topology,
+            vertex_layout,
+            width,
+            height,
+            line_width,
+            blend,
+        };
+        let pipelines = self.create_graphics_pipeline(device, shaders, pipeline_layout, &state)?;
+
+        let stage_desc = StageDescriptor {
+            desc_sets,
+            desc_pool,
+            desc_layout: desc_set_layout,
+            pipeline_layout,
+        };
+
+        stage.descriptor = Some(stage_desc);
+
+        Ok(pipelines)
+    }
+
+    /// Creates a pipeline with the fixed function state of this crate and the given layout.
+    pub(crate) fn create_graphics_pipeline(
+        &self,
+        device: &LogicalDevice,
+        shaders: &[Shader],
+        pipeline_layout: PipelineLayout,
+        state: &PipelineState,
+    ) -> VlResult<Vec<Pipeline>> {
+        let PipelineState {
+            mode,
+            topology,
+            vertex_layout,
+            width,
+            height,
+            line_width,
+            blend,
+        } = *state;
+        let vertex_binding_description = vertex_layout.binding_descriptions();
+        let vertex_input_description = vertex_layout.attribute_descriptions();
 
         let mut shader_stages = vec![];
         let entry = CString::new("main").unwrap();
@@ -231,36 +349,13 @@ impl RenderPass {
             .build();
         let multisample = PipelineMultisampleStateCreateInfo::builder()
             .sample_shading_enable(false)
-            .rasterization_samples(SampleCountFlags::TYPE_1)
+            .rasterization_samples(self.samples)
             .build();
-        let blend_attachment = vec![PipelineColorBlendAttachmentState::builder()
-            .color_write_mask(
-                ColorComponentFlags::A
-                    | ColorComponentFlags::R
-                    | ColorComponentFlags::G
-                    | ColorComponentFlags::B,
-            )
-            .blend_enable(false)
-            .build()];
-        let blend = PipelineColorBlendStateCreateInfo::builder()
+        let blend_attachment: Vec<PipelineColorBlendAttachmentState> = vec![blend.into()];
+        let color_blend = PipelineColorBlendStateCreateInfo::builder()
             .logic_op_enable(false)
             .attachments(&blend_attachment)
             .build();
-        let layout_create_info = PipelineLayoutCreateInfo::builder()
-            .set_layouts(&[desc_set_layout])
-            .build();
-
-        let pipeline_layout = match unsafe {
-            device
-                .inner
-                .create_pipeline_layout(&layout_create_info, None)
-        } {
-            Ok(p) => p,
-            Err(e) => {
-                return Err(VlError::from(e));
-            }
-        };
-
         let vertex_input_info = PipelineVertexInputStateCreateInfo::builder()
             .vertex_binding_descriptions(&vertex_binding_description)
             .vertex_attribute_descriptions(&vertex_input_description)
@@ -272,7 +367,7 @@ impl RenderPass {
             .input_assembly_state(&input_assembly)
             .rasterization_state(&rasterizer)
             .multisample_state(&multisample)
-            .color_blend_state(&blend)
+            .color_blend_state(&color_blend)
             .layout(pipeline_layout)
             .vertex_input_state(&vertex_input_info)
             .stages(&[])
@@ -294,21 +389,9 @@ impl RenderPass {
             }
         };
 
-        let mut pipelines = vec![];
-
-        let stage_desc = StageDescriptor {
-            desc_sets,
-            desc_pool,
-            desc_layout: desc_set_layout,
-            pipeline_layout,
-        };
-
-        stage.descriptor = Some(stage_desc);
-
-        for i in pipeline {
-            pipelines.push(Pipeline { inner: i });
-        }
-
-        Ok(pipelines)
+        Ok(pipeline
+            .into_iter()
+            .map(|inner| Pipeline { inner })
+            .collect())
     }
 }
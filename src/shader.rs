@@ -57,6 +57,13 @@ impl Spirv {
         Self { data: spirv }
     }
 
+    /// Reads Spir-V embedded in the crate.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Self {
+        let spirv = read_spv(&mut Cursor::new(bytes)).unwrap();
+
+        Self { data: spirv }
+    }
+
     /// Loads the default vertex shader.
     pub fn vertex_default() -> Self {
         let mut spirv_file = Cursor::new(&DEFAULT_VERTEX_SHADER);
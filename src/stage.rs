@@ -4,7 +4,7 @@ use nalgebra_glm::Mat3;
 
 use crate::{
     geometry::{Buffer, BufferUsage, Mvp},
-    Destroy, Instance, LogicalDevice, Mat4, PhysicalDevice, Vec3, VlError, VlResult,
+    Destroy, Instance, LogicalDevice, Mat4, PhysicalDevice, Vec2, Vec3, Vec4, VlError, VlResult,
 };
 
 pub struct StageBuilder<'a> {
@@ -143,7 +143,64 @@ impl Stage {
     }
 
     pub fn update(&mut self) {
-        let projection = match self.mode {
+        let mvp = self.camera.mvp(self.projection());
+
+        self.buffer
+            .write(
+                vec![mvp.model, mvp.view, mvp.projection].as_ptr() as *const c_void,
+                std::mem::size_of::<Mvp>(),
+            )
+            .unwrap();
+    }
+
+    /// Returns how many pixels one unit of geometry covers on the image.
+    pub fn pixel_scale(&self) -> f32 {
+        let mvp = self.camera.mvp(self.projection());
+        let matrix = mvp.projection * mvp.view * mvp.model;
+        let project = |x: f32, y: f32| {
+            let clip = matrix * Vec4::new(x, y, 0.0, 1.0);
+            Vec2::new(
+                clip.x / clip.w * self.width as f32 / 2.0,
+                clip.y / clip.w * self.height as f32 / 2.0,
+            )
+        };
+        let origin = project(0.0, 0.0);
+        let scale = (project(1.0, 0.0) - origin)
+            .norm()
+            .max((project(0.0, 1.0) - origin).norm());
+        if scale.is_finite() && scale > 0.0 {
+            scale
+        } else {
+            1.0
+        }
+    }
+
+    /// Maps a point in window coordinates, with the origin at the top-left corner,
+    /// to the point on the z = 0 plane of the world that is drawn there.
+    ///
+    /// Returns `None` if the camera looks along the plane.
+    pub fn window_to_world(&self, point: Vec2<f32>) -> Option<Vec2<f32>> {
+        let mvp = self.camera.mvp(self.projection());
+        let inverse = (mvp.projection * mvp.view * mvp.model).try_inverse()?;
+        let ndc = Vec2::new(
+            point.x / self.width as f32 * 2.0 - 1.0,
+            point.y / self.height as f32 * 2.0 - 1.0,
+        );
+        let unproject = |z: f32| {
+            let p = inverse * Vec4::new(ndc.x, ndc.y, z, 1.0);
+            Vec3::new(p.x, p.y, p.z) / p.w
+        };
+        let (near, far) = (unproject(0.0), unproject(1.0));
+        let direction = far - near;
+        if direction.z.abs() < f32::EPSILON {
+            return None;
+        }
+        let p = near - direction * (near.z / direction.z);
+        Some(Vec2::new(p.x, p.y))
+    }
+
+    fn projection(&self) -> Mat4<f32> {
+        match self.mode {
             StageMode::Ortho => {
                 nalgebra_glm::ortho(0.0, self.width as f32, 0.0, self.height as f32, -1.0, 1.0)
             }
@@ -153,16 +210,7 @@ impl Stage {
                 0.1,
                 10.0,
             ),
-        };
-
-        let mvp = self.camera.mvp(projection);
-
-        self.buffer
-            .write(
-                vec![mvp.model, mvp.view, mvp.projection].as_ptr() as *const c_void,
-                std::mem::size_of::<Mvp>(),
-            )
-            .unwrap();
+        }
     }
 }
 
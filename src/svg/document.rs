@@ -0,0 +1,587 @@
+use roxmltree::{Attribute, Node};
+
+use crate::{
+    geometry::{FillRule, LineCap, LineJoin, PathGeometry, StrokeStyle, Transform},
+    offset_error, Color, RenderTarget, Vec2, Vec4, VlError, VlResult,
+};
+
+use super::path::parse_numbers;
+
+/// # Document
+/// A parsed SVG document, ready to be drawn onto any [`RenderTarget`].
+///
+/// Supported elements are `<rect>`, `<circle>`, `<ellipse>`, `<line>`, `<polyline>`, `<polygon>`,
+/// `<path>`, `<g>` and nested `<svg>`, together with `transform`, `fill`, `stroke`, `stroke-width`,
+/// `fill-rule`, the stroke line join, cap, miter limit and dash pattern, and the opacity properties.
+/// Properties can be given as attributes or in the `style` attribute.
+/// Nested `<svg>` elements are positioned and scaled, but their contents are not clipped.
+/// Other elements such as `<text>` or `<defs>` are skipped.
+///
+/// ## Example
+/// ```no_run
+/// # fn draw(target: &mut impl vulx::RenderTarget) {
+/// let mut document = vulx::svg::Document::parse(
+///     r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+///         <circle cx="50" cy="50" r="40" fill="red" stroke="black" stroke-width="4"/>
+///     </svg>"#,
+/// )
+/// .unwrap();
+/// target.begin();
+/// document.draw(target);
+/// target.end();
+/// # }
+/// ```
+pub struct Document {
+    size: Vec2<f32>,
+    pub(crate) elements: Vec<Element>,
+}
+
+pub(crate) struct Element {
+    pub(crate) fill: Option<PathGeometry>,
+    pub(crate) stroke: Option<(PathGeometry, f32)>,
+}
+
+impl Document {
+    /// Parses an SVG document.
+    ///
+    /// Returns [`VlError::ParseError`] with the byte offset of the malformed XML or attribute value.
+    pub fn parse(text: &str) -> VlResult<Self> {
+        let xml = roxmltree::Document::parse(text).map_err(|e| {
+            let position = e.pos();
+            VlError::ParseError(
+                byte_offset(text, position.row, position.col),
+                "Malformed XML.",
+            )
+        })?;
+        let root = xml.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err(VlError::ParseError(
+                root.range().start,
+                "The root element must be <svg>.",
+            ));
+        }
+
+        let (size, view_box) = viewport(text, root, None)?;
+        let transform = match attribute(text, root, "transform") {
+            Some((value, position)) => view_box.then(&parse_transform(value, position)?),
+            None => view_box,
+        };
+
+        let mut document = Self {
+            size,
+            elements: vec![],
+        };
+        let mut parser = Parser {
+            text,
+            elements: &mut document.elements,
+            viewport: size,
+        };
+        let mut style = Style::default();
+        if parser.style(root, &mut style)? {
+            parser.container(root, &style, &transform)?;
+        }
+        Ok(document)
+    }
+
+    /// Size of the document in pixels, taken from the `width` and `height` of the root element.
+    pub fn size(&self) -> Vec2<f32> {
+        self.size
+    }
+
+    /// Draws all elements in document order.
+    /// Must be called between [`RenderTarget::begin`] and [`RenderTarget::end`].
+    pub fn draw(&mut self, target: &mut impl RenderTarget) {
+        for element in &mut self.elements {
+            if let Some(fill) = &mut element.fill {
+                target.fill(fill);
+            }
+            if let Some((stroke, width)) = &mut element.stroke {
+                target.stroke(stroke, *width as f64);
+            }
+        }
+    }
+}
+
+/// Properties that are inherited from the parent element.
+#[derive(Clone)]
+struct Style {
+    color: Vec4<f32>,
+    fill: Option<Vec4<f32>>,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: Option<Vec4<f32>>,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    line_join: LineJoin,
+    line_cap: LineCap,
+    miter_limit: f32,
+    dash_array: Vec<f32>,
+    dash_offset: f32,
+    opacity: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        let black = Vec4::new(0.0, 0.0, 0.0, 1.0);
+        Self {
+            color: black,
+            fill: Some(black),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            line_join: LineJoin::Miter,
+            line_cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dash_array: vec![],
+            dash_offset: 0.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+struct Parser<'a, 'input> {
+    text: &'input str,
+    elements: &'a mut Vec<Element>,
+    /// Size of the viewport established by the innermost `<svg>` element.
+    viewport: Vec2<f32>,
+}
+
+impl<'a, 'input> Parser<'a, 'input> {
+    fn container(
+        &mut self,
+        node: Node<'_, 'input>,
+        style: &Style,
+        transform: &Transform,
+    ) -> VlResult<()> {
+        for child in node.children().filter(|n| n.is_element()) {
+            let mut style = style.clone();
+            if !self.style(child, &mut style)? {
+                continue;
+            }
+            let transform = match attribute(self.text, child, "transform") {
+                Some((value, position)) => parse_transform(value, position)?.then(transform),
+                None => *transform,
+            };
+
+            match child.tag_name().name() {
+                "g" | "a" | "switch" => self.container(child, &style, &transform)?,
+                "svg" => self.nested(child, &style, &transform)?,
+                _ => self.shape(child, &style, &transform)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the contents of a nested `<svg>` into the viewport at its `x` and `y`.
+    /// The contents are not clipped to the viewport.
+    fn nested(
+        &mut self,
+        node: Node<'_, 'input>,
+        style: &Style,
+        transform: &Transform,
+    ) -> VlResult<()> {
+        let (size, view_box) = viewport(self.text, node, Some(self.viewport))?;
+        let zero = |name: &str| match attribute(self.text, node, name) {
+            Some((value, position)) => parse_length(value, position),
+            None => Ok(0.0),
+        };
+        let transform = view_box
+            .then(&Transform::translation(zero("x")?, zero("y")?))
+            .then(transform);
+
+        let parent = std::mem::replace(&mut self.viewport, size);
+        let result = self.container(node, style, &transform);
+        self.viewport = parent;
+        result
+    }
+
+    /// Applies the properties of `node` to `style`. Returns false if the element is not displayed.
+    fn style(&self, node: Node<'_, 'input>, style: &mut Style) -> VlResult<bool> {
+        // Declarations in `style` come last, so they take precedence over presentation attributes.
+        let mut declarations = vec![];
+        let mut style_declarations = vec![];
+        for attribute in node.attributes() {
+            let position = value_position(self.text, &attribute);
+            if attribute.name() == "style" {
+                for declaration in attribute.value().split(';') {
+                    if let Some((name, value)) = declaration.split_once(':') {
+                        style_declarations.push((name.trim(), value.trim(), position));
+                    }
+                }
+            } else {
+                declarations.push((attribute.name(), attribute.value().trim(), position));
+            }
+        }
+        declarations.append(&mut style_declarations);
+
+        let mut opacity = 1.0;
+        for (name, value, position) in declarations {
+            match name {
+                "display" if value == "none" => return Ok(false),
+                "visibility" if value == "hidden" || value == "collapse" => return Ok(false),
+                "color" => {
+                    if let Some(color) = parse_paint(value, style.color, position)? {
+                        style.color = color;
+                    }
+                }
+                "fill" => style.fill = parse_paint(value, style.color, position)?,
+                "stroke" => style.stroke = parse_paint(value, style.color, position)?,
+                "fill-opacity" => style.fill_opacity = parse_opacity(value, position)?,
+                "stroke-opacity" => style.stroke_opacity = parse_opacity(value, position)?,
+                "opacity" => opacity = parse_opacity(value, position)?,
+                "stroke-width" => style.stroke_width = parse_length(value, position)?,
+                "stroke-miterlimit" => style.miter_limit = parse_number(value, position)?,
+                "stroke-dasharray" if value == "none" => style.dash_array.clear(),
+                "stroke-dasharray" => {
+                    style.dash_array = value
+                        .split(|c: char| c == ',' || c.is_ascii_whitespace())
+                        .filter(|l| !l.is_empty())
+                        .map(|l| parse_length(l, position))
+                        .collect::<VlResult<_>>()?
+                }
+                "stroke-dashoffset" => style.dash_offset = parse_length(value, position)?,
+                "fill-rule" => {
+                    style.fill_rule = match value {
+                        "nonzero" => FillRule::NonZero,
+                        "evenodd" => FillRule::EvenOdd,
+                        _ => return Err(VlError::ParseError(position, "Unknown fill rule.")),
+                    }
+                }
+                "stroke-linejoin" => {
+                    style.line_join = match value {
+                        "miter" | "miter-clip" | "arcs" => LineJoin::Miter,
+                        "round" => LineJoin::Round,
+                        "bevel" => LineJoin::Bevel,
+                        _ => return Err(VlError::ParseError(position, "Unknown line join.")),
+                    }
+                }
+                "stroke-linecap" => {
+                    style.line_cap = match value {
+                        "butt" => LineCap::Butt,
+                        "round" => LineCap::Round,
+                        "square" => LineCap::Square,
+                        _ => return Err(VlError::ParseError(position, "Unknown line cap.")),
+                    }
+                }
+                _ => (),
+            }
+        }
+        style.opacity *= opacity;
+        Ok(true)
+    }
+
+    fn shape(
+        &mut self,
+        node: Node<'_, 'input>,
+        style: &Style,
+        transform: &Transform,
+    ) -> VlResult<()> {
+        let fillable = node.tag_name().name() != "line";
+        let fill = match style.fill {
+            Some(color) if fillable => {
+                let mut geometry = PathGeometry::new();
+                geometry.color(color_with_alpha(color, style.fill_opacity * style.opacity));
+                geometry.fill_rule(style.fill_rule);
+                if !self.outline(node, &mut geometry)? {
+                    return Ok(());
+                }
+                geometry.transform(transform);
+                Some(geometry)
+            }
+            _ => None,
+        };
+        let stroke = match style.stroke {
+            Some(color) if style.stroke_width > 0.0 => {
+                // Strokes are generated after transforming, so lengths are scaled along.
+                let scale = |lengths: &[f32]| {
+                    lengths
+                        .iter()
+                        .map(|l| l * scale_factor(transform))
+                        .collect::<Vec<_>>()
+                };
+                let mut geometry = PathGeometry::new();
+                geometry.color(color_with_alpha(
+                    color,
+                    style.stroke_opacity * style.opacity,
+                ));
+                geometry.stroke_style(
+                    StrokeStyle::new()
+                        .line_join(style.line_join)
+                        .line_cap(style.line_cap)
+                        .miter_limit(style.miter_limit)
+                        .dash_array(&scale(&style.dash_array))
+                        .dash_offset(style.dash_offset * scale_factor(transform)),
+                );
+                if !self.outline(node, &mut geometry)? {
+                    return Ok(());
+                }
+                geometry.transform(transform);
+                Some((geometry, style.stroke_width * scale_factor(transform)))
+            }
+            _ => None,
+        };
+
+        if fill.is_some() || stroke.is_some() {
+            self.elements.push(Element { fill, stroke });
+        }
+        Ok(())
+    }
+
+    /// Adds the outline of a basic shape to `geometry`.
+    /// Returns false if the element is not a shape or has nothing to draw.
+    fn outline(&self, node: Node<'_, 'input>, geometry: &mut PathGeometry) -> VlResult<bool> {
+        let length = |name: &str| match attribute(self.text, node, name) {
+            Some((value, position)) => parse_length(value, position).map(Some),
+            None => Ok(None),
+        };
+        let zero = |name: &str| length(name).map(|l| l.unwrap_or(0.0));
+
+        match node.tag_name().name() {
+            "rect" => {
+                let position = Vec2::new(zero("x")?, zero("y")?);
+                let size = Vec2::new(zero("width")?, zero("height")?);
+                if size.x <= 0.0 || size.y <= 0.0 {
+                    return Ok(false);
+                }
+                let (rx, ry) = match (length("rx")?, length("ry")?) {
+                    (Some(rx), Some(ry)) => (rx, ry),
+                    (Some(r), None) | (None, Some(r)) => (r, r),
+                    (None, None) => (0.0, 0.0),
+                };
+                let (rx, ry) = (rx.clamp(0.0, size.x / 2.0), ry.clamp(0.0, size.y / 2.0));
+                if rx <= 0.0 || ry <= 0.0 {
+                    geometry.rounded_rectangle(position, size, Vec4::zeros());
+                } else {
+                    // Elliptical corners are circular ones stretched vertically.
+                    // `geometry` holds only this shape, so it can be scaled as a whole.
+                    let stretch = ry / rx;
+                    geometry.rounded_rectangle(
+                        Vec2::new(position.x, position.y / stretch),
+                        Vec2::new(size.x, size.y / stretch),
+                        Vec4::repeat(rx),
+                    );
+                    geometry.transform(&Transform::scaling(1.0, stretch));
+                }
+            }
+            "circle" => {
+                let r = zero("r")?;
+                if r <= 0.0 {
+                    return Ok(false);
+                }
+                geometry.circle(Vec2::new(zero("cx")?, zero("cy")?), r);
+            }
+            "ellipse" => {
+                let radii = Vec2::new(zero("rx")?, zero("ry")?);
+                if radii.x <= 0.0 || radii.y <= 0.0 {
+                    return Ok(false);
+                }
+                geometry.ellipse(Vec2::new(zero("cx")?, zero("cy")?), radii);
+            }
+            "line" => {
+                geometry.move_to(Vec2::new(zero("x1")?, zero("y1")?));
+                geometry.line_to(Vec2::new(zero("x2")?, zero("y2")?));
+            }
+            "polyline" | "polygon" => {
+                let points = match attribute(self.text, node, "points") {
+                    Some((value, position)) => parse_numbers(value)
+                        .map_err(|e| offset_error(e, position))?
+                        .chunks_exact(2)
+                        .map(|p| Vec2::new(p[0], p[1]))
+                        .collect::<Vec<_>>(),
+                    None => return Ok(false),
+                };
+                let Some((first, rest)) = points.split_first() else {
+                    return Ok(false);
+                };
+                geometry.move_to(*first);
+                for point in rest {
+                    geometry.line_to(*point);
+                }
+                if node.tag_name().name() == "polygon" {
+                    geometry.close();
+                }
+            }
+            "path" => match attribute(self.text, node, "d") {
+                Some((value, position)) => geometry
+                    .svg_path(value)
+                    .map_err(|e| offset_error(e, position))?,
+                None => return Ok(false),
+            },
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Average scale of a transform, used to scale stroke widths and dash lengths.
+fn scale_factor(transform: &Transform) -> f32 {
+    let m = transform.matrix();
+    (m[(0, 0)] * m[(1, 1)] - m[(0, 1)] * m[(1, 0)]).abs().sqrt()
+}
+
+fn parse_transform(value: &str, position: usize) -> VlResult<Transform> {
+    let mut transform = Transform::identity();
+    let mut rest = value;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            return Ok(transform);
+        }
+        let offset = position + value.len() - rest.len();
+        let error = |cause| VlError::ParseError(offset, cause);
+
+        let (name, tail) = rest.split_once('(').ok_or(error("Expected a transform."))?;
+        let (arguments, tail) = tail.split_once(')').ok_or(error("Expected `)`."))?;
+        let n = parse_numbers(arguments).map_err(|_| error("Invalid transform arguments."))?;
+        let next = match (name.trim(), n.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform::from_2d(a, b, c, d, e, f),
+            ("translate", &[x]) => Transform::translation(x, 0.0),
+            ("translate", &[x, y]) => Transform::translation(x, y),
+            ("scale", &[s]) => Transform::scaling(s, s),
+            ("scale", &[x, y]) => Transform::scaling(x, y),
+            ("rotate", &[angle]) => Transform::rotation(angle.to_radians()),
+            ("rotate", &[angle, x, y]) => {
+                Transform::rotation_around(angle.to_radians(), Vec2::new(x, y))
+            }
+            ("skewX", &[angle]) => Transform::skew(angle.to_radians(), 0.0),
+            ("skewY", &[angle]) => Transform::skew(0.0, angle.to_radians()),
+            _ => return Err(error("Unknown transform.")),
+        };
+        // Transforms in the list apply to the coordinates from right to left.
+        transform = next.then(&transform);
+        rest = tail;
+    }
+}
+
+/// Reads the size and `viewBox` of an `<svg>` element.
+///
+/// Returns the size of its viewport and the transform from the view box onto the viewport.
+/// A missing `width` or `height` is taken from `default_size`, or from the view box if that is `None`.
+fn viewport(
+    text: &str,
+    node: Node<'_, '_>,
+    default_size: Option<Vec2<f32>>,
+) -> VlResult<(Vec2<f32>, Transform)> {
+    let view_box = match attribute(text, node, "viewBox") {
+        Some((value, position)) => match parse_numbers(value) {
+            Ok(numbers) if numbers.len() == 4 && numbers[2] > 0.0 && numbers[3] > 0.0 => {
+                Some(numbers)
+            }
+            _ => return Err(VlError::ParseError(position, "Invalid viewBox.")),
+        },
+        None => None,
+    };
+    let length_or = |name: &str, default: f32| match attribute(text, node, name) {
+        Some((value, position)) => parse_length(value, position),
+        None => Ok(default),
+    };
+    let default_size = match (default_size, &view_box) {
+        (Some(size), _) => size,
+        (None, Some(v)) => Vec2::new(v[2], v[3]),
+        (None, None) => Vec2::new(300.0, 150.0),
+    };
+    let size = Vec2::new(
+        length_or("width", default_size.x)?,
+        length_or("height", default_size.y)?,
+    );
+
+    // The view box is centered and scaled uniformly, as with `preserveAspectRatio="xMidYMid meet"`.
+    let transform = match view_box {
+        Some(v) => {
+            let scale = (size.x / v[2]).min(size.y / v[3]);
+            Transform::scaling(scale, scale).then(&Transform::translation(
+                (size.x - v[2] * scale) / 2.0 - v[0] * scale,
+                (size.y - v[3] * scale) / 2.0 - v[1] * scale,
+            ))
+        }
+        None => Transform::identity(),
+    };
+    Ok((size, transform))
+}
+
+/// Parses a paint. Returns `None` for `none` and for paint servers such as gradients.
+fn parse_paint(value: &str, current: Vec4<f32>, position: usize) -> VlResult<Option<Vec4<f32>>> {
+    let value = value.trim();
+    match value {
+        "none" => Ok(None),
+        "currentColor" => Ok(Some(current)),
+        _ if value.starts_with("url(") => Ok(None),
+        _ => match value.parse::<Color>() {
+            Ok(color) => Ok(Some(color.into())),
+            Err(_) => Err(VlError::ParseError(position, "Unknown color.")),
+        },
+    }
+}
+
+fn color_with_alpha(color: Vec4<f32>, alpha: f32) -> Vec4<f32> {
+    Vec4::new(color.x, color.y, color.z, color.w * alpha)
+}
+
+fn parse_number(value: &str, position: usize) -> VlResult<f32> {
+    match parse_numbers(value) {
+        Ok(numbers) if numbers.len() == 1 => Ok(numbers[0]),
+        _ => Err(VlError::ParseError(position, "Expected a number.")),
+    }
+}
+
+fn parse_opacity(value: &str, position: usize) -> VlResult<f32> {
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => parse_number(percent, position)? / 100.0,
+        None => parse_number(value, position)?,
+    };
+    Ok(opacity.clamp(0.0, 1.0))
+}
+
+/// Parses a length and converts it to pixels. Percentages are not supported.
+fn parse_length(value: &str, position: usize) -> VlResult<f32> {
+    let value = value.trim();
+    let units = [
+        ("px", 1.0),
+        ("pt", 4.0 / 3.0),
+        ("pc", 16.0),
+        ("mm", 96.0 / 25.4),
+        ("cm", 96.0 / 2.54),
+        ("in", 96.0),
+    ];
+    let (number, factor) = units
+        .iter()
+        .find_map(|(unit, factor)| value.strip_suffix(unit).map(|n| (n, *factor)))
+        .unwrap_or((value, 1.0));
+    if number.ends_with(|c: char| c.is_ascii_alphabetic() || c == '%') {
+        return Err(VlError::ParseError(position, "Unsupported length unit."));
+    }
+    Ok(parse_number(number, position)? * factor)
+}
+
+/// Returns the value of an attribute together with its byte offset in the document.
+fn attribute<'a>(text: &str, node: Node<'a, '_>, name: &str) -> Option<(&'a str, usize)> {
+    node.attributes()
+        .find(|a| a.name() == name && a.namespace().is_none())
+        .map(|a| (a.value(), value_position(text, &a)))
+}
+
+fn value_position(text: &str, attribute: &Attribute) -> usize {
+    let start = attribute.position();
+    text[start..]
+        .find(['"', '\''])
+        .map(|quote| start + quote + 1)
+        .unwrap_or(start)
+}
+
+/// Converts a 1-based row and column into a byte offset.
+fn byte_offset(text: &str, row: u32, col: u32) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(row.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum();
+    let line = &text[line_start.min(text.len())..];
+    line_start
+        + line
+            .char_indices()
+            .nth(col.saturating_sub(1) as usize)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len())
+}
@@ -0,0 +1,7 @@
+//! Import of SVG data.
+
+mod document;
+mod path;
+
+pub use document::*;
+pub use path::*;
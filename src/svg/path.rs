@@ -0,0 +1,354 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    geometry::{arc_to_cubics, PathGeometry},
+    Vec2, VlError, VlResult,
+};
+
+/// Parses SVG path data (the `d` attribute of `<path>`) into a new [`PathGeometry`].
+///
+/// All commands of the path mini-language are supported in their absolute and relative forms.
+///
+/// ## Example
+/// ```no_run
+/// let path = vulx::svg::parse_path("M10 10 h 80 v 80 h -80 Z").unwrap();
+/// ```
+pub fn parse_path(data: &str) -> VlResult<PathGeometry> {
+    let mut geometry = PathGeometry::new();
+    geometry.svg_path(data)?;
+    Ok(geometry)
+}
+
+impl PathGeometry {
+    /// Parses SVG path data and appends its contours to this geometry.
+    ///
+    /// Returns [`VlError::ParseError`] with the byte offset of the first malformed token.
+    pub fn svg_path(&mut self, data: &str) -> VlResult<()> {
+        PathParser::new(data, self).parse()
+    }
+}
+
+struct PathParser<'a> {
+    data: &'a [u8],
+    position: usize,
+    geometry: &'a mut PathGeometry,
+
+    current: Vec2<f32>,
+    start: Vec2<f32>,
+    /// Second control point of the previous cubic or control point of the previous quadratic curve.
+    cubic_control: Option<Vec2<f32>>,
+    quad_control: Option<Vec2<f32>>,
+}
+
+impl<'a> PathParser<'a> {
+    fn new(data: &'a str, geometry: &'a mut PathGeometry) -> Self {
+        Self {
+            data: data.as_bytes(),
+            position: 0,
+            geometry,
+            current: Vec2::new(0.0, 0.0),
+            start: Vec2::new(0.0, 0.0),
+            cubic_control: None,
+            quad_control: None,
+        }
+    }
+
+    fn parse(mut self) -> VlResult<()> {
+        self.skip_separators();
+        if self.position >= self.data.len() {
+            return Ok(());
+        }
+
+        let mut command = match self.data[self.position] {
+            b'M' | b'm' => self.data[self.position],
+            _ => return Err(self.error("Path data must start with a moveto command.")),
+        };
+        self.position += 1;
+
+        loop {
+            self.command(command)?;
+            self.skip_separators();
+            if self.position >= self.data.len() {
+                return Ok(());
+            }
+            let next = self.data[self.position];
+            if next.is_ascii_alphabetic() && next != b'e' && next != b'E' {
+                command = next;
+                self.position += 1;
+            } else if command == b'Z' || command == b'z' {
+                return Err(self.error("Unexpected number after closepath."));
+            } else if command == b'M' {
+                // Coordinates following a moveto are implicit lineto commands.
+                command = b'L';
+            } else if command == b'm' {
+                command = b'l';
+            }
+        }
+    }
+
+    fn command(&mut self, command: u8) -> VlResult<()> {
+        let relative = command.is_ascii_lowercase();
+        let origin = if relative {
+            self.current
+        } else {
+            Vec2::new(0.0, 0.0)
+        };
+
+        let mut cubic_control = None;
+        let mut quad_control = None;
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                let p = origin + self.point()?;
+                self.geometry.move_to(p);
+                self.start = p;
+                self.current = p;
+            }
+            b'L' => {
+                let p = origin + self.point()?;
+                self.line_to(p);
+            }
+            b'H' => {
+                let x = self.number()? + origin.x;
+                self.line_to(Vec2::new(x, self.current.y));
+            }
+            b'V' => {
+                let y = self.number()? + origin.y;
+                self.line_to(Vec2::new(self.current.x, y));
+            }
+            b'C' => {
+                let c1 = origin + self.point()?;
+                let c2 = origin + self.point()?;
+                let p = origin + self.point()?;
+                self.geometry.cubic_to(c1, c2, p);
+                cubic_control = Some(c2);
+                self.current = p;
+            }
+            b'S' => {
+                let c1 = self.reflect(self.cubic_control);
+                let c2 = origin + self.point()?;
+                let p = origin + self.point()?;
+                self.geometry.cubic_to(c1, c2, p);
+                cubic_control = Some(c2);
+                self.current = p;
+            }
+            b'Q' => {
+                let c = origin + self.point()?;
+                let p = origin + self.point()?;
+                self.geometry.quad_to(c, p);
+                quad_control = Some(c);
+                self.current = p;
+            }
+            b'T' => {
+                let c = self.reflect(self.quad_control);
+                let p = origin + self.point()?;
+                self.geometry.quad_to(c, p);
+                quad_control = Some(c);
+                self.current = p;
+            }
+            b'A' => {
+                let rx = self.number()?;
+                let ry = self.number()?;
+                let rotation = self.number()?;
+                let large_arc = self.flag()?;
+                let sweep = self.flag()?;
+                let p = origin + self.point()?;
+                self.arc_to(Vec2::new(rx, ry), rotation, large_arc, sweep, p);
+            }
+            b'Z' => {
+                self.geometry.close();
+                self.current = self.start;
+            }
+            _ => {
+                self.position -= 1;
+                return Err(self.error("Unknown path command."));
+            }
+        }
+        self.cubic_control = cubic_control;
+        self.quad_control = quad_control;
+
+        Ok(())
+    }
+
+    fn line_to(&mut self, p: Vec2<f32>) {
+        self.geometry.line_to(p);
+        self.current = p;
+    }
+
+    fn reflect(&self, control: Option<Vec2<f32>>) -> Vec2<f32> {
+        match control {
+            Some(c) => self.current * 2.0 - c,
+            None => self.current,
+        }
+    }
+
+    /// Converts an endpoint-parameterized arc to cubic curves (SVG 1.1, appendix F.6).
+    fn arc_to(
+        &mut self,
+        radii: Vec2<f32>,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        p: Vec2<f32>,
+    ) {
+        let from = self.current;
+        let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+        if from == p {
+            return;
+        }
+        if rx == 0.0 || ry == 0.0 {
+            self.line_to(p);
+            return;
+        }
+
+        let phi = rotation.to_radians();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let half = (from - p) / 2.0;
+        let x1 = cos_phi * half.x + sin_phi * half.y;
+        let y1 = -sin_phi * half.x + cos_phi * half.y;
+
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coefficient = (numerator / denominator).max(0.0).sqrt();
+        if large_arc == sweep {
+            coefficient = -coefficient;
+        }
+        let cx1 = coefficient * rx * y1 / ry;
+        let cy1 = -coefficient * ry * x1 / rx;
+        let middle = (from + p) / 2.0;
+        let center = Vec2::new(
+            cos_phi * cx1 - sin_phi * cy1 + middle.x,
+            sin_phi * cx1 + cos_phi * cy1 + middle.y,
+        );
+
+        let angle = |x: f32, y: f32| y.atan2(x);
+        let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let end = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        let mut delta = (end - start) % TAU;
+        if !sweep && delta > 0.0 {
+            delta -= TAU;
+        } else if sweep && delta < 0.0 {
+            delta += TAU;
+        }
+
+        let mut curves = arc_to_cubics(center, Vec2::new(rx, ry), phi, start, delta);
+        if let Some(last) = curves.last_mut() {
+            last.2 = p;
+        }
+        for (c1, c2, end) in curves {
+            self.geometry.cubic_to(c1, c2, end);
+        }
+        self.current = p;
+    }
+
+    fn skip_separators(&mut self) {
+        while self.position < self.data.len()
+            && (self.data[self.position].is_ascii_whitespace() || self.data[self.position] == b',')
+        {
+            self.position += 1;
+        }
+    }
+
+    fn point(&mut self) -> VlResult<Vec2<f32>> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(Vec2::new(x, y))
+    }
+
+    fn flag(&mut self) -> VlResult<bool> {
+        self.skip_separators();
+        match self.data.get(self.position) {
+            Some(b'0') => {
+                self.position += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.position += 1;
+                Ok(true)
+            }
+            _ => Err(self.error("Expected an arc flag (0 or 1).")),
+        }
+    }
+
+    fn number(&mut self) -> VlResult<f32> {
+        self.skip_separators();
+        match scan_number(self.data, self.position) {
+            Some((value, end)) => {
+                self.position = end;
+                Ok(value)
+            }
+            None => Err(self.error("Expected a number.")),
+        }
+    }
+
+    fn error(&self, cause: &'static str) -> VlError {
+        VlError::ParseError(self.position, cause)
+    }
+}
+
+/// Reads a number in SVG syntax starting at `position`.
+/// Returns the value and the position right after it.
+pub(crate) fn scan_number(data: &[u8], position: usize) -> Option<(f32, usize)> {
+    let digits = |end: &mut usize| {
+        let begin = *end;
+        while *end < data.len() && data[*end].is_ascii_digit() {
+            *end += 1;
+        }
+        *end > begin
+    };
+
+    let mut end = position;
+    if end < data.len() && (data[end] == b'+' || data[end] == b'-') {
+        end += 1;
+    }
+    let mut has_digits = digits(&mut end);
+    if end < data.len() && data[end] == b'.' {
+        end += 1;
+        has_digits |= digits(&mut end);
+    }
+    if !has_digits {
+        return None;
+    }
+    if end < data.len() && (data[end] == b'e' || data[end] == b'E') {
+        let mut exponent = end + 1;
+        if exponent < data.len() && (data[exponent] == b'+' || data[exponent] == b'-') {
+            exponent += 1;
+        }
+        if digits(&mut exponent) {
+            end = exponent;
+        }
+    }
+
+    // The slice only contains ASCII digits, signs, '.' and 'e'.
+    let text = std::str::from_utf8(&data[position..end]).ok()?;
+    text.parse::<f32>().ok().map(|value| (value, end))
+}
+
+/// Parses a list of numbers separated by whitespace and/or commas, such as `points` of `<polygon>`.
+pub(crate) fn parse_numbers(text: &str) -> VlResult<Vec<f32>> {
+    let data = text.as_bytes();
+    let mut numbers = vec![];
+    let mut position = 0;
+    loop {
+        while position < data.len()
+            && (data[position].is_ascii_whitespace() || data[position] == b',')
+        {
+            position += 1;
+        }
+        if position >= data.len() {
+            return Ok(numbers);
+        }
+        match scan_number(data, position) {
+            Some((value, end)) => {
+                numbers.push(value);
+                position = end;
+            }
+            None => return Err(VlError::ParseError(position, "Expected a number.")),
+        }
+    }
+}
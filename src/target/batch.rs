@@ -0,0 +1,607 @@
+use ash::vk::{DescriptorSet, Fence, IndexType, PipelineLayout};
+
+use super::{CommandBuffer, GeometryHandle, RetainedPaths};
+
+use crate::{
+    brush::Brush,
+    geometry::{
+        as_bytes, Buffer, BufferUsage, IndexBuffer, InstanceData, Path, Tessellation, Transform,
+        VertexData,
+    },
+    Destroy, Instance, LogicalDevice, PhysicalDevice, Pipeline, TexturedVertex, Vec4, VlResult,
+};
+
+/// A range of the bound index buffer drawn with one `cmd_draw_indexed`.
+#[derive(Clone, Copy)]
+pub(crate) struct BatchRange {
+    pub(crate) first_index: u32,
+    pub(crate) index_count: u32,
+    pub(crate) vertex_offset: i32,
+}
+
+/// A range of the per-instance buffer of the frame.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct InstanceRange {
+    pub(crate) first: u32,
+    pub(crate) count: u32,
+}
+
+impl InstanceRange {
+    /// The neutral instance at the start of every frame, used by draws that are not instanced.
+    ///
+    /// These draws add nothing to the instance buffer, which is written and bound once per frame
+    /// whether or not anything is instanced. The identity transform and white color of the
+    /// instance leave their vertices unchanged.
+    pub(crate) const SINGLE: InstanceRange = InstanceRange { first: 0, count: 1 };
+}
+
+/// A pipeline with its layout and the descriptor set its draws use.
+#[derive(Clone, Copy)]
+pub(crate) struct PipelineBinding {
+    pub(crate) pipeline: Pipeline,
+    pub(crate) layout: PipelineLayout,
+    pub(crate) descriptor_set: DescriptorSet,
+}
+
+impl PartialEq for PipelineBinding {
+    fn eq(&self, other: &Self) -> bool {
+        self.pipeline.inner == other.pipeline.inner && self.descriptor_set == other.descriptor_set
+    }
+}
+
+pub(crate) enum Geometry {
+    /// A path uploaded into its own buffers.
+    Path(usize),
+    Batch(BatchRange),
+    /// A path that stays uploaded across frames.
+    Retained(GeometryHandle),
+    /// Triangles in the shared textured buffers, such as glyph quads, drawn with their own pipeline.
+    Textured(BatchRange, PipelineBinding),
+    /// Triangles in the shared buffers painted with the brush at an offset of the brush data.
+    Brush(BatchRange, PipelineBinding, u32),
+}
+
+/// The shared buffers that can be bound to binding 0.
+#[derive(Clone, Copy, PartialEq)]
+enum Shared {
+    Batch,
+    Textured,
+}
+
+pub(crate) struct Draw {
+    pub(crate) geometry: Geometry,
+    pub(crate) transform: Transform,
+    pub(crate) instances: InstanceRange,
+}
+
+/// A host visible buffer that is rewritten every frame, and only replaced when it has to grow.
+struct StreamBuffer {
+    usage: BufferUsage,
+    buffer: Option<Buffer>,
+}
+
+impl StreamBuffer {
+    fn new(usage: BufferUsage) -> Self {
+        Self {
+            usage,
+            buffer: None,
+        }
+    }
+
+    /// Copies `data` to the start of the buffer. A buffer that is too small is moved to `retired`
+    /// and replaced by one whose size is rounded up to a power of two.
+    /// The GPU must have finished reading the buffer.
+    fn write(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        data: &[u8],
+        retired: &mut Vec<Buffer>,
+    ) {
+        if !self.buffer.as_ref().is_some_and(|b| b.size >= data.len()) {
+            retired.extend(self.buffer.take());
+            let capacity = data.len().max(1).next_power_of_two();
+            let mut buffer = Buffer::new(instance, physical_device, device, capacity, self.usage);
+            buffer.allocate(device);
+            self.buffer = Some(buffer);
+        }
+        let buffer = self.buffer.as_ref().unwrap();
+        buffer
+            .write(data.as_ptr() as *const std::ffi::c_void, data.len())
+            .unwrap();
+        buffer.flush_memory(device).unwrap();
+    }
+
+    fn destroy(&mut self, device: &LogicalDevice) {
+        if let Some(buffer) = self.buffer.take() {
+            buffer.destroy_with_device(device);
+        }
+    }
+}
+
+/// The draw calls of one frame, in painting order.
+///
+/// Tessellated paths are packed into one vertex buffer and one index buffer, which are written
+/// when the frame is recorded. Consecutive paths drawn with the same transform share a draw call.
+/// The per-instance data of all draws is packed into one instance buffer, bound to binding 1.
+/// Its first instance is shared by the draws that are not instanced, see [`InstanceRange::SINGLE`].
+/// Textured triangles, such as glyph quads, are packed into their own buffers, as they have a different vertex type.
+///
+/// The buffers are kept across frames and only grow. Buffers and paths that are no longer needed
+/// are freed once the fence of the frame that last used them is signaled.
+#[doc(hidden)]
+pub(crate) struct DrawList {
+    transform: Transform,
+    paths: Vec<Path>,
+    pub(crate) vertices: Vec<VertexData>,
+    pub(crate) indices: Vec<u32>,
+    pub(crate) instances: Vec<InstanceData>,
+    textured_vertices: Vec<TexturedVertex>,
+    textured_indices: Vec<u32>,
+    /// The encoded brushes of all draws, see [`Brush::encode`].
+    brushes: Vec<Vec4<f32>>,
+    pub(crate) draws: Vec<Draw>,
+    vertex_buffer: StreamBuffer,
+    index_buffer: StreamBuffer,
+    instance_buffer: StreamBuffer,
+    textured_vertex_buffer: StreamBuffer,
+    textured_index_buffer: StreamBuffer,
+    /// Buffers that were outgrown, destroyed once the GPU has finished with them.
+    retired: Vec<Buffer>,
+    pub(crate) retained: RetainedPaths,
+    /// Paths that were replaced, freed or drawn in a cleared frame,
+    /// destroyed once the GPU has finished with them.
+    released: Vec<Path>,
+    /// Signaled when the GPU has finished the last recorded frame.
+    in_flight: Option<Fence>,
+}
+
+impl DrawList {
+    pub(crate) fn new() -> Self {
+        Self {
+            transform: Transform::identity(),
+            paths: vec![],
+            vertices: vec![],
+            indices: vec![],
+            instances: vec![InstanceData::default()],
+            textured_vertices: vec![],
+            textured_indices: vec![],
+            brushes: vec![],
+            draws: vec![],
+            vertex_buffer: StreamBuffer::new(BufferUsage::Vertex),
+            index_buffer: StreamBuffer::new(BufferUsage::Index),
+            instance_buffer: StreamBuffer::new(BufferUsage::Vertex),
+            textured_vertex_buffer: StreamBuffer::new(BufferUsage::Vertex),
+            textured_index_buffer: StreamBuffer::new(BufferUsage::Index),
+            retired: vec![],
+            retained: RetainedPaths::default(),
+            released: vec![],
+            in_flight: None,
+        }
+    }
+
+    pub(crate) fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    pub(crate) fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
+    /// Adds per-instance data for one draw.
+    pub(crate) fn push_instances(&mut self, instances: &[InstanceData]) -> InstanceRange {
+        let first = self.instances.len() as u32;
+        self.instances.extend_from_slice(instances);
+        InstanceRange {
+            first,
+            count: instances.len() as u32,
+        }
+    }
+
+    /// Draws a path that has its own buffers once at each transform.
+    pub(crate) fn push_path(&mut self, path: Path, transforms: &[Transform]) {
+        self.push_path_instanced(path, transforms, InstanceRange::SINGLE);
+    }
+
+    pub(crate) fn push_path_instanced(
+        &mut self,
+        path: Path,
+        transforms: &[Transform],
+        instances: InstanceRange,
+    ) {
+        self.paths.push(path);
+        let index = self.paths.len() - 1;
+        self.push_draws(|| Geometry::Path(index), transforms, instances);
+    }
+
+    /// Keeps `path` uploaded until it is freed. `thickness` is set if it is a stroke.
+    pub(crate) fn retain(&mut self, path: Path, thickness: Option<f64>) -> GeometryHandle {
+        self.retained.insert(path, thickness)
+    }
+
+    /// Returns the stroke thickness of a retained path, or `None` if it is a fill.
+    pub(crate) fn retained_thickness(&self, handle: GeometryHandle) -> VlResult<Option<f64>> {
+        self.retained.thickness(handle)
+    }
+
+    /// Replaces the contents of a retained path.
+    /// Draws recorded in this frame use the new contents.
+    pub(crate) fn replace_retained(&mut self, handle: GeometryHandle, path: Path) -> VlResult<()> {
+        let old = self.retained.replace(handle, path)?;
+        self.released.push(old);
+        Ok(())
+    }
+
+    /// Frees a retained path. Draws of it recorded in this frame are skipped.
+    pub(crate) fn free_retained(&mut self, handle: GeometryHandle) -> VlResult<()> {
+        let old = self.retained.remove(handle)?;
+        self.released.push(old);
+        Ok(())
+    }
+
+    /// Draws a retained path once at each transform.
+    pub(crate) fn push_retained(
+        &mut self,
+        handle: GeometryHandle,
+        transforms: &[Transform],
+        instances: InstanceRange,
+    ) -> VlResult<()> {
+        self.retained.get(handle)?;
+        self.push_draws(|| Geometry::Retained(handle), transforms, instances);
+        Ok(())
+    }
+
+    /// Appends the triangles to the shared buffers and draws them once at each transform.
+    pub(crate) fn push_tessellation(
+        &mut self,
+        tessellation: Tessellation,
+        transforms: &[Transform],
+    ) {
+        self.push_tessellation_instanced(tessellation, transforms, InstanceRange::SINGLE);
+    }
+
+    pub(crate) fn push_tessellation_instanced(
+        &mut self,
+        tessellation: Tessellation,
+        transforms: &[Transform],
+        instances: InstanceRange,
+    ) {
+        let buffers = || {
+            tessellation
+                .index_buffers
+                .iter()
+                .filter(|i| !i.indices.is_empty())
+        };
+
+        // Extend the previous draw call if it ends where these triangles start.
+        if let (
+            [transform],
+            Some(Draw {
+                geometry: Geometry::Batch(range),
+                transform: last,
+                instances: last_instances,
+            }),
+        ) = (transforms, self.draws.last_mut())
+        {
+            if last == transform
+                && *last_instances == instances
+                && instances == InstanceRange::SINGLE
+                && (range.first_index + range.index_count) as usize == self.indices.len()
+            {
+                let first_index = self.indices.len();
+                for buffer in buffers() {
+                    let base = (self.vertices.len() as i32 - range.vertex_offset) as u32;
+                    append(&mut self.vertices, &mut self.indices, buffer, base);
+                }
+                range.index_count += (self.indices.len() - first_index) as u32;
+                return;
+            }
+        }
+
+        let first_index = self.indices.len() as u32;
+        let vertex_offset = self.vertices.len() as i32;
+        for buffer in buffers() {
+            let base = (self.vertices.len() as i32 - vertex_offset) as u32;
+            append(&mut self.vertices, &mut self.indices, buffer, base);
+        }
+        let range = BatchRange {
+            first_index,
+            index_count: self.indices.len() as u32 - first_index,
+            vertex_offset,
+        };
+        if range.index_count == 0 {
+            return;
+        }
+        self.push_draws(|| Geometry::Batch(range), transforms, instances);
+    }
+
+    /// Appends the triangles to the shared buffers and paints them with `brush` using `binding`.
+    pub(crate) fn push_brush(
+        &mut self,
+        tessellation: Tessellation,
+        brush: &Brush,
+        transform: Transform,
+        binding: PipelineBinding,
+    ) {
+        let first_index = self.indices.len() as u32;
+        let vertex_offset = self.vertices.len() as i32;
+        for buffer in tessellation
+            .index_buffers
+            .iter()
+            .filter(|i| !i.indices.is_empty())
+        {
+            let base = (self.vertices.len() as i32 - vertex_offset) as u32;
+            append(&mut self.vertices, &mut self.indices, buffer, base);
+        }
+        let range = BatchRange {
+            first_index,
+            index_count: self.indices.len() as u32 - first_index,
+            vertex_offset,
+        };
+        if range.index_count == 0 {
+            return;
+        }
+        let offset = self.brushes.len() as u32;
+        self.brushes.extend(brush.encode());
+        self.push_draws(
+            || Geometry::Brush(range, binding, offset),
+            &[transform],
+            InstanceRange::SINGLE,
+        );
+    }
+
+    /// The encoded brushes of the frame, to be uploaded before it is recorded.
+    pub(crate) fn brushes(&self) -> &[Vec4<f32>] {
+        &self.brushes
+    }
+
+    /// Drops the draws painted with brushes, for a frame whose brushes could not be uploaded.
+    pub(crate) fn discard_brushes(&mut self) {
+        self.draws
+            .retain(|draw| !matches!(draw.geometry, Geometry::Brush(..)));
+        self.brushes.clear();
+    }
+
+    /// Appends textured triangles to the textured buffers and draws them with `binding`.
+    pub(crate) fn push_textured(
+        &mut self,
+        vertices: &[TexturedVertex],
+        indices: &[u32],
+        transform: Transform,
+        binding: PipelineBinding,
+    ) {
+        if indices.is_empty() {
+            return;
+        }
+        // Extend the previous draw call if it ends where these triangles start.
+        if let Some(Draw {
+            geometry: Geometry::Textured(range, last_binding),
+            transform: last,
+            ..
+        }) = self.draws.last_mut()
+        {
+            if *last == transform
+                && *last_binding == binding
+                && (range.first_index + range.index_count) as usize == self.textured_indices.len()
+            {
+                let base = (self.textured_vertices.len() as i32 - range.vertex_offset) as u32;
+                self.textured_vertices.extend_from_slice(vertices);
+                self.textured_indices
+                    .extend(indices.iter().map(|i| i + base));
+                range.index_count += indices.len() as u32;
+                return;
+            }
+        }
+
+        let range = BatchRange {
+            first_index: self.textured_indices.len() as u32,
+            index_count: indices.len() as u32,
+            vertex_offset: self.textured_vertices.len() as i32,
+        };
+        self.textured_vertices.extend_from_slice(vertices);
+        self.textured_indices.extend_from_slice(indices);
+        self.push_draws(
+            || Geometry::Textured(range, binding),
+            &[transform],
+            InstanceRange::SINGLE,
+        );
+    }
+
+    fn push_draws(
+        &mut self,
+        geometry: impl Fn() -> Geometry,
+        transforms: &[Transform],
+        instances: InstanceRange,
+    ) {
+        if instances.count == 0 {
+            return;
+        }
+        for transform in transforms {
+            self.draws.push(Draw {
+                geometry: geometry(),
+                transform: *transform,
+                instances,
+            });
+        }
+    }
+
+    /// Writes the shared buffers and records all draw calls of the frame.
+    /// Draws are recorded with `main` unless they have their own pipeline.
+    ///
+    /// `fence` must be signaled by the submission of `command_buffer`, so that the buffers are
+    /// not written or freed while the GPU reads them.
+    pub(crate) fn record(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        command_buffer: &CommandBuffer,
+        main: PipelineBinding,
+        fence: Fence,
+    ) {
+        self.wait(device);
+        let mut write = |buffer: &mut StreamBuffer, data: &[u8]| {
+            buffer.write(instance, physical_device, device, data, &mut self.retired);
+        };
+        if !self.indices.is_empty() {
+            write(&mut self.vertex_buffer, as_bytes(&self.vertices));
+            write(&mut self.index_buffer, as_bytes(&self.indices));
+        }
+        if !self.textured_indices.is_empty() {
+            write(
+                &mut self.textured_vertex_buffer,
+                as_bytes(&self.textured_vertices),
+            );
+            write(
+                &mut self.textured_index_buffer,
+                as_bytes(&self.textured_indices),
+            );
+        }
+        write(&mut self.instance_buffer, as_bytes(&self.instances));
+        let shared = (!self.indices.is_empty())
+            .then_some((&self.vertex_buffer, &self.index_buffer))
+            .and_then(|(v, i)| v.buffer.as_ref().zip(i.buffer.as_ref()));
+        let textured = (!self.textured_indices.is_empty())
+            .then_some((&self.textured_vertex_buffer, &self.textured_index_buffer))
+            .and_then(|(v, i)| v.buffer.as_ref().zip(i.buffer.as_ref()));
+        command_buffer.bind_instances(device, self.instance_buffer.buffer.as_ref().unwrap());
+        self.in_flight = Some(fence);
+
+        command_buffer.bind_pipeline(device, &main.pipeline, main.layout, main.descriptor_set);
+        let mut current = main;
+        // Which shared buffers are bound to binding 0. Paths bind their own buffers,
+        // so the shared ones are bound again after them.
+        let mut bound = None;
+        for draw in &self.draws {
+            let Draw {
+                geometry,
+                transform,
+                instances,
+            } = draw;
+            let binding = match geometry {
+                Geometry::Textured(_, binding) | Geometry::Brush(_, binding, _) => *binding,
+                _ => main,
+            };
+            if binding != current {
+                command_buffer.bind_pipeline(
+                    device,
+                    &binding.pipeline,
+                    binding.layout,
+                    binding.descriptor_set,
+                );
+                current = binding;
+            }
+            let layout = binding.layout;
+            match geometry {
+                Geometry::Path(index) => {
+                    command_buffer.draw_path(
+                        device,
+                        layout,
+                        &self.paths[*index],
+                        transform,
+                        *instances,
+                    );
+                    bound = None;
+                }
+                Geometry::Retained(handle) => {
+                    if let Ok(path) = self.retained.get(*handle) {
+                        command_buffer.draw_path(device, layout, path, transform, *instances);
+                        bound = None;
+                    }
+                }
+                Geometry::Batch(range) | Geometry::Brush(range, _, _) => {
+                    let Some((vertex_buffer, index_buffer)) = shared else {
+                        continue;
+                    };
+                    if bound != Some(Shared::Batch) {
+                        command_buffer.bind_buffers(
+                            device,
+                            vertex_buffer,
+                            index_buffer,
+                            IndexType::UINT32,
+                        );
+                        bound = Some(Shared::Batch);
+                    }
+                    if let Geometry::Brush(_, _, offset) = geometry {
+                        command_buffer.push_brush(device, layout, *offset);
+                    }
+                    command_buffer.draw_indexed(device, layout, transform, range, *instances);
+                }
+                Geometry::Textured(range, _) => {
+                    let Some((vertex_buffer, index_buffer)) = textured else {
+                        continue;
+                    };
+                    if bound != Some(Shared::Textured) {
+                        command_buffer.bind_buffers(
+                            device,
+                            vertex_buffer,
+                            index_buffer,
+                            IndexType::UINT32,
+                        );
+                        bound = Some(Shared::Textured);
+                    }
+                    command_buffer.draw_indexed(device, layout, transform, range, *instances);
+                }
+            }
+        }
+    }
+
+    /// Forgets the draw calls of the previous frame and frees the paths and buffers it no
+    /// longer needs, after waiting for the GPU to finish it. Retained paths are kept.
+    pub(crate) fn reset(&mut self, device: &LogicalDevice) {
+        self.clear();
+        self.wait(device);
+        for path in self.released.drain(..) {
+            path.destroy_with_device(device);
+        }
+        for buffer in self.retired.drain(..) {
+            buffer.destroy_with_device(device);
+        }
+    }
+
+    /// Forgets the draw calls and the triangles, instances and brushes they use.
+    /// Their paths are released, to be freed by the next [`DrawList::reset`].
+    pub(crate) fn clear(&mut self) {
+        self.released.append(&mut self.paths);
+        self.vertices.clear();
+        self.indices.clear();
+        self.instances.truncate(1);
+        self.textured_vertices.clear();
+        self.textured_indices.clear();
+        self.brushes.clear();
+        self.draws.clear();
+    }
+
+    /// Frees everything, including the retained paths.
+    pub(crate) fn destroy(&mut self, device: &LogicalDevice) {
+        self.reset(device);
+        self.retained.destroy(device);
+        for buffer in [
+            &mut self.vertex_buffer,
+            &mut self.index_buffer,
+            &mut self.instance_buffer,
+            &mut self.textured_vertex_buffer,
+            &mut self.textured_index_buffer,
+        ] {
+            buffer.destroy(device);
+        }
+    }
+
+    /// Waits until the GPU has finished the last recorded frame.
+    fn wait(&mut self, device: &LogicalDevice) {
+        if let Some(fence) = self.in_flight.take() {
+            unsafe {
+                device
+                    .inner
+                    .wait_for_fences(&[fence], true, u64::MAX)
+                    .unwrap();
+            }
+        }
+    }
+}
+
+fn append(vertices: &mut Vec<VertexData>, indices: &mut Vec<u32>, buffer: &IndexBuffer, base: u32) {
+    vertices.extend_from_slice(&buffer.data);
+    indices.extend(buffer.indices.iter().map(|i| i + base));
+}
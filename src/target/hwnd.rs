@@ -0,0 +1,625 @@
+use ash::vk::{
+    ClearValue, CommandBufferResetFlags, Extent2D, Fence, Offset2D, PipelineStageFlags,
+    PresentInfoKHR, Rect2D, RenderPassBeginInfo, Semaphore, SubpassContents,
+};
+
+use super::{
+    swapchain::recreate_swapchain, CommandBuffer, DrawList, GeometryHandle, InstanceRange,
+    PipelineBinding,
+};
+
+use crate::{
+    brush::{Brush, GradientPipeline},
+    geometry::{InstanceData, Tessellation, Transform},
+    text::{GlyphAtlas, GlyphRun, TextPipeline},
+    BatchedRenderTarget, Destroy, FrameBuffer, Image, ImageView, Instance, IntoPath, LogicalDevice,
+    PhysicalDevice, Pipeline, Queue, RenderPass, RenderTarget, Sampler, Shader, Stage, SubPass,
+    Texture, TextureData, TextureHandle, TexturePipeline, TexturedVertex, Vec4, VlError, VlResult,
+};
+
+pub struct HwndRenderTarget {
+    pub(crate) buffer: CommandBuffer,
+    pub(crate) instance: Instance,
+    pub(crate) logical_device: LogicalDevice,
+    pub(crate) physical_device: PhysicalDevice,
+    pub(crate) queue: Queue,
+
+    pub(crate) frame_buffers: Vec<FrameBuffer>,
+    pub(crate) image_view: Vec<ImageView>,
+    pub(crate) images: Vec<ash::vk::Image>,
+    pub(crate) render_pass: RenderPass,
+    pub(crate) pipeline: Vec<Pipeline>,
+
+    pub(crate) image: Option<Image>,
+    pub(crate) clear_color: Vec4<f32>,
+
+    pub(crate) surface: super::surface::Surface,
+    pub(crate) swapchain: super::swapchain::Swapchain,
+    pub(crate) fence: Fence,
+    pub(crate) img_index: u32,
+
+    pub(crate) vertex: u32,
+    pub(crate) draw_list: DrawList,
+    /// Created when text is first drawn with a glyph atlas.
+    pub(crate) text_pipeline: Option<TextPipeline>,
+    /// Created when a path is first painted with a brush.
+    pub(crate) gradient_pipeline: Option<GradientPipeline>,
+    /// Created with the first texture, and owns the textures.
+    pub(crate) texture_pipeline: Option<TexturePipeline>,
+    pub(crate) offsets: Vec<u64>,
+
+    pub(crate) shaders: Vec<Shader>,
+
+    pub(crate) stage: Stage,
+
+    pub(crate) swapchain_semaphore: Semaphore,
+    pub(crate) rendered_semaphore: Semaphore,
+}
+
+impl HwndRenderTarget {
+    /// Paints the triangles with `brush`, creating the gradient pipeline on first use.
+    fn push_brush(&mut self, tessellation: Tessellation, brush: &Brush) -> VlResult<()> {
+        let gradient_pipeline = match &mut self.gradient_pipeline {
+            Some(gradient_pipeline) => gradient_pipeline,
+            None => self.gradient_pipeline.insert(GradientPipeline::new(
+                &self.logical_device,
+                &self.render_pass,
+                &self.stage,
+            )?),
+        };
+        let binding = gradient_pipeline.binding();
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_brush(tessellation, brush, transform, binding);
+        Ok(())
+    }
+}
+
+impl RenderTarget for HwndRenderTarget {
+    fn begin(&mut self) {
+        unsafe {
+            self.logical_device
+                .inner
+                .reset_command_buffer(self.buffer.cmd_buffers[0], CommandBufferResetFlags::empty())
+                .unwrap();
+
+            self.logical_device
+                .inner
+                .wait_for_fences(&[self.fence], true, u64::MAX)
+                .unwrap();
+            // The previous frame has finished, so its buffers can be freed.
+            self.draw_list.reset(&self.logical_device);
+            if let Some(texture_pipeline) = &mut self.texture_pipeline {
+                texture_pipeline.destroy_released(&self.logical_device);
+            }
+
+            self.img_index = match {
+                self.swapchain.inner.acquire_next_image(
+                    self.swapchain.khr,
+                    1000000000,
+                    self.swapchain_semaphore,
+                    Fence::null(),
+                )
+            } {
+                Ok(i) => {
+                    if i.1 {
+                        for i in &self.frame_buffers {
+                            self.logical_device.inner.destroy_framebuffer(i.inner, None);
+                        }
+                        self.frame_buffers.clear();
+                        for i in &self.image_view {
+                            self.logical_device.inner.destroy_image_view(i.inner, None);
+                        }
+                        self.image_view.clear();
+
+                        let (swapchain, capabilities) = recreate_swapchain(
+                            &self.instance,
+                            &self.logical_device,
+                            self.physical_device,
+                            &self.surface,
+                        );
+
+                        self.swapchain = swapchain;
+
+                        self.stage.resize(
+                            capabilities.current_extent.width,
+                            capabilities.current_extent.height,
+                        );
+
+                        self.images = self
+                            .swapchain
+                            .inner
+                            .get_swapchain_images(self.swapchain.khr)
+                            .unwrap();
+                        let image_view = self
+                            .swapchain
+                            .get_image(&self.logical_device, &self.images)
+                            .unwrap();
+                        let subpasses = vec![SubPass::new()];
+
+                        self.logical_device.destroy_render_pass(&self.render_pass);
+                        for i in &self.pipeline {
+                            self.logical_device.destroy_pipeline(i);
+                        }
+                        // Built for the old render pass and size; created again when they are next used.
+                        if let Some(text_pipeline) = self.text_pipeline.take() {
+                            text_pipeline.destroy_with_device(&self.logical_device);
+                        }
+                        if let Some(gradient_pipeline) = self.gradient_pipeline.take() {
+                            gradient_pipeline.destroy_with_device(&self.logical_device);
+                        }
+
+                        self.render_pass = RenderPass::new(&self.logical_device, &subpasses);
+
+                        let pipeline = Pipeline::builder()
+                            .image(&Image::from(self.images[0]))
+                            .logical_device(&self.logical_device)
+                            .shaders(&self.shaders)
+                            .width(capabilities.current_extent.width)
+                            .height(capabilities.current_extent.height)
+                            .stage(&mut self.stage)
+                            .render_pass(&self.render_pass)
+                            .build(&self.instance, self.physical_device)
+                            .unwrap();
+
+                        self.pipeline = pipeline;
+                        if let Some(texture_pipeline) = &mut self.texture_pipeline {
+                            texture_pipeline
+                                .rebuild(&self.logical_device, &self.render_pass, &self.stage)
+                                .unwrap();
+                        }
+
+                        for i in image_view {
+                            self.frame_buffers.push(
+                                i.create_frame_buffer(
+                                    &self.logical_device,
+                                    &self.render_pass,
+                                    capabilities.current_extent.width,
+                                    capabilities.current_extent.height,
+                                )
+                                .unwrap(),
+                            );
+                        }
+
+                        let result = self
+                            .swapchain
+                            .inner
+                            .acquire_next_image(
+                                self.swapchain.khr,
+                                1000000000,
+                                self.swapchain_semaphore,
+                                Fence::null(),
+                            )
+                            .unwrap();
+
+                        result.0
+                    } else {
+                        i.0
+                    }
+                }
+                Err(result) => {
+                    if result != ash::vk::Result::SUCCESS {
+                        panic!("Can't get next frame.");
+                    } else if result == ash::vk::Result::SUBOPTIMAL_KHR
+                        || result == ash::vk::Result::ERROR_OUT_OF_DATE_KHR
+                    {
+                        for i in &self.frame_buffers {
+                            self.logical_device.inner.destroy_framebuffer(i.inner, None);
+                        }
+                        self.frame_buffers.clear();
+                        for i in &self.image_view {
+                            self.logical_device.inner.destroy_image_view(i.inner, None);
+                        }
+                        self.image_view.clear();
+                        for i in &self.images {
+                            self.logical_device.inner.destroy_image(*i, None);
+                        }
+                        self.images.clear();
+                        for i in &self.pipeline {
+                            self.logical_device.destroy_pipeline(i);
+                        }
+                        // Built for the old render pass and size; created again when they are next used.
+                        if let Some(text_pipeline) = self.text_pipeline.take() {
+                            text_pipeline.destroy_with_device(&self.logical_device);
+                        }
+                        if let Some(gradient_pipeline) = self.gradient_pipeline.take() {
+                            gradient_pipeline.destroy_with_device(&self.logical_device);
+                        }
+                        self.logical_device.destroy_render_pass(&self.render_pass);
+                        println!("Cleared images");
+                        self.swapchain
+                            .inner
+                            .destroy_swapchain(self.swapchain.khr, None);
+                        let (swapchain, capabilities) = recreate_swapchain(
+                            &self.instance,
+                            &self.logical_device,
+                            self.physical_device,
+                            &self.surface,
+                        );
+                        self.swapchain = swapchain;
+                        self.images = self
+                            .swapchain
+                            .inner
+                            .get_swapchain_images(self.swapchain.khr)
+                            .unwrap();
+                        let image_view = self
+                            .swapchain
+                            .get_image(&self.logical_device, &self.images)
+                            .unwrap();
+
+                        for i in image_view {
+                            self.frame_buffers.push(
+                                i.create_frame_buffer(
+                                    &self.logical_device,
+                                    &self.render_pass,
+                                    capabilities.current_extent.width,
+                                    capabilities.current_extent.height,
+                                )
+                                .unwrap(),
+                            );
+                        }
+
+                        let result = self
+                            .swapchain
+                            .inner
+                            .acquire_next_image(
+                                self.swapchain.khr,
+                                1000000000,
+                                self.swapchain_semaphore,
+                                Fence::null(),
+                            )
+                            .unwrap();
+
+                        result.0
+                    } else {
+                        panic!("Unknown error.");
+                    }
+                }
+            };
+
+            self.logical_device
+                .inner
+                .reset_fences(&[self.fence])
+                .unwrap();
+
+            self.buffer.begin(&self.logical_device);
+
+            let mut clear = ClearValue::default();
+
+            clear.color.float32 = self.clear_color.into();
+            let create_info = RenderPassBeginInfo::builder()
+                .render_pass(self.render_pass.inner)
+                .framebuffer(self.frame_buffers[self.img_index as usize].inner)
+                .render_area(
+                    Rect2D::builder()
+                        .extent(
+                            Extent2D::builder()
+                                .width(self.stage.width)
+                                .height(self.stage.height)
+                                .build(),
+                        )
+                        .offset(Offset2D::builder().x(0).y(0).build())
+                        .build(),
+                )
+                .clear_values(&[clear])
+                .build();
+            self.logical_device.inner.cmd_begin_render_pass(
+                self.buffer.cmd_buffers[0],
+                &create_info,
+                SubpassContents::INLINE,
+            );
+        }
+    }
+
+    fn fill(&mut self, path: &mut impl IntoPath) {
+        let transform = self.draw_list.transform();
+        self.fill_transformed(path, &[transform]);
+    }
+
+    fn stroke(&mut self, path: &mut impl IntoPath, thickness: f64) {
+        let transform = self.draw_list.transform();
+        match path.stroke_tessellation(thickness) {
+            Some(tessellation) => self.draw_list.push_tessellation(tessellation, &[transform]),
+            None => {
+                let path = path.into_stroke_path(
+                    &self.instance,
+                    self.physical_device,
+                    &self.logical_device,
+                    thickness,
+                );
+                self.draw_list.push_path(path, &[transform]);
+            }
+        }
+    }
+
+    fn fill_with(&mut self, path: &mut impl IntoPath, brush: &Brush) -> VlResult<()> {
+        let tessellation = path.fill_tessellation().ok_or(VlError::InvalidState(
+            "Brushes can only paint paths that are tessellated.",
+        ))?;
+        self.push_brush(tessellation, brush)
+    }
+
+    fn stroke_with(
+        &mut self,
+        path: &mut impl IntoPath,
+        thickness: f64,
+        brush: &Brush,
+    ) -> VlResult<()> {
+        let tessellation = path
+            .stroke_tessellation(thickness)
+            .ok_or(VlError::InvalidState(
+                "Brushes can only paint paths that are tessellated.",
+            ))?;
+        self.push_brush(tessellation, brush)
+    }
+
+    fn draw_glyphs(&mut self, atlas: &mut GlyphAtlas, run: &GlyphRun) -> VlResult<()> {
+        let (vertices, indices) = atlas.quads(run)?;
+        let text_pipeline = match &mut self.text_pipeline {
+            Some(text_pipeline) => text_pipeline,
+            None => self.text_pipeline.insert(TextPipeline::new(
+                &self.logical_device,
+                &self.render_pass,
+                &self.stage,
+            )?),
+        };
+        let binding = text_pipeline.prepare(
+            &self.instance,
+            self.physical_device,
+            &self.logical_device,
+            self.queue,
+            &self.buffer,
+            atlas,
+        )?;
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_textured(&vertices, &indices, transform, binding);
+        Ok(())
+    }
+
+    fn create_texture(&mut self, data: &TextureData, sampler: &Sampler) -> VlResult<TextureHandle> {
+        let texture_pipeline = match &mut self.texture_pipeline {
+            Some(texture_pipeline) => texture_pipeline,
+            None => self.texture_pipeline.insert(TexturePipeline::new(
+                &self.logical_device,
+                &self.render_pass,
+                &self.stage,
+            )?),
+        };
+        let texture = Texture::upload(
+            &self.instance,
+            self.physical_device,
+            &self.logical_device,
+            self.queue,
+            &self.buffer,
+            data,
+            sampler,
+        )?;
+        texture_pipeline.insert(&self.logical_device, texture)
+    }
+
+    fn free_texture(&mut self, texture: TextureHandle) -> VlResult<()> {
+        match &mut self.texture_pipeline {
+            Some(texture_pipeline) => texture_pipeline.free(texture),
+            None => Err(VlError::InvalidState("The texture handle has been freed.")),
+        }
+    }
+
+    fn draw_textured(
+        &mut self,
+        texture: TextureHandle,
+        vertices: &[TexturedVertex],
+        indices: &[u32],
+    ) -> VlResult<()> {
+        let Some(texture_pipeline) = &self.texture_pipeline else {
+            return Err(VlError::InvalidState("The texture handle has been freed."));
+        };
+        let binding = texture_pipeline.binding(texture)?;
+        if indices.iter().any(|&i| i as usize >= vertices.len()) {
+            return Err(VlError::InvalidState(
+                "An index is out of range of the vertices.",
+            ));
+        }
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_textured(vertices, indices, transform, binding);
+        Ok(())
+    }
+
+    fn end(&mut self) {
+        unsafe {
+            if let Some(gradient_pipeline) = &mut self.gradient_pipeline {
+                // The frame is still drawn, only without the paths painted with brushes.
+                if gradient_pipeline
+                    .upload(
+                        &self.instance,
+                        self.physical_device,
+                        &self.logical_device,
+                        self.draw_list.brushes(),
+                    )
+                    .is_err()
+                {
+                    self.draw_list.discard_brushes();
+                }
+            }
+            let descriptor = self.stage.descriptor.as_ref().unwrap();
+            let main = PipelineBinding {
+                pipeline: self.pipeline[0],
+                layout: descriptor.pipeline_layout,
+                descriptor_set: descriptor.desc_sets[0],
+            };
+            self.draw_list.record(
+                &self.instance,
+                self.physical_device,
+                &self.logical_device,
+                &self.buffer,
+                main,
+                self.fence,
+            );
+            self.logical_device
+                .inner
+                .cmd_end_render_pass(self.buffer.cmd_buffers[0]);
+        }
+        self.buffer.end(&self.logical_device);
+        let render_wait_stages = vec![PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
+        self.buffer.submit(
+            &self.logical_device,
+            self.queue,
+            self.fence,
+            &[self.swapchain_semaphore],
+            &[self.rendered_semaphore],
+            &render_wait_stages,
+        );
+
+        let present_info = PresentInfoKHR::builder()
+            .swapchains(&[self.swapchain.khr])
+            .image_indices(&[self.img_index])
+            .wait_semaphores(&[self.rendered_semaphore])
+            .build();
+        unsafe {
+            self.swapchain
+                .inner
+                .queue_present(self.queue.0, &present_info)
+                .unwrap()
+        };
+    }
+
+    fn set_image(&mut self, image: crate::Image) {
+        self.image = Some(image);
+    }
+
+    fn set_clear_color(&mut self, color: impl Into<Vec4<f32>>) {
+        self.clear_color = color.into();
+    }
+
+    fn logical_device(&self) -> &LogicalDevice {
+        &self.logical_device
+    }
+
+    fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    fn clear(&mut self) {
+        self.draw_list.reset(&self.logical_device);
+        self.vertex = 0;
+        self.offsets.clear();
+    }
+
+    fn stage(&mut self) -> &mut Stage {
+        &mut self.stage
+    }
+}
+
+impl BatchedRenderTarget for HwndRenderTarget {
+    fn fill_transformed(&mut self, path: &mut impl IntoPath, transforms: &[Transform]) {
+        match path.fill_tessellation() {
+            Some(tessellation) => self.draw_list.push_tessellation(tessellation, transforms),
+            None => {
+                let path =
+                    path.into_path(&self.instance, self.physical_device, &self.logical_device);
+                self.draw_list.push_path(path, transforms);
+            }
+        }
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.draw_list.set_transform(transform);
+    }
+
+    fn fill_instanced(&mut self, path: &mut impl IntoPath, instances: &[InstanceData]) {
+        let transform = self.draw_list.transform();
+        let instances = self.draw_list.push_instances(instances);
+        match path.fill_tessellation() {
+            Some(tessellation) => {
+                self.draw_list
+                    .push_tessellation_instanced(tessellation, &[transform], instances)
+            }
+            None => {
+                let path =
+                    path.into_path(&self.instance, self.physical_device, &self.logical_device);
+                self.draw_list
+                    .push_path_instanced(path, &[transform], instances);
+            }
+        }
+    }
+
+    fn upload(&mut self, path: &mut impl IntoPath) -> GeometryHandle {
+        let path = path.into_path(&self.instance, self.physical_device, &self.logical_device);
+        self.draw_list.retain(path, None)
+    }
+
+    fn upload_stroke(&mut self, path: &mut impl IntoPath, thickness: f64) -> GeometryHandle {
+        let path = path.into_stroke_path(
+            &self.instance,
+            self.physical_device,
+            &self.logical_device,
+            thickness,
+        );
+        self.draw_list.retain(path, Some(thickness))
+    }
+
+    fn draw(&mut self, handle: GeometryHandle) -> VlResult<()> {
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_retained(handle, &[transform], InstanceRange::SINGLE)
+    }
+
+    fn draw_instanced(
+        &mut self,
+        handle: GeometryHandle,
+        instances: &[InstanceData],
+    ) -> VlResult<()> {
+        let transform = self.draw_list.transform();
+        let instances = self.draw_list.push_instances(instances);
+        self.draw_list
+            .push_retained(handle, &[transform], instances)
+    }
+
+    fn update(&mut self, handle: GeometryHandle, path: &mut impl IntoPath) -> VlResult<()> {
+        let path = match self.draw_list.retained_thickness(handle)? {
+            Some(thickness) => path.into_stroke_path(
+                &self.instance,
+                self.physical_device,
+                &self.logical_device,
+                thickness,
+            ),
+            None => path.into_path(&self.instance, self.physical_device, &self.logical_device),
+        };
+        self.draw_list.replace_retained(handle, path)
+    }
+
+    fn free(&mut self, handle: GeometryHandle) -> VlResult<()> {
+        self.draw_list.free_retained(handle)
+    }
+}
+
+impl Drop for HwndRenderTarget {
+    fn drop(&mut self) {
+        unsafe {
+            self.logical_device
+                .inner
+                .queue_wait_idle(self.queue.0)
+                .unwrap();
+            self.draw_list.destroy(&self.logical_device);
+            if let Some(text_pipeline) = self.text_pipeline.take() {
+                text_pipeline.destroy_with_device(&self.logical_device);
+            }
+            if let Some(gradient_pipeline) = self.gradient_pipeline.take() {
+                gradient_pipeline.destroy_with_device(&self.logical_device);
+            }
+            if let Some(texture_pipeline) = self.texture_pipeline.take() {
+                texture_pipeline.destroy_with_device(&self.logical_device);
+            }
+            self.logical_device.destroy_command_buffer(&self.buffer);
+            self.logical_device.destroy_render_pass(&self.render_pass);
+
+            for i in &self.pipeline {
+                self.logical_device.destroy_pipeline(i);
+            }
+            self.surface
+                .surface
+                .destroy_surface(self.surface.surface_khr, None);
+        }
+    }
+}
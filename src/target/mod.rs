@@ -0,0 +1,509 @@
+#[doc(hidden)]
+pub(crate) mod batch;
+#[doc(hidden)]
+#[cfg(target_os = "windows")]
+#[cfg(feature = "window")]
+mod hwnd;
+#[doc(hidden)]
+mod png;
+#[doc(hidden)]
+mod retained;
+#[doc(hidden)]
+#[cfg(target_os = "windows")]
+#[cfg(feature = "window")]
+pub(crate) mod surface;
+#[doc(hidden)]
+#[cfg(target_os = "windows")]
+#[cfg(feature = "window")]
+pub(crate) mod swapchain;
+use ash::vk::{
+    CommandBufferBeginInfo, CommandBufferUsageFlags, CommandPool, DescriptorSet, Fence, IndexType,
+    PipelineBindPoint, PipelineLayout, PipelineStageFlags, Semaphore, ShaderStageFlags, SubmitInfo,
+};
+#[cfg(target_os = "windows")]
+#[cfg(feature = "window")]
+pub use hwnd::*;
+pub use png::*;
+pub use retained::*;
+
+pub(crate) use batch::{BatchRange, DrawList, InstanceRange, PipelineBinding};
+
+use crate::{
+    geometry::{Buffer, Path, Transform},
+    FrameBuffer, Image, Instance, LogicalDevice, Mat4, PhysicalDevice, Pipeline, Queue, RenderPass,
+    Stage, Vec4, VlError, VlResult,
+};
+
+/// # RenderTargetBuilder
+/// Various render targets can be created through this builder.
+/// Some render targets cannot be used without a feature flag.
+#[derive(Default)]
+pub struct RenderTargetBuilder {
+    buffer: Option<CommandBuffer>,
+    device: Option<LogicalDevice>,
+    physical_device: Option<PhysicalDevice>,
+    instance: Option<Instance>,
+    queue: Option<Queue>,
+    frame_buffer: Option<FrameBuffer>,
+    renderpass: Option<RenderPass>,
+    pipeline: Option<Pipeline>,
+    stage: Option<Stage>,
+    image: Option<Image>,
+}
+
+impl RenderTargetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn instance(mut self, instance: Instance) -> Self {
+        self.instance = Some(instance);
+        self
+    }
+
+    pub fn queue(mut self, queue: Queue) -> Self {
+        self.queue = Some(queue);
+        self
+    }
+
+    pub fn renderpass(mut self, renderpass: RenderPass) -> Self {
+        self.renderpass = Some(renderpass);
+        self
+    }
+
+    pub fn frame_buffer(mut self, frame_buffer: FrameBuffer) -> Self {
+        self.frame_buffer = Some(frame_buffer);
+        self
+    }
+
+    pub fn pipeline(mut self, pipeline: Pipeline) -> Self {
+        self.pipeline = Some(pipeline);
+        self
+    }
+
+    pub fn logical_device(mut self, device: LogicalDevice) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    pub fn physical_device(mut self, physical_device: PhysicalDevice) -> Self {
+        self.physical_device = Some(physical_device);
+        self
+    }
+
+    pub fn command_buffer(mut self, command_buffer: CommandBuffer) -> Self {
+        self.buffer = Some(command_buffer);
+        self
+    }
+
+    pub fn image(mut self, image: Option<Image>) -> Self {
+        self.image = image;
+        self
+    }
+
+    pub fn stage(mut self, stage: Stage) -> Self {
+        self.stage = Some(stage);
+        self
+    }
+
+    /// Windows only.  
+    /// "window" feature is required
+    #[cfg(target_os = "windows")]
+    #[cfg(feature = "window")]
+    pub fn build_hwnd(
+        self,
+        hwnd: isize,
+        hinstance: isize,
+        width: u32,
+        height: u32,
+        shaders: Vec<crate::Shader>,
+    ) -> VlResult<HwndRenderTarget> {
+        use ash::vk::{FenceCreateFlags, FenceCreateInfo, SemaphoreCreateInfo};
+        use libc::c_void;
+
+        let buffer = match self.buffer {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("command_buffer")),
+        };
+        let physical_device = match self.physical_device {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("physical_device")),
+        };
+        let device = match self.device {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("logical_device")),
+        };
+        let instance = match self.instance {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("instance")),
+        };
+        let queue = match self.queue {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("queue")),
+        };
+        let render_pass = match self.renderpass {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("render_pass")),
+        };
+        let pipeline = match self.pipeline {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("pipeline")),
+        };
+        let stage = match self.stage {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("stage")),
+        };
+        // The frame buffers are created from the swapchain images alone.
+        if render_pass.samples != ash::vk::SampleCountFlags::TYPE_1 {
+            return Err(VlError::InvalidState(
+                "Window render targets do not support multisampled render passes.",
+            ));
+        }
+
+        let surface = surface::Surface::create_for_win32(
+            &instance,
+            hwnd as *const c_void,
+            hinstance as *const c_void,
+        );
+        let swapchain = device
+            .create_swapchain(&instance, physical_device, &surface)
+            .unwrap();
+
+        let images = match unsafe { swapchain.inner.get_swapchain_images(swapchain.khr) } {
+            Ok(i) => i,
+            Err(_) => panic!("Err"),
+        };
+
+        let mut frame_buffers = vec![];
+        let image_view = swapchain.get_image(&device, &images).unwrap();
+
+        for i in &image_view {
+            frame_buffers.push(
+                i.create_frame_buffer(&device, &render_pass, width, height)
+                    .unwrap(),
+            );
+        }
+
+        let create_info = FenceCreateInfo::builder()
+            .flags(FenceCreateFlags::SIGNALED)
+            .build();
+        let fence = unsafe { device.inner.create_fence(&create_info, None) }.unwrap();
+        let create_info = SemaphoreCreateInfo::builder().build();
+        let swapchain_semaphore =
+            unsafe { device.inner.create_semaphore(&create_info, None) }.unwrap();
+        let rendered_semaphore =
+            unsafe { device.inner.create_semaphore(&create_info, None) }.unwrap();
+        Ok(HwndRenderTarget {
+            instance,
+            buffer,
+            logical_device: device,
+            physical_device,
+            queue,
+            frame_buffers,
+            image_view,
+            images,
+            render_pass,
+            pipeline: vec![pipeline],
+            image: self.image,
+            clear_color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            surface,
+            swapchain,
+            fence,
+            img_index: 0,
+            vertex: 0,
+            draw_list: DrawList::new(),
+            text_pipeline: None,
+            gradient_pipeline: None,
+            texture_pipeline: None,
+            offsets: vec![],
+            swapchain_semaphore,
+            rendered_semaphore,
+
+            stage,
+
+            shaders,
+        })
+    }
+
+    pub fn build_png(self, file_path: &str, width: u32, height: u32) -> VlResult<PngRenderTarget> {
+        let buffer = match self.buffer {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("command_buffer")),
+        };
+        let physical_device = match self.physical_device {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("physical_device")),
+        };
+        let device = match self.device {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("logical_device")),
+        };
+        let instance = match self.instance {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("instance")),
+        };
+        let queue = match self.queue {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("queue")),
+        };
+        let frame_buffer = match self.frame_buffer {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("frame_buffer")),
+        };
+        let renderpass = match self.renderpass {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("render_pass")),
+        };
+        let pipeline = match self.pipeline {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("pipeline")),
+        };
+        let stage = match self.stage {
+            Some(b) => b,
+            None => return Err(VlError::MissingParameter("stage")),
+        };
+        let create_info = ash::vk::FenceCreateInfo::builder().build();
+        let fence = unsafe { device.inner.create_fence(&create_info, None) }?;
+        Ok(PngRenderTarget {
+            instance,
+            buffer,
+            logical_device: device,
+            physical_device,
+            queue,
+            fence,
+            frame_buffer,
+            render_pass: renderpass,
+            pipeline,
+            image: self.image,
+            clear_color: Vec4::new(0.0, 0.0, 0.0, 1.0),
+            path: file_path.to_owned(),
+            vertex: 0,
+            stage,
+            width,
+            height,
+            draw_list: DrawList::new(),
+            text_pipeline: None,
+            gradient_pipeline: None,
+            texture_pipeline: None,
+            offsets: vec![],
+        })
+    }
+}
+
+#[doc(hidden)]
+pub struct CommandBuffer {
+    pub(crate) command_pool: CommandPool,
+    cmd_buffers: Vec<ash::vk::CommandBuffer>,
+}
+
+impl CommandBuffer {
+    pub fn new(device: &LogicalDevice, queue_family_index: usize) -> VlResult<Self> {
+        let command_pool = device.create_command_pool(queue_family_index)?;
+        let cmd_buffers = device.allocate_command_buffer(command_pool)?;
+        Ok(Self {
+            command_pool,
+            cmd_buffers,
+        })
+    }
+
+    pub(crate) fn begin(&self, device: &LogicalDevice) {
+        unsafe {
+            let begin_info = CommandBufferBeginInfo::builder().build();
+            device
+                .inner
+                .begin_command_buffer(self.cmd_buffers[0], &begin_info)
+                .unwrap();
+        }
+    }
+
+    pub(crate) fn end(&self, device: &LogicalDevice) {
+        unsafe {
+            device
+                .inner
+                .end_command_buffer(self.cmd_buffers[0])
+                .unwrap();
+        }
+    }
+
+    pub(crate) fn submit(
+        &self,
+        device: &LogicalDevice,
+        queue: Queue,
+        fence: Fence,
+        semaphores: &[Semaphore],
+        signal_semaphores: &[Semaphore],
+        wait_dst_stage_mask: &[PipelineStageFlags],
+    ) {
+        let submit_cmd_buf = vec![self.cmd_buffers[0]];
+        let info = vec![SubmitInfo::builder()
+            .command_buffers(&submit_cmd_buf)
+            .wait_semaphores(semaphores)
+            .signal_semaphores(signal_semaphores)
+            .wait_dst_stage_mask(wait_dst_stage_mask)
+            .build()];
+        unsafe {
+            device.inner.queue_submit(queue.0, &info, fence).unwrap();
+        }
+    }
+
+    /// Records commands into a separate command buffer from the same pool, submits it and
+    /// waits until the queue is idle. Used for copies that must happen outside a render pass.
+    pub(crate) fn record_once(
+        &self,
+        device: &LogicalDevice,
+        queue: Queue,
+        record: impl FnOnce(ash::vk::CommandBuffer),
+    ) -> VlResult<()> {
+        let cmd = device.allocate_command_buffer(self.command_pool)?[0];
+        let result = unsafe {
+            let begin_info = CommandBufferBeginInfo::builder()
+                .flags(CommandBufferUsageFlags::ONE_TIME_SUBMIT)
+                .build();
+            device
+                .inner
+                .begin_command_buffer(cmd, &begin_info)
+                .and_then(|_| {
+                    record(cmd);
+                    device.inner.end_command_buffer(cmd)
+                })
+                .and_then(|_| {
+                    let submit_cmd_buf = [cmd];
+                    let info = SubmitInfo::builder()
+                        .command_buffers(&submit_cmd_buf)
+                        .build();
+                    device.inner.queue_submit(queue.0, &[info], Fence::null())
+                })
+                .and_then(|_| device.inner.queue_wait_idle(queue.0))
+        };
+        unsafe {
+            device.inner.free_command_buffers(self.command_pool, &[cmd]);
+        }
+        result.map_err(VlError::from)
+    }
+
+    /// Passes the offset of a brush to the fragment shader, after the transform of the draw.
+    pub(crate) fn push_brush(&self, device: &LogicalDevice, layout: PipelineLayout, offset: u32) {
+        unsafe {
+            device.inner.cmd_push_constants(
+                self.cmd_buffers[0],
+                layout,
+                ShaderStageFlags::FRAGMENT,
+                std::mem::size_of::<Mat4<f32>>() as u32,
+                &offset.to_ne_bytes(),
+            );
+        }
+    }
+
+    /// Binds a pipeline with the descriptor set of its layout.
+    pub(crate) fn bind_pipeline(
+        &self,
+        device: &LogicalDevice,
+        pipeline: &Pipeline,
+        layout: PipelineLayout,
+        descriptor_set: DescriptorSet,
+    ) {
+        unsafe {
+            device.inner.cmd_bind_pipeline(
+                self.cmd_buffers[0],
+                PipelineBindPoint::GRAPHICS,
+                pipeline.inner,
+            );
+            device.inner.cmd_bind_descriptor_sets(
+                self.cmd_buffers[0],
+                PipelineBindPoint::GRAPHICS,
+                layout,
+                0,
+                &[descriptor_set],
+                &[],
+            );
+        }
+    }
+
+    /// Records the draw commands of `path`, passing `transform` to the vertex shader as push constant.
+    pub(crate) fn draw_path(
+        &self,
+        device: &LogicalDevice,
+        layout: PipelineLayout,
+        path: &Path,
+        transform: &Transform,
+        instances: InstanceRange,
+    ) {
+        for (buffer, (index_buffer, index_size)) in path.buffers.iter().zip(&path.index_buffers) {
+            self.bind_buffers(device, buffer, index_buffer, path.index_type);
+            let range = BatchRange {
+                first_index: 0,
+                index_count: *index_size as u32,
+                vertex_offset: 0,
+            };
+            self.draw_indexed(device, layout, transform, &range, instances);
+        }
+    }
+
+    /// Binds the per-instance data to binding 1.
+    pub(crate) fn bind_instances(&self, device: &LogicalDevice, instance_buffer: &Buffer) {
+        unsafe {
+            device.inner.cmd_bind_vertex_buffers(
+                self.cmd_buffers[0],
+                1,
+                &[instance_buffer.buffer],
+                &[0],
+            );
+        }
+    }
+
+    pub(crate) fn bind_buffers(
+        &self,
+        device: &LogicalDevice,
+        vertex_buffer: &Buffer,
+        index_buffer: &Buffer,
+        index_type: IndexType,
+    ) {
+        unsafe {
+            device.inner.cmd_bind_vertex_buffers(
+                self.cmd_buffers[0],
+                0,
+                &[vertex_buffer.buffer],
+                &[0],
+            );
+            device.inner.cmd_bind_index_buffer(
+                self.cmd_buffers[0],
+                index_buffer.buffer,
+                0,
+                index_type,
+            );
+        }
+    }
+
+    /// Draws a range of the bound index buffer with `transform` passed as push constant,
+    /// once for each of the instances.
+    pub(crate) fn draw_indexed(
+        &self,
+        device: &LogicalDevice,
+        layout: PipelineLayout,
+        transform: &Transform,
+        range: &BatchRange,
+        instances: InstanceRange,
+    ) {
+        let matrix = transform.matrix();
+        unsafe {
+            device.inner.cmd_push_constants(
+                self.cmd_buffers[0],
+                layout,
+                ShaderStageFlags::VERTEX,
+                0,
+                std::slice::from_raw_parts(
+                    matrix.as_ptr() as *const u8,
+                    std::mem::size_of::<Mat4<f32>>(),
+                ),
+            );
+            device.inner.cmd_draw_indexed(
+                self.cmd_buffers[0],
+                range.index_count,
+                instances.count,
+                range.first_index,
+                range.vertex_offset,
+                instances.first,
+            );
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use std::{fs::File, io::BufWriter};
+
+use ash::vk::{
+    ClearValue, Extent2D, Fence, Offset2D, Rect2D, RenderPassBeginInfo, SubpassContents,
+};
+
+use super::{CommandBuffer, DrawList, GeometryHandle, InstanceRange, PipelineBinding};
+
+use crate::{
+    brush::{Brush, GradientPipeline},
+    geometry::{InstanceData, Tessellation, Transform},
+    text::{GlyphAtlas, GlyphRun, TextPipeline},
+    BatchedRenderTarget, Destroy, FrameBuffer, Image, Instance, IntoPath, LogicalDevice,
+    PhysicalDevice, Pipeline, Queue, RenderPass, RenderTarget, Sampler, Stage, Texture,
+    TextureData, TextureHandle, TexturePipeline, TexturedVertex, Vec4, VlError, VlResult,
+};
+
+pub struct PngRenderTarget {
+    pub(crate) buffer: CommandBuffer,
+    pub(crate) instance: Instance,
+    pub(crate) logical_device: LogicalDevice,
+    pub(crate) physical_device: PhysicalDevice,
+    pub(crate) queue: Queue,
+    /// Signaled when the commands of the frame have finished.
+    pub(crate) fence: Fence,
+
+    pub(crate) frame_buffer: FrameBuffer,
+    pub(crate) render_pass: RenderPass,
+    pub(crate) pipeline: Pipeline,
+
+    pub(crate) vertex: u32,
+    pub(crate) draw_list: DrawList,
+    /// Created when text is first drawn with a glyph atlas.
+    pub(crate) text_pipeline: Option<TextPipeline>,
+    /// Created when a path is first painted with a brush.
+    pub(crate) gradient_pipeline: Option<GradientPipeline>,
+    /// Created with the first texture, and owns the textures.
+    pub(crate) texture_pipeline: Option<TexturePipeline>,
+    pub(crate) offsets: Vec<u64>,
+
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+
+    pub(crate) stage: Stage,
+
+    pub(crate) image: Option<Image>,
+    pub(crate) clear_color: Vec4<f32>,
+    pub(crate) path: String,
+}
+
+impl PngRenderTarget {
+    /// Paints the triangles with `brush`, creating the gradient pipeline on first use.
+    fn push_brush(&mut self, tessellation: Tessellation, brush: &Brush) -> VlResult<()> {
+        let gradient_pipeline = match &mut self.gradient_pipeline {
+            Some(gradient_pipeline) => gradient_pipeline,
+            None => self.gradient_pipeline.insert(GradientPipeline::new(
+                &self.logical_device,
+                &self.render_pass,
+                &self.stage,
+            )?),
+        };
+        let binding = gradient_pipeline.binding();
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_brush(tessellation, brush, transform, binding);
+        Ok(())
+    }
+}
+
+impl RenderTarget for PngRenderTarget {
+    fn begin(&mut self) {
+        // `end` waits for the fence, so the buffers of the previous frame are no longer in use.
+        self.draw_list.reset(&self.logical_device);
+        if let Some(texture_pipeline) = &mut self.texture_pipeline {
+            texture_pipeline.destroy_released(&self.logical_device);
+        }
+        self.buffer.begin(&self.logical_device);
+        unsafe {
+            let mut clear = ClearValue::default();
+            clear.color.float32 = self.clear_color.into();
+            let create_info = RenderPassBeginInfo::builder()
+                .render_pass(self.render_pass.inner)
+                .framebuffer(self.frame_buffer.inner)
+                .render_area(
+                    Rect2D::builder()
+                        .extent(
+                            Extent2D::builder()
+                                .width(self.width)
+                                .height(self.height)
+                                .build(),
+                        )
+                        .offset(Offset2D::builder().x(0).y(0).build())
+                        .build(),
+                )
+                .clear_values(&[clear])
+                .build();
+            self.logical_device.inner.cmd_begin_render_pass(
+                self.buffer.cmd_buffers[0],
+                &create_info,
+                SubpassContents::INLINE,
+            );
+        }
+    }
+
+    fn fill(&mut self, path: &mut impl IntoPath) {
+        let transform = self.draw_list.transform();
+        self.fill_transformed(path, &[transform]);
+    }
+
+    fn stroke(&mut self, path: &mut impl IntoPath, thickness: f64) {
+        let transform = self.draw_list.transform();
+        match path.stroke_tessellation(thickness) {
+            Some(tessellation) => self.draw_list.push_tessellation(tessellation, &[transform]),
+            None => {
+                let path = path.into_stroke_path(
+                    &self.instance,
+                    self.physical_device,
+                    &self.logical_device,
+                    thickness,
+                );
+                self.draw_list.push_path(path, &[transform]);
+            }
+        }
+    }
+
+    fn fill_with(&mut self, path: &mut impl IntoPath, brush: &Brush) -> VlResult<()> {
+        let tessellation = path.fill_tessellation().ok_or(VlError::InvalidState(
+            "Brushes can only paint paths that are tessellated.",
+        ))?;
+        self.push_brush(tessellation, brush)
+    }
+
+    fn stroke_with(
+        &mut self,
+        path: &mut impl IntoPath,
+        thickness: f64,
+        brush: &Brush,
+    ) -> VlResult<()> {
+        let tessellation = path
+            .stroke_tessellation(thickness)
+            .ok_or(VlError::InvalidState(
+                "Brushes can only paint paths that are tessellated.",
+            ))?;
+        self.push_brush(tessellation, brush)
+    }
+
+    fn draw_glyphs(&mut self, atlas: &mut GlyphAtlas, run: &GlyphRun) -> VlResult<()> {
+        let (vertices, indices) = atlas.quads(run)?;
+        let text_pipeline = match &mut self.text_pipeline {
+            Some(text_pipeline) => text_pipeline,
+            None => self.text_pipeline.insert(TextPipeline::new(
+                &self.logical_device,
+                &self.render_pass,
+                &self.stage,
+            )?),
+        };
+        let binding = text_pipeline.prepare(
+            &self.instance,
+            self.physical_device,
+            &self.logical_device,
+            self.queue,
+            &self.buffer,
+            atlas,
+        )?;
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_textured(&vertices, &indices, transform, binding);
+        Ok(())
+    }
+
+    fn create_texture(&mut self, data: &TextureData, sampler: &Sampler) -> VlResult<TextureHandle> {
+        let texture_pipeline = match &mut self.texture_pipeline {
+            Some(texture_pipeline) => texture_pipeline,
+            None => self.texture_pipeline.insert(TexturePipeline::new(
+                &self.logical_device,
+                &self.render_pass,
+                &self.stage,
+            )?),
+        };
+        let texture = Texture::upload(
+            &self.instance,
+            self.physical_device,
+            &self.logical_device,
+            self.queue,
+            &self.buffer,
+            data,
+            sampler,
+        )?;
+        texture_pipeline.insert(&self.logical_device, texture)
+    }
+
+    fn free_texture(&mut self, texture: TextureHandle) -> VlResult<()> {
+        match &mut self.texture_pipeline {
+            Some(texture_pipeline) => texture_pipeline.free(texture),
+            None => Err(VlError::InvalidState("The texture handle has been freed.")),
+        }
+    }
+
+    fn draw_textured(
+        &mut self,
+        texture: TextureHandle,
+        vertices: &[TexturedVertex],
+        indices: &[u32],
+    ) -> VlResult<()> {
+        let Some(texture_pipeline) = &self.texture_pipeline else {
+            return Err(VlError::InvalidState("The texture handle has been freed."));
+        };
+        let binding = texture_pipeline.binding(texture)?;
+        if indices.iter().any(|&i| i as usize >= vertices.len()) {
+            return Err(VlError::InvalidState(
+                "An index is out of range of the vertices.",
+            ));
+        }
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_textured(vertices, indices, transform, binding);
+        Ok(())
+    }
+
+    fn end(&mut self) {
+        unsafe {
+            if let Some(gradient_pipeline) = &mut self.gradient_pipeline {
+                // The frame is still drawn, only without the paths painted with brushes.
+                if gradient_pipeline
+                    .upload(
+                        &self.instance,
+                        self.physical_device,
+                        &self.logical_device,
+                        self.draw_list.brushes(),
+                    )
+                    .is_err()
+                {
+                    self.draw_list.discard_brushes();
+                }
+            }
+            let descriptor = self.stage.descriptor.as_ref().unwrap();
+            let main = PipelineBinding {
+                pipeline: self.pipeline,
+                layout: descriptor.pipeline_layout,
+                descriptor_set: descriptor.desc_sets[0],
+            };
+            self.draw_list.record(
+                &self.instance,
+                self.physical_device,
+                &self.logical_device,
+                &self.buffer,
+                main,
+                self.fence,
+            );
+            self.logical_device
+                .inner
+                .cmd_end_render_pass(self.buffer.cmd_buffers[0]);
+        }
+        self.buffer.end(&self.logical_device);
+        unsafe {
+            self.logical_device
+                .inner
+                .reset_fences(&[self.fence])
+                .unwrap();
+        }
+        self.buffer
+            .submit(&self.logical_device, self.queue, self.fence, &[], &[], &[]);
+        // The image is only complete once the commands have finished.
+        // The fence stays signaled, so the draw list does not wait for it again.
+        unsafe {
+            self.logical_device
+                .inner
+                .wait_for_fences(&[self.fence], true, u64::MAX)
+                .unwrap();
+        }
+        let file = File::create(&self.path).unwrap();
+        let w = &mut BufWriter::new(file);
+
+        let mut encoder = png::Encoder::new(w, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().unwrap();
+
+        let data = self.image.unwrap().map_memory(&self.logical_device);
+        let slice: &[u8] = unsafe {
+            std::slice::from_raw_parts(data as *const u8, (self.width * self.height * 4) as usize)
+        };
+        writer.write_image_data(slice).unwrap();
+    }
+
+    fn set_image(&mut self, image: crate::Image) {
+        self.image = Some(image);
+    }
+
+    fn set_clear_color(&mut self, color: impl Into<Vec4<f32>>) {
+        self.clear_color = color.into();
+    }
+
+    fn logical_device(&self) -> &LogicalDevice {
+        &self.logical_device
+    }
+
+    fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    fn clear(&mut self) {
+        self.draw_list.reset(&self.logical_device);
+        self.vertex = 0;
+        self.offsets.clear();
+    }
+
+    fn stage(&mut self) -> &mut Stage {
+        &mut self.stage
+    }
+}
+
+impl BatchedRenderTarget for PngRenderTarget {
+    fn fill_transformed(&mut self, path: &mut impl IntoPath, transforms: &[Transform]) {
+        match path.fill_tessellation() {
+            Some(tessellation) => self.draw_list.push_tessellation(tessellation, transforms),
+            None => {
+                let path =
+                    path.into_path(&self.instance, self.physical_device, &self.logical_device);
+                self.draw_list.push_path(path, transforms);
+            }
+        }
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.draw_list.set_transform(transform);
+    }
+
+    fn fill_instanced(&mut self, path: &mut impl IntoPath, instances: &[InstanceData]) {
+        let transform = self.draw_list.transform();
+        let instances = self.draw_list.push_instances(instances);
+        match path.fill_tessellation() {
+            Some(tessellation) => {
+                self.draw_list
+                    .push_tessellation_instanced(tessellation, &[transform], instances)
+            }
+            None => {
+                let path =
+                    path.into_path(&self.instance, self.physical_device, &self.logical_device);
+                self.draw_list
+                    .push_path_instanced(path, &[transform], instances);
+            }
+        }
+    }
+
+    fn upload(&mut self, path: &mut impl IntoPath) -> GeometryHandle {
+        let path = path.into_path(&self.instance, self.physical_device, &self.logical_device);
+        self.draw_list.retain(path, None)
+    }
+
+    fn upload_stroke(&mut self, path: &mut impl IntoPath, thickness: f64) -> GeometryHandle {
+        let path = path.into_stroke_path(
+            &self.instance,
+            self.physical_device,
+            &self.logical_device,
+            thickness,
+        );
+        self.draw_list.retain(path, Some(thickness))
+    }
+
+    fn draw(&mut self, handle: GeometryHandle) -> VlResult<()> {
+        let transform = self.draw_list.transform();
+        self.draw_list
+            .push_retained(handle, &[transform], InstanceRange::SINGLE)
+    }
+
+    fn draw_instanced(
+        &mut self,
+        handle: GeometryHandle,
+        instances: &[InstanceData],
+    ) -> VlResult<()> {
+        let transform = self.draw_list.transform();
+        let instances = self.draw_list.push_instances(instances);
+        self.draw_list
+            .push_retained(handle, &[transform], instances)
+    }
+
+    fn update(&mut self, handle: GeometryHandle, path: &mut impl IntoPath) -> VlResult<()> {
+        let path = match self.draw_list.retained_thickness(handle)? {
+            Some(thickness) => path.into_stroke_path(
+                &self.instance,
+                self.physical_device,
+                &self.logical_device,
+                thickness,
+            ),
+            None => path.into_path(&self.instance, self.physical_device, &self.logical_device),
+        };
+        self.draw_list.replace_retained(handle, path)
+    }
+
+    fn free(&mut self, handle: GeometryHandle) -> VlResult<()> {
+        self.draw_list.free_retained(handle)
+    }
+}
+
+impl Drop for PngRenderTarget {
+    fn drop(&mut self) {
+        self.logical_device.destroy_render_pass(&self.render_pass);
+        self.logical_device.destroy_command_buffer(&self.buffer);
+        self.draw_list.destroy(&self.logical_device);
+        if let Some(text_pipeline) = self.text_pipeline.take() {
+            text_pipeline.destroy_with_device(&self.logical_device);
+        }
+        if let Some(gradient_pipeline) = self.gradient_pipeline.take() {
+            gradient_pipeline.destroy_with_device(&self.logical_device);
+        }
+        if let Some(texture_pipeline) = self.texture_pipeline.take() {
+            texture_pipeline.destroy_with_device(&self.logical_device);
+        }
+        unsafe {
+            self.logical_device
+                .inner
+                .destroy_image(self.image.unwrap().inner, None);
+            self.logical_device
+                .inner
+                .destroy_framebuffer(self.frame_buffer.inner, None);
+            self.logical_device.inner.destroy_fence(self.fence, None);
+        }
+        self.logical_device.destroy(&self.image.unwrap());
+        //self.logical_device.destroy(&self.descriptor);
+        self.instance.destroy(&self.logical_device);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{geometry::Path, Destroy, LogicalDevice, VlError, VlResult};
+
+/// # GeometryHandle
+/// Refers to a path that stays uploaded on a render target across frames.
+///
+/// Handles are created with [`BatchedRenderTarget::upload`](crate::BatchedRenderTarget::upload) and
+/// [`BatchedRenderTarget::upload_stroke`](crate::BatchedRenderTarget::upload_stroke),
+/// and stay valid until they are passed to [`BatchedRenderTarget::free`](crate::BatchedRenderTarget::free).
+/// A handle is only meaningful for the render target that created it.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{geometry::PathGeometry, target::PngRenderTarget, BatchedRenderTarget, RenderTarget, Vec2};
+/// # fn frames(target: &mut PngRenderTarget) -> vulx::VlResult<()> {
+/// let mut circle = PathGeometry::new();
+/// circle.circle(Vec2::new(100.0, 100.0), 50.0);
+/// let handle = target.upload(&mut circle);
+/// for _ in 0..2 {
+///     target.begin();
+///     target.draw(handle)?;
+///     target.end();
+/// }
+/// target.free(handle)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GeometryHandle {
+    pub(crate) index: usize,
+    pub(crate) generation: u32,
+}
+
+struct Slot {
+    generation: u32,
+    /// The uploaded path and the stroke thickness it was generated with, if it is a stroke.
+    geometry: Option<(Path, Option<f64>)>,
+}
+
+/// Paths owned by a render target until they are freed explicitly.
+#[doc(hidden)]
+#[derive(Default)]
+pub(crate) struct RetainedPaths {
+    slots: Vec<Slot>,
+}
+
+impl RetainedPaths {
+    pub(crate) fn insert(&mut self, path: Path, thickness: Option<f64>) -> GeometryHandle {
+        let geometry = Some((path, thickness));
+        match self.slots.iter().position(|s| s.geometry.is_none()) {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.generation += 1;
+                slot.geometry = geometry;
+                GeometryHandle {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    geometry,
+                });
+                GeometryHandle {
+                    index: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, handle: GeometryHandle) -> VlResult<&Path> {
+        self.slot(handle).map(|(path, _)| path)
+    }
+
+    /// Returns the stroke thickness of the path, or `None` if it is a fill.
+    pub(crate) fn thickness(&self, handle: GeometryHandle) -> VlResult<Option<f64>> {
+        self.slot(handle).map(|(_, thickness)| *thickness)
+    }
+
+    /// Puts `path` in place of the current one and returns the old path.
+    pub(crate) fn replace(&mut self, handle: GeometryHandle, path: Path) -> VlResult<Path> {
+        self.slot(handle)?;
+        let (old, _) = self.slots[handle.index].geometry.as_mut().unwrap();
+        Ok(std::mem::replace(old, path))
+    }
+
+    pub(crate) fn remove(&mut self, handle: GeometryHandle) -> VlResult<Path> {
+        self.slot(handle)?;
+        Ok(self.slots[handle.index].geometry.take().unwrap().0)
+    }
+
+    pub(crate) fn destroy(&mut self, device: &LogicalDevice) {
+        for (path, _) in self.slots.iter().filter_map(|s| s.geometry.as_ref()) {
+            path.destroy_with_device(device);
+        }
+        self.slots.clear();
+    }
+
+    fn slot(&self, handle: GeometryHandle) -> VlResult<&(Path, Option<f64>)> {
+        self.slots
+            .get(handle.index)
+            .filter(|s| s.generation == handle.generation)
+            .and_then(|s| s.geometry.as_ref())
+            .ok_or(VlError::InvalidState("The geometry handle has been freed."))
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    geometry::{Bounds, FillRule, PathGeometry},
+    TexturedVertex, Vec2, VlError, VlResult,
+};
+
+use super::{Font, GlyphRun};
+
+/// Empty pixels between two glyphs, so that filtering does not bleed into the neighbours.
+const GAP: u32 = 1;
+
+static NEXT_ATLAS_ID: AtomicU64 = AtomicU64::new(0);
+
+/// # AtlasGlyph
+/// Where a glyph is stored in a [`GlyphAtlas`] and where its quad is placed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasGlyph {
+    /// The texture coordinates of the glyph, from 0.0 to 1.0.
+    pub uv: Bounds,
+    /// The top-left corner of the quad relative to the baseline origin, in pixels at the em size of the atlas.
+    pub offset: Vec2<f32>,
+    /// The size of the quad in pixels at the em size of the atlas.
+    pub size: Vec2<f32>,
+}
+
+/// # GlyphAtlas
+/// A cache of glyphs rasterized as signed distance fields into one square single-channel image.
+///
+/// Glyphs are rasterized once at the em size of the atlas and can then be drawn at any size with
+/// [`RenderTarget::draw_glyphs`](crate::RenderTarget::draw_glyphs). Each pixel stores the distance
+/// to the outline, so edges stay sharp when the glyph is scaled; `spread` is the largest distance
+/// that is stored, in pixels at the em size.
+///
+/// The atlas fills lazily: a glyph is rasterized the first time it is requested. Glyphs of several
+/// fonts can share an atlas. When it is full, requesting a new glyph fails with [`VlError::OutOfMemory`].
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{text::{Font, GlyphAtlas, TextLayout}, RenderTarget, Vec2, Vec4};
+/// # fn draw(target: &mut impl RenderTarget, font: &Font) -> vulx::VlResult<()> {
+/// let mut atlas = GlyphAtlas::new(1024);
+/// let mut run = TextLayout::new(font, 64.0).layout("Scalable", Vec2::new(10.0, 10.0));
+/// run.color(Vec4::new(1.0, 1.0, 1.0, 1.0));
+/// target.draw_glyphs(&mut atlas, &run)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct GlyphAtlas {
+    id: u64,
+    size: u32,
+    em_size: f32,
+    spread: f32,
+    pixels: Vec<u8>,
+    /// Glyphs by font and glyph id. `None` for glyphs without outline.
+    glyphs: HashMap<(u64, u16), Option<AtlasGlyph>>,
+    /// The top-left corner of the free space of the current shelf.
+    cursor: Vec2<u32>,
+    shelf_height: u32,
+    version: u64,
+}
+
+impl GlyphAtlas {
+    /// Creates an empty atlas of `size` by `size` pixels.
+    pub fn new(size: u32) -> Self {
+        Self {
+            id: NEXT_ATLAS_ID.fetch_add(1, Ordering::Relaxed),
+            size,
+            em_size: 48.0,
+            spread: 6.0,
+            pixels: vec![0; size as usize * size as usize],
+            glyphs: HashMap::new(),
+            cursor: Vec2::new(0, 0),
+            shelf_height: 0,
+            version: 0,
+        }
+    }
+
+    /// The size in pixels per em at which glyphs are rasterized. Defaults to 48.
+    /// Larger sizes keep more detail at the cost of space in the atlas.
+    pub fn em_size(mut self, em_size: f32) -> Self {
+        self.em_size = em_size;
+        self
+    }
+
+    /// The largest distance from an outline that is stored, in pixels at the em size. Defaults to 6.
+    pub fn spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The distance field, one byte per pixel, rows from top to bottom.
+    /// 128 lies on an outline and greater values are inside.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Increases whenever a glyph is added.
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the glyph of `font`, rasterizing it if it is not in the atlas yet.
+    ///
+    /// Returns `None` for glyphs without an outline, such as spaces.
+    pub fn glyph(&mut self, font: &Font, glyph: u16) -> VlResult<Option<AtlasGlyph>> {
+        if let Some(entry) = self.glyphs.get(&(font.id(), glyph)) {
+            return Ok(*entry);
+        }
+        let entry = self.rasterize(font, glyph)?;
+        self.glyphs.insert((font.id(), glyph), entry);
+        Ok(entry)
+    }
+
+    fn rasterize(&mut self, font: &Font, glyph: u16) -> VlResult<Option<AtlasGlyph>> {
+        let mut path = PathGeometry::new();
+        if !font.glyph_outline(glyph, self.em_size, Vec2::new(0.0, 0.0), &mut path) {
+            return Ok(None);
+        }
+        let Some(bounds) = path.bounds() else {
+            return Ok(None);
+        };
+
+        let padding = self.spread.ceil();
+        let origin =
+            Vec2::new(bounds.min.x.floor(), bounds.min.y.floor()) - Vec2::new(padding, padding);
+        let width = (bounds.max.x.ceil() - origin.x + padding) as u32;
+        let height = (bounds.max.y.ceil() - origin.y + padding) as u32;
+        let position = self.allocate(width, height)?;
+
+        let field = path.signed_distance_field(
+            origin,
+            width as usize,
+            height as usize,
+            self.spread,
+            FillRule::NonZero,
+        );
+        for (row, line) in field.chunks_exact(width as usize).enumerate() {
+            let start = (position.y as usize + row) * self.size as usize + position.x as usize;
+            self.pixels[start..start + width as usize].copy_from_slice(line);
+        }
+        self.version += 1;
+
+        let size = self.size as f32;
+        Ok(Some(AtlasGlyph {
+            uv: Bounds {
+                min: Vec2::new(position.x as f32 / size, position.y as f32 / size),
+                max: Vec2::new(
+                    (position.x + width) as f32 / size,
+                    (position.y + height) as f32 / size,
+                ),
+            },
+            offset: origin,
+            size: Vec2::new(width as f32, height as f32),
+        }))
+    }
+
+    /// Finds space for a rectangle by filling shelves from left to right and top to bottom.
+    fn allocate(&mut self, width: u32, height: u32) -> VlResult<Vec2<u32>> {
+        if self.cursor.x + width > self.size {
+            self.cursor = Vec2::new(0, self.cursor.y + self.shelf_height + GAP);
+            self.shelf_height = 0;
+        }
+        if self.cursor.x + width > self.size || self.cursor.y + height > self.size {
+            return Err(VlError::OutOfMemory);
+        }
+        let position = self.cursor;
+        self.cursor.x += width + GAP;
+        self.shelf_height = self.shelf_height.max(height);
+        Ok(position)
+    }
+
+    /// Builds a textured quad for every glyph of `run`, two clockwise triangles each.
+    pub(crate) fn quads(&mut self, run: &GlyphRun) -> VlResult<(Vec<TexturedVertex>, Vec<u32>)> {
+        let scale = run.size() / self.em_size;
+        let color = run.fill_color();
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        for positioned in run.glyphs() {
+            let Some(glyph) = self.glyph(run.font(), positioned.glyph)? else {
+                continue;
+            };
+            let min = positioned.position + glyph.offset * scale;
+            let max = min + glyph.size * scale;
+            let vertex = |x: f32, y: f32, u: f32, v: f32| TexturedVertex {
+                pos: Vec2::new(x, y),
+                uv: Vec2::new(u, v),
+                color,
+            };
+            let (uv_min, uv_max) = (glyph.uv.min, glyph.uv.max);
+            let first = vertices.len() as u32;
+            vertices.extend([
+                vertex(min.x, min.y, uv_min.x, uv_min.y),
+                vertex(max.x, min.y, uv_max.x, uv_min.y),
+                vertex(max.x, max.y, uv_max.x, uv_max.y),
+                vertex(min.x, max.y, uv_min.x, uv_max.y),
+            ]);
+            indices.extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+        }
+        Ok((vertices, indices))
+    }
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
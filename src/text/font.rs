@@ -0,0 +1,227 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ttf_parser::{
+    gpos::{PairAdjustment, PositioningSubtable},
+    opentype_layout::LayoutTable,
+    Face, GlyphId, OutlineBuilder, Tag,
+};
+
+use crate::{geometry::PathGeometry, Vec2, VlError, VlResult};
+
+/// # Font
+/// A TrueType or OpenType font loaded from memory.
+///
+/// Glyph outlines are converted to [`PathGeometry`] at a size in pixels per em.
+/// Positions are in pixels with the y axis pointing down, and text is placed on its baseline.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{text::Font, RenderTarget, Vec2, Vec4};
+/// # fn draw(target: &mut impl RenderTarget) -> vulx::VlResult<()> {
+/// let font = Font::new(std::fs::read("DejaVuSans.ttf").unwrap())?;
+/// target.begin();
+/// target.draw_text(&font, 24.0, Vec2::new(10.0, 40.0), Vec4::new(1.0, 1.0, 1.0, 1.0), "Hello");
+/// target.end();
+/// # Ok(())
+/// # }
+/// ```
+pub struct Font {
+    /// Parsed once from `data`, which it borrows. Declared first so that it is dropped first.
+    face: Face<'static>,
+    /// Never modified, so its heap allocation stays in place when the font is moved.
+    #[allow(dead_code)]
+    data: Vec<u8>,
+    /// Tells fonts apart in a [`GlyphAtlas`](super::GlyphAtlas).
+    id: u64,
+}
+
+static NEXT_FONT_ID: AtomicU64 = AtomicU64::new(0);
+
+impl Font {
+    /// Loads the first font of a font file or collection.
+    pub fn new(data: Vec<u8>) -> VlResult<Self> {
+        Self::with_index(data, 0)
+    }
+
+    /// Loads the font at `index` of a font collection (`.ttc`/`.otc`).
+    pub fn with_index(data: Vec<u8>, index: u32) -> VlResult<Self> {
+        // SAFETY: The slice points into the heap allocation of `data`, which is owned by the font,
+        // never modified and dropped after the face.
+        let bytes: &'static [u8] = unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
+        let face = Face::parse(bytes, index)
+            .map_err(|_| VlError::ParseError(0, "Not a valid TrueType or OpenType font."))?;
+        Ok(Self {
+            face,
+            data,
+            id: NEXT_FONT_ID.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub(crate) fn face(&self) -> &Face<'_> {
+        &self.face
+    }
+
+    /// The scale from font units to pixels at `size` pixels per em.
+    pub fn scale(&self, size: f32) -> f32 {
+        size / self.face().units_per_em() as f32
+    }
+
+    /// The distance from the baseline to the top of the tallest glyphs, in pixels.
+    pub fn ascender(&self, size: f32) -> f32 {
+        self.face().ascender() as f32 * self.scale(size)
+    }
+
+    /// The distance from the baseline to the bottom of the lowest glyphs, in pixels. Usually negative.
+    pub fn descender(&self, size: f32) -> f32 {
+        self.face().descender() as f32 * self.scale(size)
+    }
+
+    /// The recommended distance between two baselines, in pixels.
+    pub fn line_height(&self, size: f32) -> f32 {
+        let face = self.face();
+        (face.ascender() - face.descender() + face.line_gap()) as f32 * self.scale(size)
+    }
+
+    /// Returns the glyph of `c`, or `None` if the font does not cover it.
+    pub fn glyph(&self, c: char) -> Option<u16> {
+        self.face().glyph_index(c).map(|id| id.0)
+    }
+
+    /// The horizontal distance from this glyph to the next one, in pixels.
+    pub fn advance(&self, glyph: u16, size: f32) -> f32 {
+        let face = self.face();
+        face.glyph_hor_advance(GlyphId(glyph)).unwrap_or(0) as f32 * self.scale(size)
+    }
+
+    /// The adjustment of the distance between two adjacent glyphs, in pixels.
+    /// Negative values move the glyphs closer together.
+    ///
+    /// Read from the pair adjustments of the `kern` feature in the `GPOS` table, or from the legacy `kern`
+    /// table if the font has no `GPOS` table. Contextual adjustments and adjustments of other
+    /// features are not applied.
+    pub fn kerning(&self, left: u16, right: u16, size: f32) -> f32 {
+        let (left, right) = (GlyphId(left), GlyphId(right));
+        let face = self.face();
+        let kerning = match face.tables().gpos {
+            Some(gpos) => gpos_kerning(gpos, left, right),
+            None => face.tables().kern.and_then(|kern| {
+                kern.subtables
+                    .into_iter()
+                    .filter(|s| s.horizontal && !s.variable && !s.has_cross_stream)
+                    .find_map(|s| s.glyphs_kerning(left, right))
+            }),
+        };
+        kerning.map_or(0.0, |k| k as f32 * self.scale(size))
+    }
+
+    /// Appends the outline of `glyph` to `path`, with its baseline origin at `origin`.
+    ///
+    /// Returns false if the glyph has no outline, e.g. a space or a bitmap-only glyph.
+    pub fn glyph_outline(
+        &self,
+        glyph: u16,
+        size: f32,
+        origin: Vec2<f32>,
+        path: &mut PathGeometry,
+    ) -> bool {
+        let mut builder = GlyphBuilder {
+            path,
+            origin,
+            scale: self.scale(size),
+        };
+        self.face()
+            .outline_glyph(GlyphId(glyph), &mut builder)
+            .is_some()
+    }
+
+    /// Appends the outlines of a single line of text starting at `position` on the baseline to `path`,
+    /// and returns the position after the last glyph.
+    ///
+    /// Glyphs are placed one after another by their advance, without kerning; see [`TextLayout`](super::TextLayout)
+    /// for kerned and multi-line text. Characters that the font does not cover are drawn with its missing glyph.
+    pub fn text_outline(
+        &self,
+        text: &str,
+        size: f32,
+        position: Vec2<f32>,
+        path: &mut PathGeometry,
+    ) -> Vec2<f32> {
+        let mut origin = position;
+        for c in text.chars() {
+            let glyph = self.glyph(c).unwrap_or(0);
+            self.glyph_outline(glyph, size, origin, path);
+            origin.x += self.advance(glyph, size);
+        }
+        origin
+    }
+}
+
+/// Finds the first pair adjustment of the `kern` feature that covers the two glyphs.
+fn gpos_kerning(gpos: LayoutTable, left: GlyphId, right: GlyphId) -> Option<i16> {
+    gpos.features
+        .into_iter()
+        .filter(|feature| feature.tag == Tag::from_bytes(b"kern"))
+        .flat_map(|feature| feature.lookup_indices)
+        .filter_map(|index| gpos.lookups.get(index))
+        .flat_map(|lookup| lookup.subtables.into_iter::<PositioningSubtable>())
+        .find_map(|subtable| {
+            let PositioningSubtable::Pair(pair) = subtable else {
+                return None;
+            };
+            let first = pair.coverage().get(left)?;
+            let (record, _) = match pair {
+                PairAdjustment::Format1 { sets, .. } => sets.get(first)?.get(right)?,
+                PairAdjustment::Format2 {
+                    classes, matrix, ..
+                } => matrix.get((classes.0.get(left), classes.1.get(right)))?,
+            };
+            Some(record.x_advance)
+        })
+}
+
+/// Converts glyph outlines from font units with the y axis up into pixels with the y axis down.
+struct GlyphBuilder<'a> {
+    path: &'a mut PathGeometry,
+    origin: Vec2<f32>,
+    scale: f32,
+}
+
+impl GlyphBuilder<'_> {
+    fn point(&self, x: f32, y: f32) -> Vec2<f32> {
+        Vec2::new(
+            self.origin.x + x * self.scale,
+            self.origin.y - y * self.scale,
+        )
+    }
+}
+
+impl OutlineBuilder for GlyphBuilder<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.path.move_to(point);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.path.line_to(point);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (control, point) = (self.point(x1, y1), self.point(x, y));
+        self.path.quad_to(control, point);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (control1, control2, point) =
+            (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.path.cubic_to(control1, control2, point);
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{
+    geometry::{Bounds, Path, PathGeometry, Tessellation},
+    Instance, IntoPath, LogicalDevice, PhysicalDevice, Vec2, Vec4,
+};
+
+use super::Font;
+
+/// Specifies how the lines of a paragraph are placed horizontally
+///
+/// # Value Meaning
+/// * `Left` - Lines start at the left edge.
+/// * `Center` - Lines are centered between the edges.
+/// * `Right` - Lines end at the right edge.
+/// * `Justify` - Spaces are widened so that lines touch both edges. The last line of a paragraph is aligned left.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+/// # PositionedGlyph
+/// A glyph placed by [`TextLayout`], with `position` on its baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositionedGlyph {
+    pub glyph: u16,
+    pub position: Vec2<f32>,
+    /// The byte offset in the text of the character this glyph was made from.
+    pub cluster: usize,
+}
+
+/// # TextLayout
+/// Breaks text into lines and places its glyphs.
+///
+/// Lines are broken at `\n` and, if a maximum width is set, between words. Words longer than the
+/// maximum width are broken between characters. Glyphs are advanced by their metrics and the
+/// kerning of the font.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{text::{Font, TextAlign, TextLayout}, RenderTarget, Vec2, Vec4};
+/// # fn draw(target: &mut impl RenderTarget, font: &Font) {
+/// let mut run = TextLayout::new(font, 16.0)
+///     .max_width(200.0)
+///     .align(TextAlign::Justify)
+///     .line_spacing(1.2)
+///     .layout("The quick brown fox jumps over the lazy dog.", Vec2::new(10.0, 10.0));
+/// run.color(Vec4::new(0.0, 0.0, 0.0, 1.0));
+/// let height = run.bounds().size().y;
+/// target.fill(&mut run);
+/// # }
+/// ```
+pub struct TextLayout<'a> {
+    font: &'a Font,
+    size: f32,
+    max_width: Option<f32>,
+    align: TextAlign,
+    line_spacing: f32,
+}
+
+/// The characters of a paragraph and its lines, as ranges of the characters.
+struct Paragraph {
+    items: Vec<Item>,
+    lines: Vec<(usize, usize)>,
+}
+
+/// A character of the text with its metrics.
+struct Item {
+    glyph: u16,
+    cluster: usize,
+    advance: f32,
+    space: bool,
+}
+
+impl<'a> TextLayout<'a> {
+    /// Lays out text in `font` at `size` pixels per em.
+    pub fn new(font: &'a Font, size: f32) -> Self {
+        Self {
+            font,
+            size,
+            max_width: None,
+            align: TextAlign::Left,
+            line_spacing: 1.0,
+        }
+    }
+
+    /// Wraps lines that would be wider than `max_width` pixels.
+    pub fn max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Multiplies the line height recommended by the font.
+    pub fn line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    /// Places the glyphs of `text` with the top-left corner of the first line at `position`.
+    pub fn layout(&self, text: &str, position: Vec2<f32>) -> GlyphRun<'a> {
+        let paragraphs: Vec<Paragraph> = text
+            .split('\n')
+            .scan(0, |offset, paragraph| {
+                let start = *offset;
+                *offset += paragraph.len() + 1;
+                Some(self.items(paragraph.trim_end_matches('\r'), start))
+            })
+            .map(|items| Paragraph {
+                lines: self.break_lines(&items),
+                items,
+            })
+            .collect();
+
+        let width = self.max_width.unwrap_or_else(|| {
+            paragraphs
+                .iter()
+                .flat_map(|Paragraph { items, lines }| {
+                    lines.iter().map(|&(start, end)| {
+                        self.width(&items[start..trim_spaces(items, start, end)])
+                    })
+                })
+                .fold(0.0, f32::max)
+        });
+        let line_height = self.font.line_height(self.size) * self.line_spacing;
+
+        let mut glyphs = vec![];
+        let mut baseline = position.y + self.font.ascender(self.size);
+        let mut min_x = f32::INFINITY;
+        let mut max_x = f32::NEG_INFINITY;
+        let mut line_count = 0;
+        for Paragraph { items, lines } in &paragraphs {
+            for (i, &(start, end)) in lines.iter().enumerate() {
+                if line_count > 0 {
+                    baseline += line_height;
+                }
+                line_count += 1;
+                let end = trim_spaces(items, start, end);
+                let line = &items[start..end];
+                let line_width = self.width(line);
+                let last = i + 1 == lines.len();
+                let spaces = line.iter().filter(|item| item.space).count();
+                let (mut x, space_extra) = match self.align {
+                    TextAlign::Left => (0.0, 0.0),
+                    TextAlign::Center => ((width - line_width) / 2.0, 0.0),
+                    TextAlign::Right => (width - line_width, 0.0),
+                    TextAlign::Justify if last || spaces == 0 => (0.0, 0.0),
+                    TextAlign::Justify => (0.0, (width - line_width).max(0.0) / spaces as f32),
+                };
+                min_x = min_x.min(x);
+                for (j, item) in line.iter().enumerate() {
+                    if j > 0 {
+                        x += self.font.kerning(line[j - 1].glyph, item.glyph, self.size);
+                    }
+                    glyphs.push(PositionedGlyph {
+                        glyph: item.glyph,
+                        position: Vec2::new(position.x + x, baseline),
+                        cluster: item.cluster,
+                    });
+                    x += item.advance;
+                    if item.space {
+                        x += space_extra;
+                    }
+                }
+                max_x = max_x.max(x);
+            }
+        }
+
+        let bounds = Bounds {
+            min: Vec2::new(position.x + min_x, position.y),
+            max: Vec2::new(
+                position.x + max_x,
+                baseline - self.font.descender(self.size),
+            ),
+        };
+        GlyphRun {
+            font: self.font,
+            size: self.size,
+            color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            glyphs,
+            bounds,
+            line_count,
+        }
+    }
+
+    fn items(&self, paragraph: &str, offset: usize) -> Vec<Item> {
+        paragraph
+            .char_indices()
+            .map(|(i, c)| {
+                let glyph = self.font.glyph(c).unwrap_or(0);
+                Item {
+                    glyph,
+                    cluster: offset + i,
+                    advance: self.font.advance(glyph, self.size),
+                    space: c.is_whitespace(),
+                }
+            })
+            .collect()
+    }
+
+    /// The width of the items from the start of the first to the end of the last one, with kerning.
+    fn width(&self, items: &[Item]) -> f32 {
+        let kerning: f32 = items
+            .windows(2)
+            .map(|pair| self.font.kerning(pair[0].glyph, pair[1].glyph, self.size))
+            .sum();
+        items.iter().map(|item| item.advance).sum::<f32>() + kerning
+    }
+
+    /// Splits a paragraph into lines no wider than the maximum width, ignoring trailing spaces.
+    fn break_lines(&self, items: &[Item]) -> Vec<(usize, usize)> {
+        let Some(max_width) = self.max_width else {
+            return vec![(0, items.len())];
+        };
+        let fits = |start: usize, end: usize| {
+            self.width(&items[start..trim_spaces(items, start, end)]) <= max_width
+        };
+
+        let mut lines = vec![];
+        let mut start = 0;
+        let mut word = 0;
+        while word < items.len() {
+            // A word with the spaces after it.
+            let word_end = (word..items.len())
+                .find(|&i| items[i].space)
+                .unwrap_or(items.len());
+            let end = (word_end..items.len())
+                .find(|&i| !items[i].space)
+                .unwrap_or(items.len());
+
+            if fits(start, end) {
+                word = end;
+                continue;
+            }
+            if start < word {
+                lines.push((start, word));
+                start = word;
+                continue;
+            }
+            // The word alone is too wide, so break it after the last character that fits.
+            let split = (start + 1..word_end)
+                .rev()
+                .find(|&i| fits(start, i))
+                .unwrap_or(start + 1);
+            lines.push((start, split));
+            start = split;
+            word = split;
+        }
+        if start < items.len() || lines.is_empty() {
+            lines.push((start, items.len()));
+        }
+        lines
+    }
+}
+
+/// The end of the range without its trailing spaces.
+fn trim_spaces(items: &[Item], start: usize, end: usize) -> usize {
+    (start..end)
+        .rev()
+        .find(|&i| !items[i].space)
+        .map_or(start, |i| i + 1)
+}
+
+/// # GlyphRun
+/// Glyphs placed by [`TextLayout`], which can be filled on any render target.
+pub struct GlyphRun<'a> {
+    font: &'a Font,
+    size: f32,
+    color: Vec4<f32>,
+    glyphs: Vec<PositionedGlyph>,
+    bounds: Bounds,
+    line_count: usize,
+}
+
+impl GlyphRun<'_> {
+    /// Specifies the fill color. Defaults to white.
+    pub fn color(&mut self, color: impl Into<Vec4<f32>>) {
+        self.color = color.into();
+    }
+
+    pub fn glyphs(&self) -> &[PositionedGlyph] {
+        &self.glyphs
+    }
+
+    /// The box from the top of the first line to the bottom of the last one,
+    /// and from the start of the leftmost line to the end of the rightmost one.
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub(crate) fn font(&self) -> &Font {
+        self.font
+    }
+
+    pub(crate) fn fill_color(&self) -> Vec4<f32> {
+        self.color
+    }
+
+    /// Builds the outlines of all glyphs.
+    pub fn path(&self) -> PathGeometry {
+        let mut path = PathGeometry::new();
+        path.color(self.color);
+        for glyph in &self.glyphs {
+            self.font
+                .glyph_outline(glyph.glyph, self.size, glyph.position, &mut path);
+        }
+        path
+    }
+}
+
+impl IntoPath for GlyphRun<'_> {
+    fn into_path(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+    ) -> Path {
+        self.path().into_path(instance, physical_device, device)
+    }
+
+    fn into_stroke_path(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        thickness: f64,
+    ) -> Path {
+        self.path()
+            .into_stroke_path(instance, physical_device, device, thickness)
+    }
+
+    fn fill_tessellation(&mut self) -> Option<Tessellation> {
+        self.path().fill_tessellation()
+    }
+
+    fn stroke_tessellation(&mut self, thickness: f64) -> Option<Tessellation> {
+        self.path().stroke_tessellation(thickness)
+    }
+}
@@ -0,0 +1,8 @@
+mod atlas;
+mod font;
+mod layout;
+mod sdf;
+pub use atlas::*;
+pub use font::*;
+pub use layout::*;
+pub(crate) use sdf::TextPipeline;
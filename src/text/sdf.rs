@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use ash::vk::{
+    DescriptorBufferInfo, DescriptorImageInfo, DescriptorPool, DescriptorPoolCreateInfo,
+    DescriptorPoolSize, DescriptorSet, DescriptorSetAllocateInfo, DescriptorSetLayout,
+    DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorType, ImageLayout,
+    PipelineLayout, PipelineLayoutCreateInfo, PolygonMode, PrimitiveTopology, PushConstantRange,
+    ShaderStageFlags, WriteDescriptorSet,
+};
+
+use crate::{
+    geometry::Mvp,
+    target::{CommandBuffer, PipelineBinding},
+    BlendMode, Destroy, Instance, LogicalDevice, Mat4, MipmapMode, PhysicalDevice, Pipeline,
+    PipelineState, Queue, RenderPass, Sampler, Shader, ShaderKind, Spirv, Stage, Texture,
+    TextureFormat, TexturedVertex, VertexLayout, VlError, VlResult,
+};
+
+use super::GlyphAtlas;
+
+const TEXT_VERTEX_SHADER: &[u8] = include_bytes!("../spv/text.vert.spv");
+const TEXT_FRAGMENT_SHADER: &[u8] = include_bytes!("../spv/text.frag.spv");
+
+/// How many atlases one render target can draw with.
+const MAX_ATLASES: u32 = 16;
+
+/// The texture of an atlas on the GPU.
+struct AtlasTexture {
+    texture: Texture,
+    descriptor_set: DescriptorSet,
+    /// The version of the atlas that was last copied into the texture.
+    version: u64,
+}
+
+/// Draws glyph quads by sampling the distance fields of a [`GlyphAtlas`].
+///
+/// Each atlas gets its own texture and descriptor set, which are kept until the pipeline is destroyed.
+/// The output is blended over the image by its alpha.
+pub(crate) struct TextPipeline {
+    pipeline: Pipeline,
+    /// The uniform buffer of the stage, bound to binding 0.
+    uniform_buffer: ash::vk::Buffer,
+    layout: PipelineLayout,
+    descriptor_layout: DescriptorSetLayout,
+    descriptor_pool: DescriptorPool,
+    shaders: [Shader; 2],
+    atlases: HashMap<u64, AtlasTexture>,
+}
+
+impl TextPipeline {
+    pub(crate) fn new(
+        device: &LogicalDevice,
+        render_pass: &RenderPass,
+        stage: &Stage,
+    ) -> VlResult<Self> {
+        let bindings = [
+            (0, DescriptorType::UNIFORM_BUFFER, ShaderStageFlags::VERTEX),
+            (1, DescriptorType::SAMPLED_IMAGE, ShaderStageFlags::FRAGMENT),
+            (2, DescriptorType::SAMPLER, ShaderStageFlags::FRAGMENT),
+        ]
+        .map(|(binding, ty, stage)| {
+            DescriptorSetLayoutBinding::builder()
+                .binding(binding)
+                .descriptor_type(ty)
+                .descriptor_count(1)
+                .stage_flags(stage)
+                .build()
+        });
+        let create_info = DescriptorSetLayoutCreateInfo::builder()
+            .bindings(&bindings)
+            .build();
+        let descriptor_layout = unsafe {
+            device
+                .inner
+                .create_descriptor_set_layout(&create_info, None)
+        }?;
+
+        let pool_sizes = bindings.map(|binding| {
+            DescriptorPoolSize::builder()
+                .ty(binding.descriptor_type)
+                .descriptor_count(MAX_ATLASES)
+                .build()
+        });
+        let create_info = DescriptorPoolCreateInfo::builder()
+            .pool_sizes(&pool_sizes)
+            .max_sets(MAX_ATLASES)
+            .build();
+        let descriptor_pool = unsafe { device.inner.create_descriptor_pool(&create_info, None) }?;
+
+        // The per-draw transform, as for the default pipeline.
+        let push_constant_ranges = [PushConstantRange::builder()
+            .stage_flags(ShaderStageFlags::VERTEX)
+            .offset(0)
+            .size(std::mem::size_of::<Mat4<f32>>() as u32)
+            .build()];
+        let layout_create_info = PipelineLayoutCreateInfo::builder()
+            .set_layouts(&[descriptor_layout])
+            .push_constant_ranges(&push_constant_ranges)
+            .build();
+        let layout = unsafe {
+            device
+                .inner
+                .create_pipeline_layout(&layout_create_info, None)
+        }?;
+
+        let shaders = [
+            device
+                .create_shader_module(Spirv::from_bytes(TEXT_VERTEX_SHADER), ShaderKind::Vertex)?,
+            device.create_shader_module(
+                Spirv::from_bytes(TEXT_FRAGMENT_SHADER),
+                ShaderKind::Fragment,
+            )?,
+        ];
+        let vertex_layout = VertexLayout::of::<TexturedVertex>();
+        let state = PipelineState {
+            mode: PolygonMode::FILL,
+            topology: PrimitiveTopology::TRIANGLE_LIST,
+            vertex_layout: &vertex_layout,
+            width: stage.width,
+            height: stage.height,
+            line_width: 1.0,
+            blend: BlendMode::Alpha,
+        };
+        let pipeline = render_pass
+            .create_graphics_pipeline(device, &shaders, layout, &state)?
+            .pop()
+            .ok_or(VlError::InvalidState("No text pipeline was created."))?;
+
+        Ok(Self {
+            pipeline,
+            uniform_buffer: stage.buffer.buffer,
+            layout,
+            descriptor_layout,
+            descriptor_pool,
+            shaders,
+            atlases: HashMap::new(),
+        })
+    }
+
+    /// Copies the glyphs added to `atlas` since the last call into its texture, and returns
+    /// how draws with the atlas are bound.
+    ///
+    /// The copy waits for the queue, so no submitted frame may still be drawing with the atlas.
+    pub(crate) fn prepare(
+        &mut self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        queue: Queue,
+        command_buffer: &CommandBuffer,
+        atlas: &GlyphAtlas,
+    ) -> VlResult<PipelineBinding> {
+        if !self.atlases.contains_key(&atlas.id()) {
+            let entry = self.create_atlas_texture(instance, physical_device, device, atlas)?;
+            self.atlases.insert(atlas.id(), entry);
+        }
+        let entry = self.atlases.get_mut(&atlas.id()).unwrap();
+        if entry.version != atlas.version() {
+            entry.texture.write(
+                instance,
+                physical_device,
+                device,
+                queue,
+                command_buffer,
+                atlas.pixels(),
+            )?;
+            entry.version = atlas.version();
+        }
+        Ok(PipelineBinding {
+            pipeline: self.pipeline,
+            layout: self.layout,
+            descriptor_set: entry.descriptor_set,
+        })
+    }
+
+    fn create_atlas_texture(
+        &self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        atlas: &GlyphAtlas,
+    ) -> VlResult<AtlasTexture> {
+        if self.atlases.len() as u32 >= MAX_ATLASES {
+            return Err(VlError::InvalidState(
+                "Too many glyph atlases are drawn on this render target.",
+            ));
+        }
+        let texture = Texture::new(
+            instance,
+            physical_device,
+            device,
+            atlas.size(),
+            atlas.size(),
+            TextureFormat::R8,
+            // A single level, as the atlas is rewritten as glyphs are added.
+            &Sampler::builder()
+                .mipmap_mode(MipmapMode::Nearest)
+                .lod_range(0.0, 0.0)
+                .build(),
+        )?;
+
+        let alloc_info = DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(self.descriptor_pool)
+            .set_layouts(&[self.descriptor_layout])
+            .build();
+        let descriptor_set = match unsafe { device.inner.allocate_descriptor_sets(&alloc_info) } {
+            Ok(sets) => sets[0],
+            Err(e) => {
+                texture.destroy_with_device(device);
+                return Err(VlError::from(e));
+            }
+        };
+
+        let buffer_info = [DescriptorBufferInfo::builder()
+            .buffer(self.uniform_buffer)
+            .offset(0)
+            .range(std::mem::size_of::<Mvp>() as u64)
+            .build()];
+        let image_info = [DescriptorImageInfo::builder()
+            .image_view(texture.view)
+            .image_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .build()];
+        let sampler_info = [DescriptorImageInfo::builder()
+            .sampler(texture.sampler)
+            .build()];
+        let writes = [
+            WriteDescriptorSet::builder()
+                .dst_set(descriptor_set)
+                .dst_binding(0)
+                .descriptor_type(DescriptorType::UNIFORM_BUFFER)
+                .buffer_info(&buffer_info)
+                .build(),
+            WriteDescriptorSet::builder()
+                .dst_set(descriptor_set)
+                .dst_binding(1)
+                .descriptor_type(DescriptorType::SAMPLED_IMAGE)
+                .image_info(&image_info)
+                .build(),
+            WriteDescriptorSet::builder()
+                .dst_set(descriptor_set)
+                .dst_binding(2)
+                .descriptor_type(DescriptorType::SAMPLER)
+                .image_info(&sampler_info)
+                .build(),
+        ];
+        unsafe {
+            device.inner.update_descriptor_sets(&writes, &[]);
+        }
+
+        Ok(AtlasTexture {
+            texture,
+            descriptor_set,
+            // Never matches an atlas, so the first `prepare` copies the pixels.
+            version: u64::MAX,
+        })
+    }
+}
+
+impl Destroy for TextPipeline {
+    fn destroy_with_instance(&self, _: &Instance) {}
+
+    fn destroy_with_device(&self, device: &LogicalDevice) {
+        for entry in self.atlases.values() {
+            entry.texture.destroy_with_device(device);
+        }
+        for shader in &self.shaders {
+            shader.destroy_with_device(device);
+        }
+        device.destroy_pipeline(&self.pipeline);
+        unsafe {
+            device.inner.destroy_pipeline_layout(self.layout, None);
+            device
+                .inner
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            device
+                .inner
+                .destroy_descriptor_set_layout(self.descriptor_layout, None);
+        }
+    }
+}
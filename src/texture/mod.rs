@@ -0,0 +1,500 @@
+use ash::vk::{
+    AccessFlags, BufferImageCopy, ComponentMapping, DependencyFlags, DeviceMemory, Extent3D,
+    Filter, Format, FormatFeatureFlags, ImageAspectFlags, ImageBlit, ImageCreateInfo, ImageLayout,
+    ImageMemoryBarrier, ImageSubresourceLayers, ImageSubresourceRange, ImageTiling,
+    ImageUsageFlags, ImageViewCreateInfo, ImageViewType, MemoryAllocateInfo, MemoryPropertyFlags,
+    Offset3D, PipelineStageFlags, SampleCountFlags, SharingMode, QUEUE_FAMILY_IGNORED,
+};
+
+use crate::{
+    geometry::{Buffer, BufferUsage},
+    target::CommandBuffer,
+    Destroy, Instance, LogicalDevice, PhysicalDevice, Queue, Vec2, Vec4, VlError, VlResult,
+};
+
+mod pipeline;
+mod sampler;
+
+pub use pipeline::TextureHandle;
+pub(crate) use pipeline::TexturePipeline;
+pub use sampler::*;
+
+/// Indicates how the pixels of a texture are stored
+///
+/// # Value Meaning
+/// * `R8` - One byte per pixel, read as the red channel. Used for masks and distance fields.
+/// * `Rgba8` - Four bytes per pixel in RGBA order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TextureFormat {
+    R8,
+    Rgba8,
+}
+
+impl TextureFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            TextureFormat::R8 => 1,
+            TextureFormat::Rgba8 => 4,
+        }
+    }
+}
+
+impl From<TextureFormat> for Format {
+    fn from(format: TextureFormat) -> Self {
+        match format {
+            TextureFormat::R8 => Format::R8_UNORM,
+            TextureFormat::Rgba8 => Format::R8G8B8A8_UNORM,
+        }
+    }
+}
+
+/// # TexturedVertex
+/// A vertex of textured triangles and glyph quads.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TexturedVertex {
+    pub pos: Vec2<f32>,
+    /// The position in the texture, from 0.0 to 1.0.
+    pub uv: Vec2<f32>,
+    /// Multiplied with the sampled color.
+    pub color: Vec4<f32>,
+}
+
+impl TexturedVertex {
+    pub fn new(pos: Vec2<f32>, uv: Vec2<f32>, color: impl Into<Vec4<f32>>) -> Self {
+        Self {
+            pos,
+            uv,
+            color: color.into(),
+        }
+    }
+}
+
+/// # TextureData
+/// The pixels of a texture before they are uploaded, rows from top to bottom.
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{geometry::Bounds, RenderTarget, Sampler, TextureData, Vec2};
+/// # fn draw(target: &mut impl RenderTarget) -> vulx::VlResult<()> {
+/// let data = TextureData::from_png(&std::fs::read("sprite.png").unwrap())?;
+/// let texture = target.create_texture(&data, &Sampler::default())?;
+/// target.begin();
+/// let destination = Bounds {
+///     min: Vec2::new(10.0, 10.0),
+///     max: Vec2::new(10.0 + data.width() as f32, 10.0 + data.height() as f32),
+/// };
+/// target.draw_image(texture, destination)?;
+/// target.end();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureData {
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    pixels: Vec<u8>,
+}
+
+impl TextureData {
+    /// Returns an error if `pixels` does not hold `width` by `height` pixels of `format`.
+    pub fn new(width: u32, height: u32, format: TextureFormat, pixels: Vec<u8>) -> VlResult<Self> {
+        if width == 0
+            || height == 0
+            || pixels.len() != width as usize * height as usize * format.bytes_per_pixel()
+        {
+            return Err(VlError::InvalidState(
+                "The pixels do not match the size of the texture.",
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            format,
+            pixels,
+        })
+    }
+
+    /// Decodes a PNG image into `Rgba8` pixels. Gray, indexed and 16-bit images are converted.
+    pub fn from_png(bytes: &[u8]) -> VlResult<Self> {
+        let error = |_| VlError::ParseError(0, "The PNG image could not be decoded.");
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(error)?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(error)?;
+        buffer.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            // Expanded into RGB by the transformations.
+            png::ColorType::Indexed => {
+                return Err(VlError::ParseError(0, "Unsupported PNG color type."))
+            }
+        };
+        Self::new(info.width, info.height, TextureFormat::Rgba8, pixels)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
+
+/// # Texture
+/// An image in device memory that shaders sample from.
+///
+/// The pixels are copied in through a staging buffer.
+pub struct Texture {
+    pub(crate) image: ash::vk::Image,
+    memory: DeviceMemory,
+    pub(crate) view: ash::vk::ImageView,
+    pub(crate) sampler: ash::vk::Sampler,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    mip_levels: u32,
+}
+
+impl Texture {
+    /// Creates a texture with undefined contents, read with `sampler`.
+    ///
+    /// The texture gets the mip levels the sampler can reach, if the device can generate them for the format.
+    pub(crate) fn new(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        sampler: &Sampler,
+    ) -> VlResult<Self> {
+        // Mip levels are generated by blitting each level into the next.
+        let features = unsafe {
+            instance
+                .inner
+                .get_physical_device_format_properties(physical_device.0, format.into())
+        }
+        .optimal_tiling_features;
+        let mip_levels = if features.contains(
+            FormatFeatureFlags::BLIT_SRC
+                | FormatFeatureFlags::BLIT_DST
+                | FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+        ) {
+            sampler.mip_levels(width, height)
+        } else {
+            1
+        };
+        let mut usage = ImageUsageFlags::SAMPLED | ImageUsageFlags::TRANSFER_DST;
+        if mip_levels > 1 {
+            usage |= ImageUsageFlags::TRANSFER_SRC;
+        }
+
+        let create_info = ImageCreateInfo::builder()
+            .image_type(ash::vk::ImageType::TYPE_2D)
+            .extent(Extent3D {
+                width,
+                height,
+                depth: 1,
+            })
+            .mip_levels(mip_levels)
+            .array_layers(1)
+            .format(format.into())
+            .tiling(ImageTiling::OPTIMAL)
+            .initial_layout(ImageLayout::UNDEFINED)
+            .usage(usage)
+            .sharing_mode(SharingMode::EXCLUSIVE)
+            .samples(SampleCountFlags::TYPE_1)
+            .build();
+        let image = unsafe { device.inner.create_image(&create_info, None) }?;
+
+        let requirements = unsafe { device.inner.get_image_memory_requirements(image) };
+        let properties = unsafe {
+            instance
+                .inner
+                .get_physical_device_memory_properties(physical_device.0)
+        };
+        let memory_type = (0..properties.memory_type_count)
+            .find(|&i| {
+                requirements.memory_type_bits & (1 << i) != 0
+                    && properties.memory_types[i as usize]
+                        .property_flags
+                        .contains(MemoryPropertyFlags::DEVICE_LOCAL)
+            })
+            .ok_or(VlError::OutOfMemory)?;
+        let allocate_info = MemoryAllocateInfo::builder()
+            .allocation_size(requirements.size)
+            .memory_type_index(memory_type)
+            .build();
+        let memory = unsafe { device.inner.allocate_memory(&allocate_info, None) }?;
+        unsafe { device.inner.bind_image_memory(image, memory, 0) }?;
+
+        let view_info = ImageViewCreateInfo::builder()
+            .image(image)
+            .view_type(ImageViewType::TYPE_2D)
+            .format(format.into())
+            .components(ComponentMapping::default())
+            .subresource_range(color_range(0, mip_levels))
+            .build();
+        let view = unsafe { device.inner.create_image_view(&view_info, None) }?;
+        let sampler = sampler.create(instance, physical_device, device)?;
+
+        Ok(Self {
+            image,
+            memory,
+            view,
+            sampler,
+            width,
+            height,
+            format,
+            mip_levels,
+        })
+    }
+
+    /// Creates a texture read with `sampler` and copies `data` into it. Waits until the copy has finished.
+    pub(crate) fn upload(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        queue: Queue,
+        command_buffer: &CommandBuffer,
+        data: &TextureData,
+        sampler: &Sampler,
+    ) -> VlResult<Self> {
+        let texture = Self::new(
+            instance,
+            physical_device,
+            device,
+            data.width(),
+            data.height(),
+            data.format(),
+            sampler,
+        )?;
+        match texture.write(
+            instance,
+            physical_device,
+            device,
+            queue,
+            command_buffer,
+            data.pixels(),
+        ) {
+            Ok(()) => Ok(texture),
+            Err(e) => {
+                texture.destroy_with_device(device);
+                Err(e)
+            }
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /// Replaces all pixels, rows from top to bottom, and generates the smaller mip levels from them.
+    /// Waits until the copy has finished, so it must not be called while a submitted frame
+    /// still samples the texture.
+    pub(crate) fn write(
+        &self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        queue: Queue,
+        command_buffer: &CommandBuffer,
+        pixels: &[u8],
+    ) -> VlResult<()> {
+        let size = self.width as usize * self.height as usize * self.format.bytes_per_pixel();
+        if pixels.len() != size {
+            return Err(VlError::InvalidState(
+                "The pixels do not match the size of the texture.",
+            ));
+        }
+
+        let mut staging = Buffer::new(
+            instance,
+            physical_device,
+            device,
+            pixels.len(),
+            BufferUsage::Staging,
+        );
+        staging.allocate_data(pixels.as_ptr() as *const std::ffi::c_void, device);
+        staging.flush_memory(device)?;
+        staging.unmap_memory(device)?;
+
+        let barrier = |levels: ImageSubresourceRange, old, new, src_access, dst_access| {
+            ImageMemoryBarrier::builder()
+                .old_layout(old)
+                .new_layout(new)
+                .src_access_mask(src_access)
+                .dst_access_mask(dst_access)
+                .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+                .image(self.image)
+                .subresource_range(levels)
+                .build()
+        };
+        let pipeline_barrier = |cmd, src_stage, dst_stage, barrier: ImageMemoryBarrier| unsafe {
+            device.inner.cmd_pipeline_barrier(
+                cmd,
+                src_stage,
+                dst_stage,
+                DependencyFlags::empty(),
+                &[],
+                &[],
+                &[barrier],
+            );
+        };
+        let level_size = |level: u32| Offset3D {
+            x: (self.width >> level).max(1) as i32,
+            y: (self.height >> level).max(1) as i32,
+            z: 1,
+        };
+        let result = command_buffer.record_once(device, queue, |cmd| unsafe {
+            // The previous contents are replaced as a whole, so they need not be preserved.
+            pipeline_barrier(
+                cmd,
+                PipelineStageFlags::TOP_OF_PIPE,
+                PipelineStageFlags::TRANSFER,
+                barrier(
+                    color_range(0, self.mip_levels),
+                    ImageLayout::UNDEFINED,
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    AccessFlags::empty(),
+                    AccessFlags::TRANSFER_WRITE,
+                ),
+            );
+            let region = BufferImageCopy::builder()
+                .image_subresource(color_layers(0))
+                .image_extent(Extent3D {
+                    width: self.width,
+                    height: self.height,
+                    depth: 1,
+                })
+                .build();
+            device.inner.cmd_copy_buffer_to_image(
+                cmd,
+                staging.buffer,
+                self.image,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[region],
+            );
+
+            // Each level is read once written, to fill the next one, and is then ready for the shaders.
+            for level in 1..self.mip_levels {
+                pipeline_barrier(
+                    cmd,
+                    PipelineStageFlags::TRANSFER,
+                    PipelineStageFlags::TRANSFER,
+                    barrier(
+                        color_range(level - 1, 1),
+                        ImageLayout::TRANSFER_DST_OPTIMAL,
+                        ImageLayout::TRANSFER_SRC_OPTIMAL,
+                        AccessFlags::TRANSFER_WRITE,
+                        AccessFlags::TRANSFER_READ,
+                    ),
+                );
+                let blit = ImageBlit::builder()
+                    .src_subresource(color_layers(level - 1))
+                    .src_offsets([Offset3D::default(), level_size(level - 1)])
+                    .dst_subresource(color_layers(level))
+                    .dst_offsets([Offset3D::default(), level_size(level)])
+                    .build();
+                device.inner.cmd_blit_image(
+                    cmd,
+                    self.image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    self.image,
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[blit],
+                    Filter::LINEAR,
+                );
+                pipeline_barrier(
+                    cmd,
+                    PipelineStageFlags::TRANSFER,
+                    PipelineStageFlags::FRAGMENT_SHADER,
+                    barrier(
+                        color_range(level - 1, 1),
+                        ImageLayout::TRANSFER_SRC_OPTIMAL,
+                        ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                        AccessFlags::TRANSFER_READ,
+                        AccessFlags::SHADER_READ,
+                    ),
+                );
+            }
+            pipeline_barrier(
+                cmd,
+                PipelineStageFlags::TRANSFER,
+                PipelineStageFlags::FRAGMENT_SHADER,
+                barrier(
+                    color_range(self.mip_levels - 1, 1),
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                    AccessFlags::TRANSFER_WRITE,
+                    AccessFlags::SHADER_READ,
+                ),
+            );
+        });
+        staging.destroy_with_device(device);
+        result
+    }
+}
+
+fn color_range(base_mip_level: u32, level_count: u32) -> ImageSubresourceRange {
+    ImageSubresourceRange::builder()
+        .aspect_mask(ImageAspectFlags::COLOR)
+        .base_mip_level(base_mip_level)
+        .level_count(level_count)
+        .base_array_layer(0)
+        .layer_count(1)
+        .build()
+}
+
+fn color_layers(mip_level: u32) -> ImageSubresourceLayers {
+    ImageSubresourceLayers::builder()
+        .aspect_mask(ImageAspectFlags::COLOR)
+        .mip_level(mip_level)
+        .base_array_layer(0)
+        .layer_count(1)
+        .build()
+}
+
+impl Destroy for Texture {
+    fn destroy_with_instance(&self, _: &Instance) {}
+
+    fn destroy_with_device(&self, device: &LogicalDevice) {
+        unsafe {
+            device.inner.destroy_sampler(self.sampler, None);
+            device.inner.destroy_image_view(self.view, None);
+            device.inner.destroy_image(self.image, None);
+            device.inner.free_memory(self.memory, None);
+        }
+    }
+}
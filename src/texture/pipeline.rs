@@ -0,0 +1,344 @@
+use ash::vk::{
+    DescriptorBufferInfo, DescriptorImageInfo, DescriptorPool, DescriptorPoolCreateFlags,
+    DescriptorPoolCreateInfo, DescriptorPoolSize, DescriptorSet, DescriptorSetAllocateInfo,
+    DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorType,
+    ImageLayout, PipelineLayout, PipelineLayoutCreateInfo, PolygonMode, PrimitiveTopology,
+    PushConstantRange, ShaderStageFlags, WriteDescriptorSet,
+};
+
+use crate::{
+    geometry::Mvp, target::PipelineBinding, BlendMode, Destroy, Instance, LogicalDevice, Mat4,
+    Pipeline, PipelineState, RenderPass, Shader, ShaderKind, Spirv, Stage, VertexLayout, VlError,
+    VlResult,
+};
+
+use super::{Texture, TexturedVertex};
+
+/// The vertices are the same as for text, so the text vertex shader is shared.
+const TEXTURED_VERTEX_SHADER: &[u8] = include_bytes!("../spv/text.vert.spv");
+const TEXTURED_FRAGMENT_SHADER: &[u8] = include_bytes!("../spv/textured.frag.spv");
+
+/// How many textures one render target can hold at the same time.
+const MAX_TEXTURES: u32 = 256;
+
+/// # TextureHandle
+/// Refers to a texture uploaded to a render target.
+///
+/// Handles are created with [`RenderTarget::create_texture`](crate::RenderTarget::create_texture)
+/// and stay valid until they are passed to [`RenderTarget::free_texture`](crate::RenderTarget::free_texture).
+/// A handle is only meaningful for the render target that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextureHandle {
+    index: usize,
+    generation: u32,
+}
+
+/// A texture with the descriptor set its draws are bound with.
+struct BoundTexture {
+    texture: Texture,
+    descriptor_set: DescriptorSet,
+}
+
+struct Slot {
+    generation: u32,
+    texture: Option<BoundTexture>,
+}
+
+/// Draws textured triangles, multiplying the sampled colors with the vertex colors.
+///
+/// The pipeline owns the textures of a render target. Each gets its own descriptor set.
+/// The output is blended over the image by its alpha.
+pub(crate) struct TexturePipeline {
+    pipeline: Pipeline,
+    /// The uniform buffer of the stage, bound to binding 0.
+    uniform_buffer: ash::vk::Buffer,
+    layout: PipelineLayout,
+    descriptor_layout: DescriptorSetLayout,
+    descriptor_pool: DescriptorPool,
+    shaders: [Shader; 2],
+    slots: Vec<Slot>,
+    /// Textures that were freed, destroyed once the GPU has finished with them.
+    released: Vec<BoundTexture>,
+}
+
+impl TexturePipeline {
+    pub(crate) fn new(
+        device: &LogicalDevice,
+        render_pass: &RenderPass,
+        stage: &Stage,
+    ) -> VlResult<Self> {
+        let bindings = [
+            (0, DescriptorType::UNIFORM_BUFFER, ShaderStageFlags::VERTEX),
+            (1, DescriptorType::SAMPLED_IMAGE, ShaderStageFlags::FRAGMENT),
+            (2, DescriptorType::SAMPLER, ShaderStageFlags::FRAGMENT),
+        ]
+        .map(|(binding, ty, stage)| {
+            DescriptorSetLayoutBinding::builder()
+                .binding(binding)
+                .descriptor_type(ty)
+                .descriptor_count(1)
+                .stage_flags(stage)
+                .build()
+        });
+        let create_info = DescriptorSetLayoutCreateInfo::builder()
+            .bindings(&bindings)
+            .build();
+        let descriptor_layout = unsafe {
+            device
+                .inner
+                .create_descriptor_set_layout(&create_info, None)
+        }?;
+
+        let pool_sizes = bindings.map(|binding| {
+            DescriptorPoolSize::builder()
+                .ty(binding.descriptor_type)
+                .descriptor_count(MAX_TEXTURES)
+                .build()
+        });
+        // Sets are freed one by one with their textures.
+        let create_info = DescriptorPoolCreateInfo::builder()
+            .flags(DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+            .pool_sizes(&pool_sizes)
+            .max_sets(MAX_TEXTURES)
+            .build();
+        let descriptor_pool = unsafe { device.inner.create_descriptor_pool(&create_info, None) }?;
+
+        // The per-draw transform, as for the default pipeline.
+        let push_constant_ranges = [PushConstantRange::builder()
+            .stage_flags(ShaderStageFlags::VERTEX)
+            .offset(0)
+            .size(std::mem::size_of::<Mat4<f32>>() as u32)
+            .build()];
+        let layout_create_info = PipelineLayoutCreateInfo::builder()
+            .set_layouts(&[descriptor_layout])
+            .push_constant_ranges(&push_constant_ranges)
+            .build();
+        let layout = unsafe {
+            device
+                .inner
+                .create_pipeline_layout(&layout_create_info, None)
+        }?;
+
+        let shaders = [
+            device.create_shader_module(
+                Spirv::from_bytes(TEXTURED_VERTEX_SHADER),
+                ShaderKind::Vertex,
+            )?,
+            device.create_shader_module(
+                Spirv::from_bytes(TEXTURED_FRAGMENT_SHADER),
+                ShaderKind::Fragment,
+            )?,
+        ];
+        let pipeline = create_pipeline(device, render_pass, stage, &shaders, layout)?;
+
+        Ok(Self {
+            pipeline,
+            uniform_buffer: stage.buffer.buffer,
+            layout,
+            descriptor_layout,
+            descriptor_pool,
+            shaders,
+            slots: vec![],
+            released: vec![],
+        })
+    }
+
+    /// Creates the pipeline again for a new render pass or size. The textures are kept.
+    #[cfg(target_os = "windows")]
+    #[cfg(feature = "window")]
+    pub(crate) fn rebuild(
+        &mut self,
+        device: &LogicalDevice,
+        render_pass: &RenderPass,
+        stage: &Stage,
+    ) -> VlResult<()> {
+        let pipeline = create_pipeline(device, render_pass, stage, &self.shaders, self.layout)?;
+        device.destroy_pipeline(&std::mem::replace(&mut self.pipeline, pipeline));
+        Ok(())
+    }
+
+    /// Takes ownership of `texture` and returns the handle to draw it with.
+    /// The texture is destroyed if it cannot be added.
+    pub(crate) fn insert(
+        &mut self,
+        device: &LogicalDevice,
+        texture: Texture,
+    ) -> VlResult<TextureHandle> {
+        let live = self.slots.iter().filter(|s| s.texture.is_some()).count();
+        let bound = if (live + self.released.len()) as u32 >= MAX_TEXTURES {
+            Err(VlError::InvalidState(
+                "Too many textures are held by this render target.",
+            ))
+        } else {
+            self.allocate_descriptor_set(device, &texture)
+        };
+        let descriptor_set = match bound {
+            Ok(descriptor_set) => descriptor_set,
+            Err(e) => {
+                texture.destroy_with_device(device);
+                return Err(e);
+            }
+        };
+
+        let texture = Some(BoundTexture {
+            texture,
+            descriptor_set,
+        });
+        match self.slots.iter().position(|s| s.texture.is_none()) {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.generation += 1;
+                slot.texture = texture;
+                Ok(TextureHandle {
+                    index,
+                    generation: slot.generation,
+                })
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    texture,
+                });
+                Ok(TextureHandle {
+                    index: self.slots.len() - 1,
+                    generation: 0,
+                })
+            }
+        }
+    }
+
+    /// Returns how draws with the texture are bound.
+    pub(crate) fn binding(&self, handle: TextureHandle) -> VlResult<PipelineBinding> {
+        Ok(PipelineBinding {
+            pipeline: self.pipeline,
+            layout: self.layout,
+            descriptor_set: self.slot(handle)?.descriptor_set,
+        })
+    }
+
+    /// Frees a texture. Draws of it recorded in this frame still sample it,
+    /// so it is only destroyed by [`TexturePipeline::destroy_released`].
+    pub(crate) fn free(&mut self, handle: TextureHandle) -> VlResult<()> {
+        self.slot(handle)?;
+        let texture = self.slots[handle.index].texture.take().unwrap();
+        self.released.push(texture);
+        Ok(())
+    }
+
+    /// Destroys the freed textures. The GPU must have finished the frames that drew them.
+    pub(crate) fn destroy_released(&mut self, device: &LogicalDevice) {
+        for bound in self.released.drain(..) {
+            bound.texture.destroy_with_device(device);
+            unsafe {
+                // Only fails for pools without `FREE_DESCRIPTOR_SET`.
+                let _ = device
+                    .inner
+                    .free_descriptor_sets(self.descriptor_pool, &[bound.descriptor_set]);
+            }
+        }
+    }
+
+    fn slot(&self, handle: TextureHandle) -> VlResult<&BoundTexture> {
+        self.slots
+            .get(handle.index)
+            .filter(|s| s.generation == handle.generation)
+            .and_then(|s| s.texture.as_ref())
+            .ok_or(VlError::InvalidState("The texture handle has been freed."))
+    }
+
+    fn allocate_descriptor_set(
+        &self,
+        device: &LogicalDevice,
+        texture: &Texture,
+    ) -> VlResult<DescriptorSet> {
+        let alloc_info = DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(self.descriptor_pool)
+            .set_layouts(&[self.descriptor_layout])
+            .build();
+        let descriptor_set = unsafe { device.inner.allocate_descriptor_sets(&alloc_info) }?[0];
+
+        let buffer_info = [DescriptorBufferInfo::builder()
+            .buffer(self.uniform_buffer)
+            .offset(0)
+            .range(std::mem::size_of::<Mvp>() as u64)
+            .build()];
+        let image_info = [DescriptorImageInfo::builder()
+            .image_view(texture.view)
+            .image_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .build()];
+        let sampler_info = [DescriptorImageInfo::builder()
+            .sampler(texture.sampler)
+            .build()];
+        let writes = [
+            WriteDescriptorSet::builder()
+                .dst_set(descriptor_set)
+                .dst_binding(0)
+                .descriptor_type(DescriptorType::UNIFORM_BUFFER)
+                .buffer_info(&buffer_info)
+                .build(),
+            WriteDescriptorSet::builder()
+                .dst_set(descriptor_set)
+                .dst_binding(1)
+                .descriptor_type(DescriptorType::SAMPLED_IMAGE)
+                .image_info(&image_info)
+                .build(),
+            WriteDescriptorSet::builder()
+                .dst_set(descriptor_set)
+                .dst_binding(2)
+                .descriptor_type(DescriptorType::SAMPLER)
+                .image_info(&sampler_info)
+                .build(),
+        ];
+        unsafe {
+            device.inner.update_descriptor_sets(&writes, &[]);
+        }
+        Ok(descriptor_set)
+    }
+}
+
+fn create_pipeline(
+    device: &LogicalDevice,
+    render_pass: &RenderPass,
+    stage: &Stage,
+    shaders: &[Shader],
+    layout: PipelineLayout,
+) -> VlResult<Pipeline> {
+    let vertex_layout = VertexLayout::of::<TexturedVertex>();
+    let state = PipelineState {
+        mode: PolygonMode::FILL,
+        topology: PrimitiveTopology::TRIANGLE_LIST,
+        vertex_layout: &vertex_layout,
+        width: stage.width,
+        height: stage.height,
+        line_width: 1.0,
+        blend: BlendMode::Alpha,
+    };
+    render_pass
+        .create_graphics_pipeline(device, shaders, layout, &state)?
+        .pop()
+        .ok_or(VlError::InvalidState("No texture pipeline was created."))
+}
+
+impl Destroy for TexturePipeline {
+    fn destroy_with_instance(&self, _: &Instance) {}
+
+    fn destroy_with_device(&self, device: &LogicalDevice) {
+        let textures = self.slots.iter().filter_map(|s| s.texture.as_ref());
+        for bound in textures.chain(&self.released) {
+            bound.texture.destroy_with_device(device);
+        }
+        for shader in &self.shaders {
+            shader.destroy_with_device(device);
+        }
+        device.destroy_pipeline(&self.pipeline);
+        unsafe {
+            device.inner.destroy_pipeline_layout(self.layout, None);
+            device
+                .inner
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            device
+                .inner
+                .destroy_descriptor_set_layout(self.descriptor_layout, None);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use ash::vk::{SamplerCreateInfo, LOD_CLAMP_NONE, TRUE};
+
+use crate::{Instance, LogicalDevice, PhysicalDevice, VlResult};
+
+/// Indicates how texels are combined when a texture is magnified or minified
+///
+/// # Value Meaning
+/// * `Nearest` - The closest texel, for crisp pixel art.
+/// * `Linear` - A weighted average of the four closest texels.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FilterMode {
+    Nearest,
+    #[default]
+    Linear,
+}
+
+impl From<FilterMode> for ash::vk::Filter {
+    fn from(filter: FilterMode) -> Self {
+        match filter {
+            FilterMode::Nearest => ash::vk::Filter::NEAREST,
+            FilterMode::Linear => ash::vk::Filter::LINEAR,
+        }
+    }
+}
+
+/// Indicates how mip levels are combined when a texture is minified
+///
+/// # Value Meaning
+/// * `Nearest` - The closest mip level.
+/// * `Linear` - A weighted average of the two closest mip levels.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MipmapMode {
+    Nearest,
+    #[default]
+    Linear,
+}
+
+impl From<MipmapMode> for ash::vk::SamplerMipmapMode {
+    fn from(mode: MipmapMode) -> Self {
+        match mode {
+            MipmapMode::Nearest => ash::vk::SamplerMipmapMode::NEAREST,
+            MipmapMode::Linear => ash::vk::SamplerMipmapMode::LINEAR,
+        }
+    }
+}
+
+/// Indicates what is sampled outside of the texture coordinates 0.0 to 1.0
+///
+/// # Value Meaning
+/// * `Repeat` - The texture is tiled.
+/// * `MirroredRepeat` - The texture is tiled, mirrored at every other tile.
+/// * `ClampToEdge` - The texels at the edge extend to infinity.
+/// * `ClampToBorder` - The border color of the sampler.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AddressMode {
+    Repeat,
+    MirroredRepeat,
+    #[default]
+    ClampToEdge,
+    ClampToBorder,
+}
+
+impl From<AddressMode> for ash::vk::SamplerAddressMode {
+    fn from(mode: AddressMode) -> Self {
+        match mode {
+            AddressMode::Repeat => ash::vk::SamplerAddressMode::REPEAT,
+            AddressMode::MirroredRepeat => ash::vk::SamplerAddressMode::MIRRORED_REPEAT,
+            AddressMode::ClampToEdge => ash::vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            AddressMode::ClampToBorder => ash::vk::SamplerAddressMode::CLAMP_TO_BORDER,
+        }
+    }
+}
+
+/// Indicates the color sampled outside of a texture with [`AddressMode::ClampToBorder`]
+///
+/// # Value Meaning
+/// * `TransparentBlack` - All channels are 0.0.
+/// * `OpaqueBlack` - Black with alpha 1.0.
+/// * `OpaqueWhite` - All channels are 1.0.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BorderColor {
+    #[default]
+    TransparentBlack,
+    OpaqueBlack,
+    OpaqueWhite,
+}
+
+impl From<BorderColor> for ash::vk::BorderColor {
+    fn from(color: BorderColor) -> Self {
+        match color {
+            BorderColor::TransparentBlack => ash::vk::BorderColor::FLOAT_TRANSPARENT_BLACK,
+            BorderColor::OpaqueBlack => ash::vk::BorderColor::FLOAT_OPAQUE_BLACK,
+            BorderColor::OpaqueWhite => ash::vk::BorderColor::FLOAT_OPAQUE_WHITE,
+        }
+    }
+}
+
+/// # Sampler
+/// Specifies how a texture is read by the shaders.
+///
+/// The default filters linearly, between mip levels too, and clamps to the edges.
+/// A texture gets the mip levels its sampler can reach; see [`SamplerBuilder::lod_range`].
+///
+/// ## Example
+/// ```no_run
+/// use vulx::{AddressMode, FilterMode, RenderTarget, Sampler, TextureData};
+/// # fn load(target: &mut impl RenderTarget, data: &TextureData) -> vulx::VlResult<()> {
+/// let pixelated = Sampler::builder()
+///     .filter(FilterMode::Nearest)
+///     .address_mode(AddressMode::Repeat)
+///     .build();
+/// let texture = target.create_texture(data, &pixelated)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampler {
+    mag_filter: FilterMode,
+    min_filter: FilterMode,
+    mipmap_mode: MipmapMode,
+    address_mode_u: AddressMode,
+    address_mode_v: AddressMode,
+    anisotropy: Option<f32>,
+    border_color: BorderColor,
+    min_lod: f32,
+    max_lod: f32,
+}
+
+impl Sampler {
+    pub fn builder() -> SamplerBuilder {
+        SamplerBuilder::default()
+    }
+
+    /// How many mip levels a texture of the given size needs for this sampler.
+    pub(crate) fn mip_levels(&self, width: u32, height: u32) -> u32 {
+        let levels = 32 - width.max(height).max(1).leading_zeros();
+        (self.max_lod.max(0.0).ceil() as u32 + 1).min(levels)
+    }
+
+    /// Creates the Vulkan sampler. Anisotropy is clamped to what the device supports,
+    /// and left out if it is not supported at all.
+    pub(crate) fn create(
+        &self,
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device: &LogicalDevice,
+    ) -> VlResult<ash::vk::Sampler> {
+        let features = unsafe {
+            instance
+                .inner
+                .get_physical_device_features(physical_device.0)
+        };
+        let limit = unsafe {
+            instance
+                .inner
+                .get_physical_device_properties(physical_device.0)
+        }
+        .limits
+        .max_sampler_anisotropy;
+        let anisotropy = self
+            .anisotropy
+            .filter(|_| features.sampler_anisotropy == TRUE)
+            .map(|anisotropy| anisotropy.clamp(1.0, limit));
+
+        let create_info = SamplerCreateInfo::builder()
+            .mag_filter(self.mag_filter.into())
+            .min_filter(self.min_filter.into())
+            .mipmap_mode(self.mipmap_mode.into())
+            .address_mode_u(self.address_mode_u.into())
+            .address_mode_v(self.address_mode_v.into())
+            .address_mode_w(self.address_mode_u.into())
+            .anisotropy_enable(anisotropy.is_some())
+            .max_anisotropy(anisotropy.unwrap_or(1.0))
+            .border_color(self.border_color.into())
+            .min_lod(self.min_lod)
+            .max_lod(self.max_lod)
+            .build();
+        Ok(unsafe { device.inner.create_sampler(&create_info, None) }?)
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_mode: MipmapMode::Linear,
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            anisotropy: None,
+            border_color: BorderColor::TransparentBlack,
+            min_lod: 0.0,
+            max_lod: LOD_CLAMP_NONE,
+        }
+    }
+}
+
+/// # SamplerBuilder
+/// Builds a [`Sampler`]. Options that are not set keep the defaults of [`Sampler::default`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SamplerBuilder {
+    sampler: Sampler,
+}
+
+impl SamplerBuilder {
+    /// Sets both the magnification and the minification filter.
+    pub fn filter(mut self, filter: FilterMode) -> Self {
+        self.sampler.mag_filter = filter;
+        self.sampler.min_filter = filter;
+        self
+    }
+
+    /// The filter used when the texture is drawn larger than its size.
+    pub fn mag_filter(mut self, filter: FilterMode) -> Self {
+        self.sampler.mag_filter = filter;
+        self
+    }
+
+    /// The filter used when the texture is drawn smaller than its size.
+    pub fn min_filter(mut self, filter: FilterMode) -> Self {
+        self.sampler.min_filter = filter;
+        self
+    }
+
+    pub fn mipmap_mode(mut self, mode: MipmapMode) -> Self {
+        self.sampler.mipmap_mode = mode;
+        self
+    }
+
+    /// Sets the address mode of both texture coordinates.
+    pub fn address_mode(mut self, mode: AddressMode) -> Self {
+        self.sampler.address_mode_u = mode;
+        self.sampler.address_mode_v = mode;
+        self
+    }
+
+    /// The address mode of the horizontal texture coordinate.
+    pub fn address_mode_u(mut self, mode: AddressMode) -> Self {
+        self.sampler.address_mode_u = mode;
+        self
+    }
+
+    /// The address mode of the vertical texture coordinate.
+    pub fn address_mode_v(mut self, mode: AddressMode) -> Self {
+        self.sampler.address_mode_v = mode;
+        self
+    }
+
+    /// Enables anisotropic filtering with up to `max` samples, which keeps textures seen at
+    /// a steep angle sharp. Ignored if the device does not support it.
+    pub fn anisotropy(mut self, max: f32) -> Self {
+        self.sampler.anisotropy = Some(max);
+        self
+    }
+
+    pub fn border_color(mut self, color: BorderColor) -> Self {
+        self.sampler.border_color = color;
+        self
+    }
+
+    /// Restricts the mip levels that are sampled. Level 0 is the full size texture.
+    /// Textures only get the levels up to `max`, so `lod_range(0.0, 0.0)` disables mipmaps.
+    pub fn lod_range(mut self, min: f32, max: f32) -> Self {
+        self.sampler.min_lod = min;
+        self.sampler.max_lod = max.max(min);
+        self
+    }
+
+    pub fn build(self) -> Sampler {
+        self.sampler
+    }
+}
@@ -2,7 +2,14 @@
 
 use nalgebra::{Matrix4, Vector2, Vector3, Vector4};
 
-use crate::{geometry::Path, Image, Instance, LogicalDevice, Stage};
+use crate::{
+    brush::Brush,
+    geometry::{Bounds, InstanceData, Path, PathGeometry, Tessellation, Transform},
+    target::GeometryHandle,
+    text::{Font, GlyphAtlas, GlyphRun},
+    Image, Instance, LogicalDevice, Sampler, Stage, TextureData, TextureHandle, TexturedVertex,
+    VlError, VlResult,
+};
 
 pub type Vec2<T> = Vector2<T>;
 pub type Vec3<T> = Vector3<T>;
@@ -70,19 +77,6 @@ pub fn radians(r: f32) -> f32 {
     r * (PI / 180.0)
 }
 
-/// Represents a color
-///
-/// Initialization with ARGB allows you to create your own colors
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum Color {
-    /// Specified in RGB color space.
-    RGB(f64, f64, f64),
-    /// Specified in RGBA color space.
-    RGBA(f64, f64, f64, f64),
-    /// Specified in HSV color space.
-    HSV(f64, f64, f64),
-}
-
 /// A handle representing the physical device.
 #[derive(Clone, Copy)]
 pub struct PhysicalDevice(pub(crate) ash::vk::PhysicalDevice);
@@ -91,18 +85,172 @@ pub trait RenderTarget {
     fn begin(&mut self);
     fn fill(&mut self, path: &mut impl IntoPath);
     fn stroke(&mut self, path: &mut impl IntoPath, thickness: f64);
+    /// Fills the path with the current transform, painted with `brush` instead of its vertex colors.
+    ///
+    /// Returns an error if the path has no tessellation, such as a [`Mesh`](crate::geometry::Mesh).
+    fn fill_with(&mut self, path: &mut impl IntoPath, brush: &Brush) -> VlResult<()>;
+    /// Strokes the path with the current transform, painted with `brush` instead of its vertex colors.
+    ///
+    /// Returns an error if the path has no tessellation, such as a [`Mesh`](crate::geometry::Mesh).
+    fn stroke_with(
+        &mut self,
+        path: &mut impl IntoPath,
+        thickness: f64,
+        brush: &Brush,
+    ) -> VlResult<()>;
+    /// Draws the glyphs of `run` as textured quads from the distance fields in `atlas`, with the current transform.
+    /// Glyphs that are not in the atlas yet are rasterized into it.
+    ///
+    /// Unlike [`RenderTarget::fill`], the edges stay smooth at any scale and no outlines are tessellated.
+    /// The text is blended over what was drawn before it.
+    fn draw_glyphs(&mut self, atlas: &mut GlyphAtlas, run: &GlyphRun) -> VlResult<()>;
+    /// Uploads the pixels into a device-local texture through a staging buffer
+    /// and keeps it until it is freed. Waits until the copy has finished.
+    ///
+    /// The texture is read with `sampler`, and gets the mip levels the sampler can reach.
+    fn create_texture(&mut self, data: &TextureData, sampler: &Sampler) -> VlResult<TextureHandle>;
+    /// Frees a texture. Draws of it in the current frame still sample it;
+    /// it is destroyed once the GPU has finished with them.
+    fn free_texture(&mut self, texture: TextureHandle) -> VlResult<()>;
+    /// Draws triangles that sample `texture` at the UVs of their vertices, multiplied by the vertex colors,
+    /// with the current transform. Triangles are clockwise, as for paths.
+    ///
+    /// The triangles are blended over what was drawn before them.
+    fn draw_textured(
+        &mut self,
+        texture: TextureHandle,
+        vertices: &[TexturedVertex],
+        indices: &[u32],
+    ) -> VlResult<()>;
     fn end(&mut self);
 
+    /// Draws the whole texture stretched over `destination`.
+    fn draw_image(&mut self, texture: TextureHandle, destination: Bounds) -> VlResult<()> {
+        let source = Bounds {
+            min: Vec2::new(0.0, 0.0),
+            max: Vec2::new(1.0, 1.0),
+        };
+        self.draw_sprite(texture, source, destination, Vec4::new(1.0, 1.0, 1.0, 1.0))
+    }
+
+    /// Draws the part `source` of the texture over `destination`, multiplied by `tint`.
+    /// `source` is in texture coordinates, from 0.0 to 1.0.
+    ///
+    /// Sprites of one texture drawn one after the other share a draw call.
+    fn draw_sprite(
+        &mut self,
+        texture: TextureHandle,
+        source: Bounds,
+        destination: Bounds,
+        tint: impl Into<Vec4<f32>>,
+    ) -> VlResult<()> {
+        let tint = tint.into();
+        let (s, d) = (source, destination);
+        let corner = |x: f32, y: f32, u: f32, v: f32| {
+            TexturedVertex::new(Vec2::new(x, y), Vec2::new(u, v), tint)
+        };
+        let vertices = [
+            corner(d.min.x, d.min.y, s.min.x, s.min.y),
+            corner(d.max.x, d.min.y, s.max.x, s.min.y),
+            corner(d.max.x, d.max.y, s.max.x, s.max.y),
+            corner(d.min.x, d.max.y, s.min.x, s.max.y),
+        ];
+        self.draw_textured(texture, &vertices, &[0, 1, 2, 0, 2, 3])
+    }
+
+    /// Fills the path with the texture, tinted by the colors of the path.
+    /// `uv` maps the coordinates of the path to texture coordinates.
+    ///
+    /// Returns an error if the path has no tessellation, such as a [`Mesh`](crate::geometry::Mesh).
+    fn fill_textured(
+        &mut self,
+        path: &mut impl IntoPath,
+        texture: TextureHandle,
+        uv: Transform,
+    ) -> VlResult<()> {
+        let Some(tessellation) = path.fill_tessellation() else {
+            return Err(VlError::InvalidState(
+                "Textures can only fill paths that are tessellated.",
+            ));
+        };
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        for buffer in &tessellation.index_buffers {
+            let base = vertices.len() as u32;
+            vertices.extend(buffer.data.iter().map(|vertex| {
+                let pos = Vec2::new(vertex.pos.x, vertex.pos.y);
+                TexturedVertex::new(pos, uv.apply(pos), vertex.color)
+            }));
+            indices.extend(buffer.indices.iter().map(|i| i + base));
+        }
+        self.draw_textured(texture, &vertices, &indices)
+    }
+
+    /// Fills a single line of `text` in `color`, with its baseline starting at `position`.
+    /// `size` is in pixels per em.
+    fn draw_text(
+        &mut self,
+        font: &Font,
+        size: f32,
+        position: Vec2<f32>,
+        color: impl Into<Vec4<f32>>,
+        text: &str,
+    ) {
+        let mut path = PathGeometry::new();
+        path.color(color);
+        path.fit_to_stage(self.stage());
+        font.text_outline(text, size, position, &mut path);
+        self.fill(&mut path);
+    }
+
+    /// Forgets the paths drawn in the current frame. Retained paths are kept.
     fn clear(&mut self);
 
     fn set_image(&mut self, image: Image);
 
+    /// Specifies the color the image is cleared to by the next [`RenderTarget::begin`].
+    fn set_clear_color(&mut self, color: impl Into<Vec4<f32>>);
+
     fn stage(&mut self) -> &mut Stage;
 
     fn logical_device(&self) -> &LogicalDevice;
     fn instance(&self) -> &Instance;
 }
 
+/// Draws that are packed into the per-frame buffers of a render target: transforms, retained paths and instances.
+///
+/// Kept apart from [`RenderTarget`] so that targets that only fill and stroke do not have to implement them.
+pub trait BatchedRenderTarget: RenderTarget {
+    /// Uploads the path once and draws it once at each of the transforms.
+    fn fill_transformed(&mut self, path: &mut impl IntoPath, transforms: &[Transform]);
+    /// Specifies the transform applied on the GPU to the paths filled or stroked after this call.
+    /// It is applied before the stage camera and projection, and kept until it is changed.
+    fn set_transform(&mut self, transform: Transform);
+    /// Uploads the path once and draws all instances with a single draw call, using the current transform.
+    ///
+    /// The instances are bound with `VertexInputRate::INSTANCE`; see [`InstanceData`] for how the default shaders use them.
+    fn fill_instanced(&mut self, path: &mut impl IntoPath, instances: &[InstanceData]);
+
+    /// Uploads the fill of the path and keeps it on the GPU until it is freed,
+    /// so that it can be drawn in any later frame without uploading it again.
+    fn upload(&mut self, path: &mut impl IntoPath) -> GeometryHandle;
+    /// Uploads the stroke of the path with the given thickness and keeps it until it is freed.
+    fn upload_stroke(&mut self, path: &mut impl IntoPath, thickness: f64) -> GeometryHandle;
+    /// Draws a retained path with the current transform.
+    fn draw(&mut self, handle: GeometryHandle) -> VlResult<()>;
+    /// Draws all instances of a retained path with a single draw call, using the current transform.
+    fn draw_instanced(
+        &mut self,
+        handle: GeometryHandle,
+        instances: &[InstanceData],
+    ) -> VlResult<()>;
+    /// Replaces the contents of a retained path. A stroke is regenerated with its original thickness.
+    /// Draws of this frame that have not been ended yet use the new contents.
+    fn update(&mut self, handle: GeometryHandle, path: &mut impl IntoPath) -> VlResult<()>;
+    /// Frees a retained path. Its buffers are destroyed once the GPU no longer uses them.
+    fn free(&mut self, handle: GeometryHandle) -> VlResult<()>;
+}
+
 pub trait IntoPath {
     fn into_path(
         &mut self,
@@ -110,6 +258,30 @@ pub trait IntoPath {
         phsyical_device: PhysicalDevice,
         device: &LogicalDevice,
     ) -> Path;
+
+    /// Generates and uploads the stroke of the outline with the given thickness.
+    fn into_stroke_path(
+        &mut self,
+        instance: &Instance,
+        phsyical_device: PhysicalDevice,
+        device: &LogicalDevice,
+        thickness: f64,
+    ) -> Path;
+
+    /// Generates the triangles of the fill without uploading them,
+    /// so that render targets can pack all fills of a frame into one buffer.
+    ///
+    /// Returns `None` if the path can only be uploaded with [`IntoPath::into_path`].
+    fn fill_tessellation(&mut self) -> Option<Tessellation> {
+        None
+    }
+
+    /// Generates the triangles of the stroke without uploading them.
+    ///
+    /// Returns `None` if the path can only be uploaded with [`IntoPath::into_stroke_path`].
+    fn stroke_tessellation(&mut self, _thickness: f64) -> Option<Tessellation> {
+        None
+    }
 }
 
 pub trait Destroy {
@@ -0,0 +1,238 @@
+use std::mem::offset_of;
+
+use ash::vk::{
+    Format, VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
+};
+
+use crate::{
+    geometry::{InstanceData, VertexData},
+    TexturedVertex,
+};
+
+/// Indicates the type of a vertex attribute as it is read by the vertex shader
+///
+/// # Value Meaning
+/// * `Float` - One 32-bit float, `float` in GLSL.
+/// * `Vec2` - Two 32-bit floats, `vec2` in GLSL.
+/// * `Vec3` - Three 32-bit floats, `vec3` in GLSL.
+/// * `Vec4` - Four 32-bit floats, `vec4` in GLSL.
+/// * `Int` - One 32-bit signed integer, `int` in GLSL.
+/// * `IVec2` - Two 32-bit signed integers, `ivec2` in GLSL.
+/// * `IVec3` - Three 32-bit signed integers, `ivec3` in GLSL.
+/// * `IVec4` - Four 32-bit signed integers, `ivec4` in GLSL.
+/// * `UInt` - One 32-bit unsigned integer, `uint` in GLSL.
+/// * `UVec2` - Two 32-bit unsigned integers, `uvec2` in GLSL.
+/// * `UVec3` - Three 32-bit unsigned integers, `uvec3` in GLSL.
+/// * `UVec4` - Four 32-bit unsigned integers, `uvec4` in GLSL.
+/// * `Unorm8x4` - Four bytes mapped to `0.0..=1.0`, read as `vec4`. Useful for packed colors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VertexFormat {
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+    Int,
+    IVec2,
+    IVec3,
+    IVec4,
+    UInt,
+    UVec2,
+    UVec3,
+    UVec4,
+    Unorm8x4,
+}
+
+impl From<VertexFormat> for Format {
+    fn from(format: VertexFormat) -> Self {
+        match format {
+            VertexFormat::Float => Format::R32_SFLOAT,
+            VertexFormat::Vec2 => Format::R32G32_SFLOAT,
+            VertexFormat::Vec3 => Format::R32G32B32_SFLOAT,
+            VertexFormat::Vec4 => Format::R32G32B32A32_SFLOAT,
+            VertexFormat::Int => Format::R32_SINT,
+            VertexFormat::IVec2 => Format::R32G32_SINT,
+            VertexFormat::IVec3 => Format::R32G32B32_SINT,
+            VertexFormat::IVec4 => Format::R32G32B32A32_SINT,
+            VertexFormat::UInt => Format::R32_UINT,
+            VertexFormat::UVec2 => Format::R32G32_UINT,
+            VertexFormat::UVec3 => Format::R32G32B32_UINT,
+            VertexFormat::UVec4 => Format::R32G32B32A32_UINT,
+            VertexFormat::Unorm8x4 => Format::R8G8B8A8_UNORM,
+        }
+    }
+}
+
+/// # VertexAttribute
+/// One input of the vertex shader, read from `offset` bytes into each vertex.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VertexAttribute {
+    /// The `layout(location = ...)` of the input in the vertex shader.
+    pub location: u32,
+    pub format: VertexFormat,
+    pub offset: u32,
+}
+
+impl VertexAttribute {
+    pub fn new(location: u32, format: VertexFormat, offset: usize) -> Self {
+        Self {
+            location,
+            format,
+            offset: offset as u32,
+        }
+    }
+}
+
+/// Describes how the vertex shader reads a vertex type from a vertex buffer.
+///
+/// Implement it for a `#[repr(C)]` struct and pass the type to [`PipelineBuilder::vertex`](crate::PipelineBuilder::vertex)
+/// or, for per-instance data, to [`PipelineBuilder::instance`](crate::PipelineBuilder::instance).
+///
+/// ## Example
+/// ```no_run
+/// use std::mem::offset_of;
+/// use vulx::{Vertex, VertexAttribute, VertexFormat};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// struct MeshVertex {
+///     position: [f32; 3],
+///     normal: [f32; 3],
+///     uv: [f32; 2],
+/// }
+///
+/// impl Vertex for MeshVertex {
+///     fn attributes() -> Vec<VertexAttribute> {
+///         vec![
+///             VertexAttribute::new(0, VertexFormat::Vec3, offset_of!(MeshVertex, position)),
+///             VertexAttribute::new(1, VertexFormat::Vec3, offset_of!(MeshVertex, normal)),
+///             VertexAttribute::new(2, VertexFormat::Vec2, offset_of!(MeshVertex, uv)),
+///         ]
+///     }
+/// }
+/// ```
+pub trait Vertex: Copy {
+    fn attributes() -> Vec<VertexAttribute>;
+
+    /// The distance in bytes between two vertices in the buffer.
+    fn stride() -> u32 {
+        std::mem::size_of::<Self>() as u32
+    }
+}
+
+/// The vertex of paths drawn with the default shaders.
+impl Vertex for VertexData {
+    fn attributes() -> Vec<VertexAttribute> {
+        vec![
+            VertexAttribute::new(0, VertexFormat::Vec2, offset_of!(VertexData, pos)),
+            VertexAttribute::new(1, VertexFormat::Vec4, offset_of!(VertexData, color)),
+        ]
+    }
+}
+
+/// The per-instance data read by the default shaders. The transform takes one location per column.
+impl Vertex for InstanceData {
+    fn attributes() -> Vec<VertexAttribute> {
+        let transform = offset_of!(InstanceData, transform);
+        let column = 4 * std::mem::size_of::<f32>();
+        let mut attributes: Vec<_> = (0..4)
+            .map(|i| {
+                VertexAttribute::new(2 + i, VertexFormat::Vec4, transform + i as usize * column)
+            })
+            .collect();
+        attributes.push(VertexAttribute::new(
+            6,
+            VertexFormat::Vec4,
+            offset_of!(InstanceData, color),
+        ));
+        attributes.push(VertexAttribute::new(
+            7,
+            VertexFormat::Vec4,
+            offset_of!(InstanceData, custom),
+        ));
+        attributes
+    }
+}
+
+/// The vertex of textured triangles and glyph quads.
+impl Vertex for TexturedVertex {
+    fn attributes() -> Vec<VertexAttribute> {
+        vec![
+            VertexAttribute::new(0, VertexFormat::Vec2, offset_of!(TexturedVertex, pos)),
+            VertexAttribute::new(1, VertexFormat::Vec2, offset_of!(TexturedVertex, uv)),
+            VertexAttribute::new(2, VertexFormat::Vec4, offset_of!(TexturedVertex, color)),
+        ]
+    }
+}
+
+/// # VertexLayout
+/// The stride and attributes of a [`Vertex`] type, as the pipeline consumes them.
+///
+/// Vertices are read from binding 0. If there are instance attributes,
+/// the per-instance data is read from binding 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VertexLayout {
+    pub stride: u32,
+    pub attributes: Vec<VertexAttribute>,
+    pub instance_stride: u32,
+    pub instance_attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    /// The layout of `V` without per-instance data.
+    pub fn of<V: Vertex>() -> Self {
+        Self {
+            stride: V::stride(),
+            attributes: V::attributes(),
+            instance_stride: 0,
+            instance_attributes: vec![],
+        }
+    }
+
+    /// Reads `I` once per instance. Its locations must not overlap the ones of the vertex.
+    pub fn with_instance<I: Vertex>(mut self) -> Self {
+        self.instance_stride = I::stride();
+        self.instance_attributes = I::attributes();
+        self
+    }
+
+    pub(crate) fn binding_descriptions(&self) -> Vec<VertexInputBindingDescription> {
+        let mut bindings = vec![VertexInputBindingDescription::builder()
+            .binding(0)
+            .stride(self.stride)
+            .input_rate(VertexInputRate::VERTEX)
+            .build()];
+        if !self.instance_attributes.is_empty() {
+            bindings.push(
+                VertexInputBindingDescription::builder()
+                    .binding(1)
+                    .stride(self.instance_stride)
+                    .input_rate(VertexInputRate::INSTANCE)
+                    .build(),
+            );
+        }
+        bindings
+    }
+
+    pub(crate) fn attribute_descriptions(&self) -> Vec<VertexInputAttributeDescription> {
+        let describe = |binding: u32, a: &VertexAttribute| {
+            VertexInputAttributeDescription::builder()
+                .binding(binding)
+                .location(a.location)
+                .format(a.format.into())
+                .offset(a.offset)
+                .build()
+        };
+        self.attributes
+            .iter()
+            .map(|a| describe(0, a))
+            .chain(self.instance_attributes.iter().map(|a| describe(1, a)))
+            .collect()
+    }
+}
+
+impl Default for VertexLayout {
+    /// The layout of the default shaders.
+    fn default() -> Self {
+        Self::of::<VertexData>().with_instance::<InstanceData>()
+    }
+}